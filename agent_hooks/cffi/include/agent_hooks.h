@@ -0,0 +1,78 @@
+#ifndef AGENT_HOOKS_H
+#define AGENT_HOOKS_H
+
+/* This file is generated by cbindgen. Do not edit by hand. */
+
+#include <stdbool.h>
+
+// Result of checking for Rust allow/expect attributes.
+typedef enum AgentHooksRustAllowCheck {
+  // No problematic attributes found.
+  AGENT_HOOKS_RUST_ALLOW_CHECK_OK = 0,
+  // Found #[allow(...)] attribute.
+  AGENT_HOOKS_RUST_ALLOW_CHECK_HAS_ALLOW = 1,
+  // Found #[expect(...)] attribute.
+  AGENT_HOOKS_RUST_ALLOW_CHECK_HAS_EXPECT = 2,
+  // Found both #[allow(...)] and #[expect(...)] attributes.
+  AGENT_HOOKS_RUST_ALLOW_CHECK_HAS_BOTH = 3,
+  // Found only `#[cfg_attr(..., allow(...))]` attributes.
+  AGENT_HOOKS_RUST_ALLOW_CHECK_HAS_CFG_ATTR_ALLOW = 4,
+  // Found only `#[cfg_attr(..., expect(...))]` attributes.
+  AGENT_HOOKS_RUST_ALLOW_CHECK_HAS_CFG_ATTR_EXPECT = 5,
+  // Found only allow/expect attributes inside `quote! { ... }`.
+  AGENT_HOOKS_RUST_ALLOW_CHECK_HAS_MACRO_GENERATED = 6,
+} AgentHooksRustAllowCheck;
+
+#ifdef __cplusplus
+extern "C" {
+#endif // __cplusplus
+
+// Check if a command contains an rm (or equivalent) command.
+//
+// Returns `true` if the command should be blocked.
+//
+// # Safety
+//
+// `cmd` must be `NULL` or point to a valid NUL-terminated string.
+bool agent_hooks_is_rm_command(const char *cmd);
+
+// Check if a command is a destructive find command.
+//
+// Returns a static description of the destructive pattern, or `NULL` if the
+// command is safe. The returned string must not be freed.
+//
+// # Safety
+//
+// `cmd` must be `NULL` or point to a valid NUL-terminated string.
+const char *agent_hooks_check_destructive_find(const char *cmd);
+
+// Check if a command redirects output to `nul`.
+//
+// This check is Windows-only. On non-Windows platforms it always returns `false`.
+//
+// # Safety
+//
+// `cmd` must be `NULL` or point to a valid NUL-terminated string.
+bool agent_hooks_has_nul_redirect(const char *cmd);
+
+// Check if a file path is a Rust file.
+//
+// # Safety
+//
+// `file_path` must be `NULL` or point to a valid NUL-terminated string.
+bool agent_hooks_is_rust_file(const char *file_path);
+
+// Check if content contains #[allow(...)] or #[expect(...)] attributes.
+//
+// This function ignores attributes in comments and string literals.
+//
+// # Safety
+//
+// `content` must be `NULL` or point to a valid NUL-terminated string.
+enum AgentHooksRustAllowCheck agent_hooks_check_rust_allow_attributes(const char *content);
+
+#ifdef __cplusplus
+}  // extern "C"
+#endif  // __cplusplus
+
+#endif  /* AGENT_HOOKS_H */
@@ -0,0 +1,151 @@
+//! C FFI bindings for `agent_hooks`.
+//!
+//! These bindings expose the core check functions over a plain C ABI so that
+//! runtimes with a C FFI (Ruby, Julia, R, ...) can call them directly. The
+//! matching header is generated with `cbindgen` into `include/agent_hooks.h`.
+//!
+//! Strings passed in must be valid, NUL-terminated C strings. Invalid UTF-8 is
+//! replaced lossily before checking. `NULL` inputs are treated as empty.
+
+use agent_hooks::{
+    RustAllowCheckResult, check_destructive_find, check_rust_allow_attributes, has_nul_redirect,
+    is_rm_command, is_rust_file,
+};
+use std::borrow::Cow;
+use std::collections::HashMap;
+use std::ffi::{CStr, CString, c_char};
+use std::sync::{LazyLock, Mutex, PoisonError};
+
+/// Descriptions returned by `check_destructive_find`, interned as C strings.
+///
+/// Entries are never removed and a `CString`'s heap buffer does not move when
+/// the map grows, so pointers handed out stay valid for the process lifetime.
+static DESCRIPTIONS: LazyLock<Mutex<HashMap<&'static str, CString>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+fn intern_description(description: &'static str) -> *const c_char {
+    DESCRIPTIONS
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+        .entry(description)
+        .or_insert_with(|| CString::new(description).unwrap_or_default())
+        .as_ptr()
+}
+
+/// Convert a C string pointer into a Rust string.
+///
+/// # Safety
+///
+/// `ptr` must be `NULL` or point to a valid NUL-terminated string.
+unsafe fn to_str<'a>(ptr: *const c_char) -> Cow<'a, str> {
+    if ptr.is_null() {
+        return Cow::Borrowed("");
+    }
+    // SAFETY: the caller guarantees `ptr` is a valid NUL-terminated string.
+    unsafe { CStr::from_ptr(ptr) }.to_string_lossy()
+}
+
+/// Check if a command contains an rm (or equivalent) command.
+///
+/// Returns `true` if the command should be blocked.
+///
+/// # Safety
+///
+/// `cmd` must be `NULL` or point to a valid NUL-terminated string.
+#[unsafe(no_mangle)]
+#[must_use]
+pub unsafe extern "C" fn agent_hooks_is_rm_command(cmd: *const c_char) -> bool {
+    // SAFETY: forwarded from the caller's contract.
+    is_rm_command(&unsafe { to_str(cmd) })
+}
+
+/// Check if a command is a destructive find command.
+///
+/// Returns a static description of the destructive pattern, or `NULL` if the
+/// command is safe. The returned string must not be freed.
+///
+/// # Safety
+///
+/// `cmd` must be `NULL` or point to a valid NUL-terminated string.
+#[unsafe(no_mangle)]
+#[must_use]
+pub unsafe extern "C" fn agent_hooks_check_destructive_find(cmd: *const c_char) -> *const c_char {
+    // SAFETY: forwarded from the caller's contract.
+    check_destructive_find(&unsafe { to_str(cmd) }).map_or(std::ptr::null(), intern_description)
+}
+
+/// Check if a command redirects output to `nul`.
+///
+/// This check is Windows-only. On non-Windows platforms it always returns `false`.
+///
+/// # Safety
+///
+/// `cmd` must be `NULL` or point to a valid NUL-terminated string.
+#[unsafe(no_mangle)]
+#[must_use]
+pub unsafe extern "C" fn agent_hooks_has_nul_redirect(cmd: *const c_char) -> bool {
+    // SAFETY: forwarded from the caller's contract.
+    has_nul_redirect(&unsafe { to_str(cmd) })
+}
+
+/// Check if a file path is a Rust file.
+///
+/// # Safety
+///
+/// `file_path` must be `NULL` or point to a valid NUL-terminated string.
+#[unsafe(no_mangle)]
+#[must_use]
+pub unsafe extern "C" fn agent_hooks_is_rust_file(file_path: *const c_char) -> bool {
+    // SAFETY: forwarded from the caller's contract.
+    is_rust_file(&unsafe { to_str(file_path) })
+}
+
+/// Result of checking for Rust allow/expect attributes.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AgentHooksRustAllowCheck {
+    /// No problematic attributes found.
+    Ok = 0,
+    /// Found #[allow(...)] attribute.
+    HasAllow = 1,
+    /// Found #[expect(...)] attribute.
+    HasExpect = 2,
+    /// Found both #[allow(...)] and #[expect(...)] attributes.
+    HasBoth = 3,
+    /// Found only `#[cfg_attr(..., allow(...))]` attributes.
+    HasCfgAttrAllow = 4,
+    /// Found only `#[cfg_attr(..., expect(...))]` attributes.
+    HasCfgAttrExpect = 5,
+    /// Found only allow/expect attributes inside `quote! { ... }`.
+    HasMacroGenerated = 6,
+}
+
+impl From<RustAllowCheckResult> for AgentHooksRustAllowCheck {
+    fn from(result: RustAllowCheckResult) -> Self {
+        match result {
+            RustAllowCheckResult::Ok => Self::Ok,
+            RustAllowCheckResult::HasAllow => Self::HasAllow,
+            RustAllowCheckResult::HasExpect => Self::HasExpect,
+            RustAllowCheckResult::HasBoth => Self::HasBoth,
+            RustAllowCheckResult::HasCfgAttrAllow => Self::HasCfgAttrAllow,
+            RustAllowCheckResult::HasCfgAttrExpect => Self::HasCfgAttrExpect,
+            RustAllowCheckResult::HasMacroGenerated => Self::HasMacroGenerated,
+        }
+    }
+}
+
+/// Check if content contains #[allow(...)] or #[expect(...)] attributes.
+///
+/// This function ignores attributes in comments and string literals.
+///
+/// # Safety
+///
+/// `content` must be `NULL` or point to a valid NUL-terminated string.
+#[unsafe(no_mangle)]
+#[must_use]
+pub unsafe extern "C" fn agent_hooks_check_rust_allow_attributes(
+    content: *const c_char,
+) -> AgentHooksRustAllowCheck {
+    // SAFETY: forwarded from the caller's contract.
+    check_rust_allow_attributes(&unsafe { to_str(content) }).into()
+}
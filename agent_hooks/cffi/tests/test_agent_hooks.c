@@ -0,0 +1,60 @@
+/*
+ * Minimal C smoke test for the agent_hooks FFI bindings.
+ *
+ * Build and run (Linux):
+ *   cargo build -p agent_hooks_cffi --release
+ *   cc agent_hooks/cffi/tests/test_agent_hooks.c -Iagent_hooks/cffi/include \
+ *     target/release/libagent_hooks_cffi.a -lpthread -ldl -lm -o target/test_agent_hooks
+ *   ./target/test_agent_hooks
+ */
+
+#include <stdio.h>
+#include <string.h>
+
+#include "agent_hooks.h"
+
+static int failures = 0;
+
+#define CHECK(cond)                                                   \
+    do {                                                              \
+        if (!(cond)) {                                                \
+            fprintf(stderr, "%s:%d: check failed: %s\n", __FILE__,    \
+                    __LINE__, #cond);                                 \
+            failures++;                                               \
+        }                                                             \
+    } while (0)
+
+int main(void) {
+    CHECK(agent_hooks_is_rm_command("rm -rf /tmp/test"));
+    CHECK(!agent_hooks_is_rm_command("trash file.txt"));
+    CHECK(!agent_hooks_is_rm_command(NULL));
+
+    const char *description =
+        agent_hooks_check_destructive_find("find . -name '*.tmp' -delete");
+    CHECK(description != NULL);
+    if (description != NULL) {
+        CHECK(strcmp(description, "find with -delete option") == 0);
+    }
+    CHECK(agent_hooks_check_destructive_find("find . -name '*.rs'") == NULL);
+
+    CHECK(!agent_hooks_has_nul_redirect("echo test > /dev/null"));
+
+    CHECK(agent_hooks_is_rust_file("src/main.rs"));
+    CHECK(!agent_hooks_is_rust_file("README.md"));
+
+    CHECK(agent_hooks_check_rust_allow_attributes("#[allow(dead_code)]") ==
+          AGENT_HOOKS_RUST_ALLOW_CHECK_HAS_ALLOW);
+    CHECK(agent_hooks_check_rust_allow_attributes("// #[allow(dead_code)]") ==
+          AGENT_HOOKS_RUST_ALLOW_CHECK_OK);
+    CHECK(agent_hooks_check_rust_allow_attributes(
+              "#[cfg_attr(test, allow(dead_code))]") ==
+          AGENT_HOOKS_RUST_ALLOW_CHECK_HAS_CFG_ATTR_ALLOW);
+
+    if (failures != 0) {
+        fprintf(stderr, "%d check(s) failed\n", failures);
+        return 1;
+    }
+
+    printf("all checks passed\n");
+    return 0;
+}
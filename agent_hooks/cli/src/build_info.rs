@@ -0,0 +1,26 @@
+use serde::Serialize;
+
+/// Build metadata printed by `--build-info`, populated by `build.rs`.
+#[derive(Debug, Serialize)]
+pub struct BuildInfo {
+    pub version: &'static str,
+    pub target: &'static str,
+    pub rustc: &'static str,
+    pub features: Vec<&'static str>,
+    pub build_date: &'static str,
+}
+
+impl BuildInfo {
+    pub fn current() -> Self {
+        Self {
+            version: env!("CARGO_PKG_VERSION"),
+            target: env!("AGENT_HOOKS_BUILD_TARGET"),
+            rustc: env!("AGENT_HOOKS_BUILD_RUSTC"),
+            features: env!("AGENT_HOOKS_BUILD_FEATURES")
+                .split(',')
+                .filter(|feature| !feature.is_empty())
+                .collect(),
+            build_date: env!("AGENT_HOOKS_BUILD_DATE"),
+        }
+    }
+}
@@ -1,19 +1,45 @@
 use agent_hooks::{
-    PackageManagerCheckResult, RustAllowCheckResult, check_dangerous_path_command,
-    check_destructive_find, check_package_manager, check_rust_allow_attributes, has_nul_redirect,
-    is_rm_command, is_rust_file,
+    CrontabAction, DangerousPathCheck, NodeVersionCheckResult, PackageManager,
+    PackageManagerCheckResult, build_rust_allow_denial_reason, check_aws_destructive,
+    check_base64_encoded_command, check_cargo_publish_with, check_crontab_modification,
+    check_dangerous_path_command, check_database_dangerous, check_destructive_find,
+    check_disk_destructive, check_docker_destructive, check_firewall_modification,
+    check_git_config_global, check_hosts_file_modification, check_kubectl_delete,
+    check_mount_operation, check_node_version_compatibility, check_npm_publish_with,
+    check_package_manager, check_process_kill_dangerous, check_rust_allow_attributes,
+    check_sensitive_file_read, check_sudo_escalation, check_systemctl_dangerous_with,
+    find_lock_files_with_paths, format_package_manager_mismatch, has_nul_redirect, is_cron_file,
+    is_pnpm_workspace_root, is_rust_file,
 };
 use serde::de::DeserializeOwned;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
+use std::borrow::Cow;
+use std::collections::HashMap;
 use std::path::{Path, PathBuf};
 
-use crate::CliOptions;
+use crate::{CliOptions, DecisionMode, Provider};
 
 #[derive(Debug, Deserialize)]
 struct ClaudeHookInput {
     tool_name: Option<String>,
     tool_input: Option<ClaudeToolInput>,
+    /// Environment variables the command runs with, used to resolve `$VAR` references.
+    env: Option<HashMap<String, String>>,
+}
+
+impl ClaudeHookInput {
+    /// The Bash command with `$VAR`/`${VAR}` references from `env` expanded.
+    fn bash_command(&self) -> Option<Cow<'_, str>> {
+        let cmd = self.tool_input.as_ref()?.command.as_deref()?;
+        if cmd.is_empty() {
+            return None;
+        }
+        Some(match &self.env {
+            Some(env) if !env.is_empty() => Cow::Owned(expand_env_vars(cmd, env)),
+            _ => Cow::Borrowed(cmd),
+        })
+    }
 }
 
 #[derive(Debug, Deserialize)]
@@ -22,8 +48,24 @@ struct ClaudeToolInput {
     new_string: Option<String>,
     content: Option<String>,
     file_path: Option<String>,
+    /// `MultiEdit` replacements.
+    edits: Option<Vec<ClaudeMultiEditEntry>>,
+    /// `NotebookEdit` target.
+    notebook_path: Option<String>,
+    /// `NotebookEdit` cell content.
+    new_source: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ClaudeMultiEditEntry {
+    /// Target of this replacement when a batch spans several files.
+    file_path: Option<String>,
+    new_string: Option<String>,
 }
 
+/// Claude tools that write files.
+const CLAUDE_EDIT_TOOLS: &[&str] = &["Edit", "Write", "MultiEdit", "NotebookEdit"];
+
 #[derive(Debug, Serialize)]
 #[serde(rename_all = "camelCase")]
 struct ClaudeHookOutput {
@@ -70,6 +112,69 @@ enum ClaudePermissionDecision {
     Deny,
 }
 
+/// A Claude hook decision, converted into the matching [`ClaudeHookOutput`] shape.
+#[derive(Debug)]
+enum HookDecision {
+    /// `PermissionRequest` denial reported through `decision.behavior`/`decision.message`.
+    DenyBash(String),
+    /// Asks the user to confirm, reported through `permissionDecision`.
+    Ask {
+        event: ClaudeHookEventName,
+        reason: String,
+    },
+    /// `PreToolUse` denial reported through `permissionDecision`.
+    DenyEdit(String),
+}
+
+impl HookDecision {
+    fn deny_bash(message: impl Into<String>) -> Self {
+        Self::DenyBash(message.into())
+    }
+
+    const fn ask(event: ClaudeHookEventName, reason: String) -> Self {
+        Self::Ask { event, reason }
+    }
+
+    const fn deny_edit(reason: String) -> Self {
+        Self::DenyEdit(reason)
+    }
+
+    fn into_json(self) -> Option<String> {
+        serialize_json(&ClaudeHookOutput::from(self))
+    }
+}
+
+impl From<HookDecision> for ClaudeHookOutput {
+    fn from(decision: HookDecision) -> Self {
+        let hook_specific_output = match decision {
+            HookDecision::DenyBash(message) => ClaudeHookSpecificOutput {
+                hook_event_name: ClaudeHookEventName::PermissionRequest,
+                decision: Some(ClaudeDecision {
+                    behavior: ClaudeDecisionBehavior::Deny,
+                    message,
+                }),
+                permission_decision: None,
+                permission_decision_reason: None,
+            },
+            HookDecision::Ask { event, reason } => ClaudeHookSpecificOutput {
+                hook_event_name: event,
+                decision: None,
+                permission_decision: Some(ClaudePermissionDecision::Ask),
+                permission_decision_reason: Some(reason),
+            },
+            HookDecision::DenyEdit(reason) => ClaudeHookSpecificOutput {
+                hook_event_name: ClaudeHookEventName::PreToolUse,
+                decision: None,
+                permission_decision: Some(ClaudePermissionDecision::Deny),
+                permission_decision_reason: Some(reason),
+            },
+        };
+        Self {
+            hook_specific_output,
+        }
+    }
+}
+
 #[derive(Debug, Deserialize)]
 #[serde(rename_all = "camelCase")]
 struct CopilotHookInput {
@@ -168,6 +273,17 @@ struct BashChecks {
     dangerous_paths: bool,
 }
 
+/// Which bash check produced a denial, so callers can apply a per-check decision mode.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum BashCheck {
+    Rm,
+    DangerousPaths,
+    Other,
+}
+
+const RM_FORBIDDEN_REASON: &str =
+    "rm is forbidden. Use trash command to delete files. Example: trash <path...>";
+
 pub fn handle_claude_permission_request(options: &CliOptions, input: &str) -> Option<String> {
     if !options.bash_permissions.block_rm && options.bash_permissions.dangerous_paths.is_none() {
         return None;
@@ -178,56 +294,55 @@ pub fn handle_claude_permission_request(options: &CliOptions, input: &str) -> Op
         return None;
     }
 
-    let cmd = data
-        .tool_input
-        .as_ref()
-        .and_then(|tool_input| tool_input.command.as_deref())
-        .unwrap_or_default();
-    if cmd.is_empty() {
-        return None;
-    }
+    let cmd = data.bash_command()?;
+    claude_permission_decision(cmd.as_ref(), options)?.into_json()
+}
 
-    if options.bash_permissions.block_rm && is_rm_command(cmd) {
-        return serialize_json(&ClaudeHookOutput {
-            hook_specific_output: ClaudeHookSpecificOutput {
-                hook_event_name: ClaudeHookEventName::PermissionRequest,
-                decision: Some(ClaudeDecision {
-                    behavior: ClaudeDecisionBehavior::Deny,
-                    message:
-                        "rm is forbidden. Use trash command to delete files. Example: trash <path...>"
-                            .to_string(),
-                }),
-                permission_decision: None,
-                permission_decision_reason: None,
-            },
-        });
+/// Deny `rm` and ask before dangerous path operations, also in a base64 payload that
+/// is decoded and executed.
+fn claude_permission_decision(cmd: &str, options: &CliOptions) -> Option<HookDecision> {
+    if options.bash_permissions.blocks_rm(cmd) {
+        return Some(HookDecision::deny_bash(RM_FORBIDDEN_REASON));
     }
 
-    let paths = parse_dangerous_paths(options.bash_permissions.dangerous_paths.as_deref());
+    let paths = parse_list(options.bash_permissions.dangerous_paths.as_deref());
     if !paths.is_empty()
         && let Some(check) = check_dangerous_path_command(cmd, &paths)
     {
-        return serialize_json(&ClaudeHookOutput {
-            hook_specific_output: ClaudeHookSpecificOutput {
-                hook_event_name: ClaudeHookEventName::PermissionRequest,
-                decision: None,
-                permission_decision: Some(ClaudePermissionDecision::Ask),
-                permission_decision_reason: Some(format!(
-                    "Dangerous path operation detected: {} command targeting protected path '{}'. Please confirm this operation.",
-                    check.command_type, check.matched_path
-                )),
-            },
-        });
+        return Some(HookDecision::ask(
+            ClaudeHookEventName::PermissionRequest,
+            format!(
+                "Dangerous path operation detected: {}. Please confirm this operation.",
+                describe_dangerous_path(&check)
+            ),
+        ));
     }
 
-    None
+    let decoded = check_base64_encoded_command(cmd)?.decoded_command?;
+    Some(match claude_permission_decision(&decoded, options)? {
+        HookDecision::DenyBash(message) => HookDecision::deny_bash(format!(
+            "Base64-encoded command `{decoded}` was denied: {message}"
+        )),
+        HookDecision::Ask { event, reason } => HookDecision::ask(
+            event,
+            format!("Base64-encoded command `{decoded}` needs confirmation: {reason}"),
+        ),
+        decision @ HookDecision::DenyEdit(_) => decision,
+    })
 }
 
 pub fn handle_claude_pre_tool_use(options: &CliOptions, input: &str) -> Option<String> {
     if !options.rust_edits.deny_rust_allow
-        && !options.bash_safety.check_package_manager
+        && !options.node_tooling.check_package_manager
+        && !options.node_tooling.check_node_version
         && !options.bash_safety.deny_destructive_find
         && !options.bash_safety.deny_nul_redirect
+        && !options.system_safety.any()
+        && !options.cloud_safety.any()
+        && !options.repo_safety.any()
+        && !options.privilege_safety.any()
+        && !options.persistence_safety.any()
+        && !options.disk_safety.any()
     {
         return None;
     }
@@ -235,47 +350,54 @@ pub fn handle_claude_pre_tool_use(options: &CliOptions, input: &str) -> Option<S
     let data: ClaudeHookInput = parse_json(input)?;
     let tool_name = data.tool_name.as_deref().unwrap_or_default();
 
-    if matches_tool_name(tool_name, &["Bash"]) {
-        let cmd = data
-            .tool_input
-            .as_ref()
-            .and_then(|tool_input| tool_input.command.as_deref())
-            .unwrap_or_default();
-
-        if !cmd.is_empty()
-            && let Some(reason) = evaluate_bash_denial(
-                cmd,
-                None,
-                options,
-                BashChecks {
-                    block_rm: false,
-                    dangerous_paths: false,
-                },
-            )
-        {
-            return serialize_json(&build_claude_pre_tool_use_denial(reason));
-        }
+    if matches_tool_name(tool_name, &["Bash"])
+        && let Some(cmd) = data.bash_command()
+        && let Some(reason) = evaluate_bash_denial(
+            &cmd,
+            None,
+            options,
+            BashChecks {
+                block_rm: false,
+                dangerous_paths: false,
+            },
+        )
+    {
+        return HookDecision::deny_edit(reason).into_json();
     }
 
-    if !options.rust_edits.deny_rust_allow || !matches_tool_name(tool_name, &["Edit", "Write"]) {
+    if !matches_tool_name(tool_name, CLAUDE_EDIT_TOOLS) {
         return None;
     }
 
-    let edit = data
+    let targets = data
         .tool_input
         .as_ref()
-        .and_then(extract_claude_rust_edit)?;
-    let reason = build_rust_allow_denial(options, &edit.content)?;
-    serialize_json(&build_claude_pre_tool_use_denial(reason))
+        .map(extract_claude_edit_targets)
+        .unwrap_or_default();
+    let reason = targets.iter().find_map(|&(file_path, content)| {
+        build_file_write_denial(file_path, options).or_else(|| {
+            (options.rust_edits.deny_rust_allow && is_rust_file(file_path) && !content.is_empty())
+                .then(|| build_rust_allow_denial(options, content))
+                .flatten()
+        })
+    })?;
+    HookDecision::deny_edit(reason).into_json()
 }
 
 pub fn handle_copilot_pre_tool_use(options: &CliOptions, input: &str) -> Option<String> {
     if !options.bash_permissions.block_rm
         && options.bash_permissions.dangerous_paths.is_none()
         && !options.rust_edits.deny_rust_allow
-        && !options.bash_safety.check_package_manager
+        && !options.node_tooling.check_package_manager
+        && !options.node_tooling.check_node_version
         && !options.bash_safety.deny_destructive_find
         && !options.bash_safety.deny_nul_redirect
+        && !options.system_safety.any()
+        && !options.cloud_safety.any()
+        && !options.repo_safety.any()
+        && !options.privilege_safety.any()
+        && !options.persistence_safety.any()
+        && !options.disk_safety.any()
     {
         return None;
     }
@@ -290,7 +412,7 @@ pub fn handle_copilot_pre_tool_use(options: &CliOptions, input: &str) -> Option<
     if matches_tool_name(&data.tool_name, &["bash", "shell"]) {
         let cmd = tool_args.command.trim();
         if !cmd.is_empty()
-            && let Some(reason) = evaluate_bash_denial(
+            && let Some(output) = find_bash_denial(
                 cmd,
                 Some(data.cwd.trim()),
                 options,
@@ -298,36 +420,73 @@ pub fn handle_copilot_pre_tool_use(options: &CliOptions, input: &str) -> Option<
                     block_rm: true,
                     dangerous_paths: true,
                 },
+                &mut |check, reason| copilot_decision(copilot_bash_mode(check, options), reason),
             )
         {
-            return serialize_json(&CopilotHookOutput {
-                permission_decision: "deny",
-                permission_decision_reason: reason,
-            });
+            return Some(output);
         }
     }
 
-    if !options.rust_edits.deny_rust_allow
-        || !matches_tool_name(&data.tool_name, &["edit", "write", "create"])
+    if !matches_tool_name(&data.tool_name, &["edit", "write", "create"]) {
+        return None;
+    }
+
+    if let Some(reason) = build_file_write_denial(tool_args.file_path.trim(), options)
+        && let Some(output) = copilot_decision(options.decision_modes.global(), reason)
     {
+        return Some(output);
+    }
+
+    if !options.rust_edits.deny_rust_allow {
         return None;
     }
 
     let edit = extract_copilot_rust_edit(&tool_args)?;
     let reason = build_rust_allow_denial(options, &edit.content)?;
+    copilot_decision(options.decision_modes.rust_allow(), reason)
+}
+
+/// Report a failed Copilot check in `mode`. `Warn` only logs the reason, so the caller
+/// moves on to the remaining checks and the tool call is otherwise let through.
+fn copilot_decision(mode: DecisionMode, reason: String) -> Option<String> {
+    let permission_decision = match mode {
+        DecisionMode::Deny => "deny",
+        DecisionMode::Ask => "ask",
+        DecisionMode::Warn => {
+            eprintln!("agent_hooks: warning: {reason}");
+            return None;
+        }
+    };
     serialize_json(&CopilotHookOutput {
-        permission_decision: "deny",
+        permission_decision,
         permission_decision_reason: reason,
     })
 }
 
+/// The mode for a Copilot bash denial, using the override of the check that fired.
+fn copilot_bash_mode(check: BashCheck, options: &CliOptions) -> DecisionMode {
+    let modes = &options.decision_modes;
+    match check {
+        BashCheck::Rm => modes.rm(),
+        BashCheck::DangerousPaths => modes.dangerous_paths(),
+        BashCheck::Other => modes.global(),
+    }
+}
+
 pub fn handle_codex_pre_tool_use(options: &CliOptions, input: &str) -> Option<String> {
     if !options.bash_permissions.block_rm
         && options.bash_permissions.dangerous_paths.is_none()
         && !options.rust_edits.deny_rust_allow
-        && !options.bash_safety.check_package_manager
+        && !options.node_tooling.check_package_manager
+        && !options.node_tooling.check_node_version
         && !options.bash_safety.deny_destructive_find
         && !options.bash_safety.deny_nul_redirect
+        && !options.system_safety.any()
+        && !options.cloud_safety.any()
+        && !options.repo_safety.any()
+        && !options.privilege_safety.any()
+        && !options.persistence_safety.any()
+        && !options.disk_safety.any()
     {
         return None;
     }
@@ -356,6 +515,20 @@ pub fn handle_codex_pre_tool_use(options: &CliOptions, input: &str) -> Option<St
         });
     }
 
+    if matches_tool_name(tool_name, &["apply_patch", "Edit", "Write"])
+        && let Some(patch) = extract_codex_command(&data.tool_input)
+        && let Some(reason) = extract_apply_patch_paths(patch)
+            .find_map(|file_path| build_file_write_denial(file_path, options))
+    {
+        return serialize_json(&CodexPreToolUseOutput {
+            hook_specific_output: CodexPreToolUseHookSpecificOutput {
+                hook_event_name: CodexHookEventName::PreToolUse,
+                permission_decision: CodexPermissionDecision::Deny,
+                permission_decision_reason: reason,
+            },
+        });
+    }
+
     if !options.rust_edits.deny_rust_allow {
         return None;
     }
@@ -410,25 +583,71 @@ fn evaluate_bash_denial(
     options: &CliOptions,
     checks: BashChecks,
 ) -> Option<String> {
-    if checks.block_rm && options.bash_permissions.block_rm && is_rm_command(cmd) {
-        return Some(
-            "rm is forbidden. Use trash command to delete files. Example: trash <path...>"
-                .to_string(),
-        );
+    find_bash_denial(cmd, cwd, options, checks, &mut |_, reason| Some(reason))
+}
+
+/// Run the bash checks in order and pass each failure to `decide`, stopping at the
+/// first one it turns into an output. Returning `None` from `decide` (e.g. for a
+/// warning) moves on to the remaining checks.
+fn find_bash_denial(
+    cmd: &str,
+    cwd: Option<&str>,
+    options: &CliOptions,
+    checks: BashChecks,
+    decide: &mut dyn FnMut(BashCheck, String) -> Option<String>,
+) -> Option<String> {
+    let steps: [(BashCheck, &dyn Fn() -> Option<String>); 7] = [
+        (BashCheck::Rm, &|| {
+            (checks.block_rm && options.bash_permissions.blocks_rm(cmd))
+                .then(|| RM_FORBIDDEN_REASON.to_string())
+        }),
+        (BashCheck::DangerousPaths, &|| {
+            checks
+                .dangerous_paths
+                .then(|| build_dangerous_path_denial(cmd, options))
+                .flatten()
+        }),
+        (BashCheck::Other, &|| build_bash_safety_denial(cmd, options)),
+        (BashCheck::Other, &|| build_system_denial(cmd, options)),
+        (BashCheck::Other, &|| {
+            build_cloud_denial(cmd, options).or_else(|| build_host_denial(cmd, options))
+        }),
+        (BashCheck::Other, &|| build_repo_denial(cmd, options)),
+        (BashCheck::Other, &|| {
+            build_node_tooling_denial(cmd, cwd, options)
+        }),
+    ];
+    if let Some(output) = steps
+        .iter()
+        .filter_map(|(check, step)| Some((*check, step()?)))
+        .find_map(|(check, reason)| decide(check, reason))
+    {
+        return Some(output);
     }
 
-    if checks.dangerous_paths {
-        let paths = parse_dangerous_paths(options.bash_permissions.dangerous_paths.as_deref());
-        if !paths.is_empty()
-            && let Some(check) = check_dangerous_path_command(cmd, &paths)
-        {
-            return Some(format!(
-                "Dangerous path operation detected: {} command targeting protected path '{}'. Please avoid this operation.",
-                check.command_type, check.matched_path
-            ));
-        }
+    // Apply the same checks to a base64 payload that is decoded and executed
+    let decoded = check_base64_encoded_command(cmd)?.decoded_command?;
+    find_bash_denial(&decoded, cwd, options, checks, &mut |check, reason| {
+        decide(
+            check,
+            format!("Base64-encoded command `{decoded}` was denied: {reason}"),
+        )
+    })
+}
+
+fn build_dangerous_path_denial(cmd: &str, options: &CliOptions) -> Option<String> {
+    let paths = parse_list(options.bash_permissions.dangerous_paths.as_deref());
+    if paths.is_empty() {
+        return None;
     }
+    let check = check_dangerous_path_command(cmd, &paths)?;
+    Some(format!(
+        "Dangerous path operation detected: {}. Please avoid this operation.",
+        describe_dangerous_path(&check)
+    ))
+}
 
+fn build_bash_safety_denial(cmd: &str, options: &CliOptions) -> Option<String> {
     if options.bash_safety.deny_nul_redirect && has_nul_redirect(cmd) {
         return Some(
             "Use /dev/null instead of nul. On Windows bash, '> nul' creates an undeletable file."
@@ -444,12 +663,158 @@ fn evaluate_bash_denial(
         ));
     }
 
-    if options.bash_safety.check_package_manager
+    None
+}
+
+fn build_repo_denial(cmd: &str, options: &CliOptions) -> Option<String> {
+    if options.repo_safety.deny_git_config_global && check_git_config_global(cmd) {
+        return Some(
+            "Global git config modification detected. Changing global or system git config affects every repository on this machine; use `git config --local` instead."
+                .to_string(),
+        );
+    }
+
+    if options.repo_safety.deny_publish {
+        let allow_dry_run = options.repo_safety.allow_publish_dry_run;
+        if check_npm_publish_with(cmd, allow_dry_run)
+            || check_cargo_publish_with(cmd, allow_dry_run)
+        {
+            return Some(
+                "Package publishing detected. Publishing to a registry is irreversible; ask the user to publish manually."
+                    .to_string(),
+            );
+        }
+    }
+
+    None
+}
+
+fn build_node_tooling_denial(cmd: &str, cwd: Option<&str>, options: &CliOptions) -> Option<String> {
+    if options.node_tooling.check_package_manager
         && let Some(reason) = build_package_manager_mismatch(cmd, cwd)
     {
         return Some(reason);
     }
 
+    if options.node_tooling.check_node_version {
+        return build_node_version_mismatch(cmd, cwd);
+    }
+
+    None
+}
+
+/// What a dangerous path operation does, e.g. "mv command moving protected path '~/.ssh' away".
+fn describe_dangerous_path(check: &DangerousPathCheck) -> String {
+    match &check.mv_direction {
+        Some(direction) if direction.from.is_some() => format!(
+            "mv command moving protected path '{}' away",
+            check.matched_path
+        ),
+        Some(_) => format!(
+            "mv command overwriting protected path '{}'",
+            check.matched_path
+        ),
+        None => format!(
+            "{} command targeting protected path '{}'",
+            check.command_type, check.matched_path
+        ),
+    }
+}
+
+/// Denials for commands that can break the host (process kills, firewall, services, Docker).
+fn build_system_denial(cmd: &str, options: &CliOptions) -> Option<String> {
+    if options.system_safety.deny_critical_kill
+        && let Some(kill) = check_process_kill_dangerous(cmd)
+    {
+        return Some(format!(
+            "Critical process kill detected: {} targeting '{}'. Stopping this process may break the host.",
+            kill.command, kill.process_name
+        ));
+    }
+
+    if options.system_safety.deny_firewall_modification
+        && let Some(firewall) = check_firewall_modification(cmd)
+    {
+        return Some(format!(
+            "Firewall modification detected: {} ({} severity). This may expose the host or lock out remote access.",
+            firewall.command, firewall.severity
+        ));
+    }
+
+    if options.disk_safety.deny_disk_destructive
+        && let Some(disk) = check_disk_destructive(cmd)
+    {
+        let target = disk
+            .target
+            .map(|target| format!(" targeting '{target}'"))
+            .unwrap_or_default();
+        return Some(format!(
+            "Disk-destructive command detected: {}{target}. Formatting or wiping a disk irreversibly destroys its data; ask the user to run this manually.",
+            disk.command
+        ));
+    }
+
+    let services = parse_list(options.system_safety.dangerous_services.as_deref());
+    if !services.is_empty()
+        && let Some(systemctl) = check_systemctl_dangerous_with(cmd, &services)
+    {
+        return Some(format!(
+            "Critical service operation detected: systemctl {} {}. This may break the host or cut off remote access.",
+            systemctl.action, systemctl.service
+        ));
+    }
+
+    if options.system_safety.check_docker_destructive
+        && let Some(docker) = check_docker_destructive(cmd)
+    {
+        let force = if docker.has_force_flag {
+            " with --force"
+        } else {
+            ""
+        };
+        return Some(format!(
+            "Destructive Docker command detected: docker {}{force}. This may delete images, containers, or volumes, or stop running services.",
+            docker.subcommand
+        ));
+    }
+
+    None
+}
+
+/// Denials for commands that destroy remote infrastructure or data (Kubernetes, AWS, SQL).
+fn build_cloud_denial(cmd: &str, options: &CliOptions) -> Option<String> {
+    if options.cloud_safety.check_kubectl_delete
+        && let Some(kubectl) = check_kubectl_delete(cmd)
+    {
+        let target = match (kubectl.resource_type, kubectl.has_all_flag) {
+            (Some(resource), true) => format!("{resource} with --all"),
+            (Some(resource), false) => resource,
+            (None, true) => "all resources".to_string(),
+            (None, false) => "every manifest in the current directory".to_string(),
+        };
+        return Some(format!(
+            "Dangerous kubectl delete detected: deleting {target}. This may be irreversible."
+        ));
+    }
+
+    if options.cloud_safety.check_aws_destructive
+        && let Some(aws) = check_aws_destructive(cmd)
+    {
+        return Some(format!(
+            "Destructive AWS command detected: aws {} {}. This may irreversibly delete cloud resources or data.",
+            aws.service, aws.operation
+        ));
+    }
+
+    if options.cloud_safety.check_database_dangerous
+        && let Some(database) = check_database_dangerous(cmd)
+    {
+        return Some(format!(
+            "Destructive SQL detected: {} via {}. This may irreversibly delete data.",
+            database.sql_operation, database.db_tool
+        ));
+    }
+
     None
 }
 
@@ -459,78 +824,110 @@ fn build_package_manager_mismatch(cmd: &str, cwd: Option<&str>) -> Option<String
         PackageManagerCheckResult::Mismatch {
             command_pm,
             expected_pm,
+        } => {
+            let lock_file = find_lock_files_with_paths(Path::new(&start_dir))
+                .into_iter()
+                .find_map(|(pm, path)| (pm == expected_pm).then_some(path));
+            let mut reason =
+                format_package_manager_mismatch(command_pm, expected_pm, lock_file.as_deref());
+            if expected_pm == PackageManager::Pnpm
+                && Path::new(&start_dir)
+                    .ancestors()
+                    .any(is_pnpm_workspace_root)
+            {
+                reason.push_str(
+                    " This is a pnpm workspace; run `pnpm add` from the workspace root or the correct package directory.",
+                );
+            }
+            Some(reason)
+        }
+        _ => None,
+    }
+}
+
+fn build_node_version_mismatch(cmd: &str, cwd: Option<&str>) -> Option<String> {
+    let start_dir = parse_start_dir(cwd.unwrap_or_default());
+    match check_node_version_compatibility(cmd, Path::new(&start_dir)) {
+        NodeVersionCheckResult::Incompatible {
+            required,
+            source,
+            current,
         } => Some(format!(
-            "Package manager mismatch: This project uses {} (detected {}), but you are trying to use {}. Please use {} instead.",
-            expected_pm.name(),
-            expected_pm.lock_files()[0],
-            command_pm.name(),
-            expected_pm.name()
+            "Node version mismatch: This project requires Node {required} (from {source}), but the current Node version is {current}. Switch to a matching Node version (e.g. `nvm use`) before running package manager commands."
         )),
         _ => None,
     }
 }
 
 fn build_rust_allow_denial(options: &CliOptions, content: &str) -> Option<String> {
-    let check_result = check_rust_allow_attributes(content);
-    let base_message = if options.rust_edits.expect {
-        match check_result {
-            RustAllowCheckResult::HasAllow | RustAllowCheckResult::HasBoth => Some(
-                "Adding #[allow(...)] or #![allow(...)] attributes is not permitted. Use #[expect(...)] instead, which will warn when the lint is no longer triggered.",
-            ),
-            _ => None,
-        }
-    } else {
-        match check_result {
-            RustAllowCheckResult::Ok => None,
-            RustAllowCheckResult::HasBoth => Some(
-                "Adding #[allow(...)] or #[expect(...)] attributes is not permitted. Fix the underlying issue instead of suppressing the warning.",
-            ),
-            RustAllowCheckResult::HasAllow => Some(
-                "Adding #[allow(...)] or #![allow(...)] attributes is not permitted. Fix the underlying issue instead of suppressing the warning.",
-            ),
-            RustAllowCheckResult::HasExpect => Some(
-                "Adding #[expect(...)] or #![expect(...)] attributes is not permitted. Fix the underlying issue instead of suppressing the warning.",
-            ),
-        }
-    }?;
+    build_rust_allow_denial_reason(
+        check_rust_allow_attributes(content),
+        options.rust_edits.expect,
+        options.rust_edits.additional_context.as_deref(),
+    )
+}
+
+const HOSTS_MODIFICATION_REASON: &str = "Hosts or DNS configuration modification detected. Changing /etc/hosts or resolver settings can redirect domains for the whole machine; ask the user to make this change manually.";
 
-    let mut result = base_message.to_string();
-    if let Some(extra_context) = options.rust_edits.additional_context.as_deref() {
-        result.push(' ');
-        result.push_str(extra_context);
+/// Denials for edits to files that take effect outside the session (cron, hosts, DNS).
+fn build_file_write_denial(file_path: &str, options: &CliOptions) -> Option<String> {
+    if file_path.is_empty() {
+        return None;
     }
-    Some(result)
-}
 
-const fn build_claude_pre_tool_use_denial(reason: String) -> ClaudeHookOutput {
-    ClaudeHookOutput {
-        hook_specific_output: ClaudeHookSpecificOutput {
-            hook_event_name: ClaudeHookEventName::PreToolUse,
-            decision: None,
-            permission_decision: Some(ClaudePermissionDecision::Deny),
-            permission_decision_reason: Some(reason),
-        },
+    if options.persistence_safety.deny_crontab_modification && is_cron_file(file_path) {
+        return Some(format!(
+            "Cron file modification detected: '{file_path}'. Cron jobs run persistently outside this session; ask the user to edit it manually."
+        ));
     }
-}
 
-fn extract_claude_rust_edit(tool_input: &ClaudeToolInput) -> Option<RustEdit> {
-    let file_path = tool_input.file_path.as_deref().unwrap_or_default();
-    if file_path.is_empty() || !is_rust_file(file_path) {
-        return None;
+    if options.persistence_safety.deny_hosts_modification
+        && check_hosts_file_modification("", Some(file_path))
+    {
+        return Some(HOSTS_MODIFICATION_REASON.to_string());
     }
 
-    let content = tool_input
-        .new_string
+    None
+}
+
+/// The `(file_path, new content)` pairs a Claude edit tool writes.
+///
+/// `Edit`, `Write` and `NotebookEdit` write one file; each `MultiEdit` entry is its own
+/// pair, targeting the tool-level `file_path` unless the entry names another file.
+fn extract_claude_edit_targets(tool_input: &ClaudeToolInput) -> Vec<(&str, &str)> {
+    let default_path = tool_input
+        .file_path
         .as_deref()
-        .or(tool_input.content.as_deref())
+        .or(tool_input.notebook_path.as_deref())
         .unwrap_or_default();
-    if content.is_empty() {
-        return None;
-    }
 
-    Some(RustEdit {
-        content: content.to_string(),
-    })
+    let targets: Vec<(&str, &str)> = tool_input.edits.as_ref().map_or_else(
+        || {
+            let content = tool_input
+                .new_string
+                .as_deref()
+                .or(tool_input.content.as_deref())
+                .or(tool_input.new_source.as_deref())
+                .unwrap_or_default();
+            vec![(default_path, content)]
+        },
+        |edits| {
+            edits
+                .iter()
+                .map(|edit| {
+                    (
+                        edit.file_path.as_deref().unwrap_or(default_path),
+                        edit.new_string.as_deref().unwrap_or_default(),
+                    )
+                })
+                .collect()
+        },
+    );
+
+    targets
+        .into_iter()
+        .filter(|(file_path, _)| !file_path.is_empty())
+        .collect()
 }
 
 fn extract_copilot_rust_edit(tool_args: &CopilotToolArgs) -> Option<RustEdit> {
@@ -570,6 +967,17 @@ fn extract_codex_rust_edit(tool_name: &str, tool_input: &Value) -> Option<RustEd
     Some(RustEdit { content })
 }
 
+/// Paths of the files that an `apply_patch` patch adds, updates, moves, or deletes.
+fn extract_apply_patch_paths(patch: &str) -> impl Iterator<Item = &str> {
+    patch.lines().filter_map(|line| {
+        line.strip_prefix("*** Add File: ")
+            .or_else(|| line.strip_prefix("*** Update File: "))
+            .or_else(|| line.strip_prefix("*** Move to: "))
+            .or_else(|| line.strip_prefix("*** Delete File: "))
+            .map(str::trim)
+    })
+}
+
 fn extract_apply_patch_rust_additions(patch: &str) -> Option<String> {
     let mut current_is_rust = false;
     let mut additions = Vec::new();
@@ -601,8 +1009,8 @@ fn extract_apply_patch_rust_additions(patch: &str) -> Option<String> {
     }
 }
 
-fn parse_dangerous_paths(paths: Option<&str>) -> Vec<&str> {
-    paths
+fn parse_list(values: Option<&str>) -> Vec<&str> {
+    values
         .into_iter()
         .flat_map(|value| value.split(','))
         .map(str::trim)
@@ -618,16 +1026,218 @@ fn parse_start_dir(cwd: &str) -> PathBuf {
     std::env::current_dir().unwrap_or_default()
 }
 
+/// Expand `$VAR` and `${VAR}` references that are defined in `env`.
+///
+/// References inside single quotes and undefined variables are left as-is.
+fn expand_env_vars(cmd: &str, env: &HashMap<String, String>) -> String {
+    let mut expanded = String::with_capacity(cmd.len());
+    let mut in_single_quotes = false;
+    let mut in_double_quotes = false;
+    let mut rest = cmd;
+
+    while let Some(ch) = rest.chars().next() {
+        rest = &rest[ch.len_utf8()..];
+        match ch {
+            '\'' if !in_double_quotes => in_single_quotes = !in_single_quotes,
+            '"' if !in_single_quotes => in_double_quotes = !in_double_quotes,
+            '$' if !in_single_quotes => {
+                let (name, reference_len) = env_var_reference(rest);
+                if let Some(value) = env.get(name) {
+                    expanded.push_str(value);
+                    rest = &rest[reference_len..];
+                    continue;
+                }
+            }
+            _ => {}
+        }
+        expanded.push(ch);
+    }
+
+    expanded
+}
+
+/// Split the variable reference after a `$` into its name and length in bytes,
+/// e.g. `{HOME}/x` gives `("HOME", 6)` and `HOME/x` gives `("HOME", 4)`.
+fn env_var_reference(rest: &str) -> (&str, usize) {
+    if let Some(braced) = rest.strip_prefix('{') {
+        return braced
+            .find('}')
+            .map_or(("", 0), |end| (&braced[..end], end + 2));
+    }
+    let end = rest
+        .find(|c: char| !(c.is_ascii_alphanumeric() || c == '_'))
+        .unwrap_or(rest.len());
+    (&rest[..end], end)
+}
+
 fn matches_tool_name(tool_name: &str, candidates: &[&str]) -> bool {
     candidates
         .iter()
         .any(|candidate| tool_name.eq_ignore_ascii_case(candidate))
 }
 
+/// Parse hook input, warning on stderr when it is valid JSON that does not have the
+/// expected shape, since the hook would otherwise silently let the tool call through.
 fn parse_json<T: DeserializeOwned>(input: &str) -> Option<T> {
-    serde_json::from_str(input).ok()
+    let value: Value = serde_json::from_str(input).ok()?;
+    T::deserialize(&value)
+        .inspect_err(|err| eprintln!("{}", unprocessable_input_warning(&value, err)))
+        .ok()
+}
+
+/// Describe hook input that is valid JSON but cannot be processed, naming the raw tool.
+pub fn unprocessable_input_warning(value: &Value, err: &serde_json::Error) -> String {
+    let tool_name = value
+        .get("tool_name")
+        .or_else(|| value.get("toolName"))
+        .map_or_else(|| "<missing>".to_string(), ToString::to_string);
+    format!("agent_hooks: skipping checks for tool {tool_name}: unexpected hook input: {err}")
+}
+
+/// Why hook input was rejected before any check could run.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum HookInputError {
+    /// The input is not valid JSON.
+    InvalidJson(String),
+    /// The input is valid JSON but not an object.
+    NotAnObject,
+    /// A field every hook input must carry is absent or empty.
+    MissingField(&'static str),
+}
+
+impl HookInputError {
+    /// The JSON error response printed to stdout so the agent can show it.
+    pub fn to_json(&self) -> String {
+        #[derive(Serialize)]
+        struct ErrorOutput<'a> {
+            error: ErrorBody<'a>,
+        }
+
+        #[derive(Serialize)]
+        struct ErrorBody<'a> {
+            kind: &'static str,
+            #[serde(skip_serializing_if = "Option::is_none")]
+            field: Option<&'static str>,
+            message: &'a str,
+        }
+
+        let (kind, field) = match self {
+            Self::InvalidJson(_) => ("invalid_json", None),
+            Self::NotAnObject => ("not_an_object", None),
+            Self::MissingField(field) => ("missing_field", Some(*field)),
+        };
+        let message = self.to_string();
+        serialize_json(&ErrorOutput {
+            error: ErrorBody {
+                kind,
+                field,
+                message: &message,
+            },
+        })
+        .unwrap_or_default()
+    }
+}
+
+impl std::fmt::Display for HookInputError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::InvalidJson(err) => write!(f, "hook input is not valid JSON: {err}"),
+            Self::NotAnObject => f.write_str("hook input must be a JSON object"),
+            Self::MissingField(field) => {
+                write!(f, "hook input is missing required field `{field}`")
+            }
+        }
+    }
+}
+
+impl std::error::Error for HookInputError {}
+
+/// Check that hook input carries the fields every handler relies on before running it.
+///
+/// Copilot sends `toolName`, while Claude and Codex send `tool_name`.
+pub fn validate_hook_input(provider: Provider, input: &str) -> Result<(), HookInputError> {
+    let value: Value =
+        serde_json::from_str(input).map_err(|err| HookInputError::InvalidJson(err.to_string()))?;
+    let object = value.as_object().ok_or(HookInputError::NotAnObject)?;
+    let tool_name_keys: &[&str] = match provider {
+        Provider::Copilot => &["toolName", "tool_name"],
+        Provider::Claude | Provider::Codex => &["tool_name"],
+    };
+    let has_tool_name = tool_name_keys.iter().any(|key| {
+        object
+            .get(*key)
+            .and_then(Value::as_str)
+            .is_some_and(|name| !name.is_empty())
+    });
+    if has_tool_name {
+        Ok(())
+    } else {
+        Err(HookInputError::MissingField(tool_name_keys[0]))
+    }
 }
 
 fn serialize_json<T: Serialize>(value: &T) -> Option<String> {
     serde_json::to_string(value).ok()
 }
+
+/// Denials for commands that escalate privileges, expose secrets, or persist on the host.
+fn build_host_denial(cmd: &str, options: &CliOptions) -> Option<String> {
+    if options.privilege_safety.deny_sudo_escalation
+        && let Some(escalation) = check_sudo_escalation(cmd)
+    {
+        return Some(format!(
+            "Root shell escalation detected: {}. Interactive root shells bypass per-command review; run the specific command with sudo instead.",
+            escalation.escalation_type
+        ));
+    }
+
+    if options.privilege_safety.deny_mount
+        && let Some(mount) = check_mount_operation(cmd)
+    {
+        let target = mount
+            .target
+            .map(|target| format!(" on '{target}'"))
+            .unwrap_or_default();
+        return Some(format!(
+            "Mount operation detected: {}{target} ({} severity). Mounting filesystems or images can expose the host or escape container boundaries.",
+            mount.command, mount.severity
+        ));
+    }
+
+    if options.privilege_safety.deny_sensitive_read
+        && let Some(read) = check_sensitive_file_read(cmd)
+    {
+        return Some(format!(
+            "Sensitive file read detected: {} '{}'. This file contains credentials or secrets that should not be printed.",
+            read.command, read.path
+        ));
+    }
+
+    if options.persistence_safety.deny_crontab_modification
+        && let Some(crontab) = check_crontab_modification(cmd)
+    {
+        match crontab.action {
+            CrontabAction::Remove => {
+                return Some(
+                    "Crontab removal detected: crontab -r irreversibly deletes every cron job."
+                        .to_string(),
+                );
+            }
+            CrontabAction::Edit => {
+                return Some(
+                    "Crontab modification detected. Cron jobs run persistently outside this session; ask the user to edit the crontab manually."
+                        .to_string(),
+                );
+            }
+            CrontabAction::List => {}
+        }
+    }
+
+    if options.persistence_safety.deny_hosts_modification
+        && check_hosts_file_modification(cmd, None)
+    {
+        return Some(HOSTS_MODIFICATION_REASON.to_string());
+    }
+
+    None
+}
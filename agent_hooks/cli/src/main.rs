@@ -1,13 +1,18 @@
+mod build_info;
 mod hooks;
+#[cfg(feature = "serve")]
+mod serve;
 #[cfg(test)]
 mod tests;
 
 use std::io::{self, Read};
 use std::process;
 
+use agent_hooks::{DEFAULT_CRITICAL_SERVICES, is_rm_command, is_rm_command_with_heredocs};
+use build_info::BuildInfo;
 use hooks::{
     handle_claude_permission_request, handle_claude_pre_tool_use, handle_codex_permission_request,
-    handle_codex_pre_tool_use, handle_copilot_pre_tool_use,
+    handle_codex_pre_tool_use, handle_copilot_pre_tool_use, validate_hook_input,
 };
 
 const USAGE: &str = "\
@@ -17,18 +22,47 @@ Usage:
   agent_hooks copilot pre-tool-use [flags]
   agent_hooks codex permission-request [flags]
   agent_hooks codex pre-tool-use [flags]
+  agent_hooks serve <provider> <event> [--port <port>] [--cache-ttl-ms <ms>] [--cache-size <n>] [flags]
+  agent_hooks --version
+  agent_hooks --build-info
 
 Flags:
+  --check-all
   --block-rm
+  --check-heredoc
   --dangerous-paths <paths>
   --deny-rust-allow
   --expect
   --additional-context <message>
   --check-package-manager
+  --check-node-version
   --deny-destructive-find
   --deny-nul-redirect
+  --deny-critical-kill
+  --deny-disk-destructive
+  --deny-firewall-modification
+  --dangerous-services <services>
+  --check-docker-destructive
+  --check-kubectl-delete
+  --check-aws-destructive
+  --check-database-dangerous
+  --deny-git-config-global
+  --deny-publish
+  --allow-publish-dry-run
+  --deny-sudo-escalation
+  --deny-mount
+  --deny-sensitive-read
+  --deny-crontab-modification
+  --deny-hosts-modification
+  --mode <deny|ask|warn>
+  --rm-mode <deny|ask|warn>
+  --dangerous-paths-mode <deny|ask|warn>
+  --rust-allow-mode <deny|ask|warn>
 ";
 
+/// Dangerous paths protected by `--check-all` when `--dangerous-paths` is not given.
+const DEFAULT_DANGEROUS_PATHS: &str = "/,~/,~/.ssh/,~/.gnupg/";
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 enum Provider {
     Claude,
@@ -67,22 +101,184 @@ impl Event {
 struct CliOptions {
     bash_permissions: BashPermissionOptions,
     bash_safety: BashSafetyOptions,
+    node_tooling: NodeToolingOptions,
+    system_safety: SystemSafetyOptions,
+    cloud_safety: CloudSafetyOptions,
+    repo_safety: RepoSafetyOptions,
+    privilege_safety: PrivilegeSafetyOptions,
+    persistence_safety: PersistenceSafetyOptions,
+    disk_safety: DiskSafetyOptions,
     rust_edits: RustEditOptions,
+    decision_modes: DecisionModeOptions,
+}
+
+impl CliOptions {
+    /// Enables every check supported by `provider`/`event`, used as the base for `--check-all`.
+    fn check_all(provider: Provider, event: Event) -> Self {
+        let bash_permissions = supports_bash_permissions(provider, event);
+        let pre_tool_use = supports_pre_tool_use_checks(provider, event);
+        Self {
+            bash_permissions: BashPermissionOptions {
+                block_rm: bash_permissions,
+                check_heredoc: false,
+                dangerous_paths: bash_permissions.then(|| DEFAULT_DANGEROUS_PATHS.to_string()),
+            },
+            bash_safety: BashSafetyOptions {
+                deny_destructive_find: pre_tool_use,
+                deny_nul_redirect: pre_tool_use,
+            },
+            node_tooling: NodeToolingOptions {
+                check_package_manager: pre_tool_use,
+                check_node_version: pre_tool_use,
+            },
+            system_safety: SystemSafetyOptions {
+                deny_critical_kill: pre_tool_use,
+                deny_firewall_modification: pre_tool_use,
+                dangerous_services: pre_tool_use.then(|| DEFAULT_CRITICAL_SERVICES.join(",")),
+                check_docker_destructive: pre_tool_use,
+            },
+            cloud_safety: CloudSafetyOptions {
+                check_kubectl_delete: pre_tool_use,
+                check_aws_destructive: pre_tool_use,
+                check_database_dangerous: pre_tool_use,
+            },
+            repo_safety: RepoSafetyOptions {
+                deny_git_config_global: pre_tool_use,
+                deny_publish: pre_tool_use,
+                allow_publish_dry_run: false,
+            },
+            privilege_safety: PrivilegeSafetyOptions {
+                deny_sudo_escalation: pre_tool_use,
+                deny_mount: pre_tool_use,
+                deny_sensitive_read: pre_tool_use,
+            },
+            persistence_safety: PersistenceSafetyOptions {
+                deny_crontab_modification: pre_tool_use,
+                deny_hosts_modification: pre_tool_use,
+            },
+            disk_safety: DiskSafetyOptions {
+                deny_disk_destructive: pre_tool_use,
+            },
+            rust_edits: RustEditOptions {
+                deny_rust_allow: pre_tool_use,
+                ..RustEditOptions::default()
+            },
+            decision_modes: DecisionModeOptions::default(),
+        }
+    }
 }
 
 #[derive(Debug, Default, Clone, PartialEq, Eq)]
 struct BashPermissionOptions {
     block_rm: bool,
+    /// Also check heredoc bodies that no shell runs.
+    check_heredoc: bool,
     dangerous_paths: Option<String>,
 }
 
+impl BashPermissionOptions {
+    /// Whether `--block-rm` rejects `cmd`.
+    fn blocks_rm(&self, cmd: &str) -> bool {
+        self.block_rm
+            && if self.check_heredoc {
+                is_rm_command_with_heredocs(cmd)
+            } else {
+                is_rm_command(cmd)
+            }
+    }
+}
+
 #[derive(Debug, Default, Clone, PartialEq, Eq)]
 struct BashSafetyOptions {
-    check_package_manager: bool,
     deny_destructive_find: bool,
     deny_nul_redirect: bool,
 }
 
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+struct NodeToolingOptions {
+    check_package_manager: bool,
+    check_node_version: bool,
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+struct SystemSafetyOptions {
+    deny_critical_kill: bool,
+    deny_firewall_modification: bool,
+    dangerous_services: Option<String>,
+    check_docker_destructive: bool,
+}
+
+impl SystemSafetyOptions {
+    const fn any(&self) -> bool {
+        self.deny_critical_kill
+            || self.deny_firewall_modification
+            || self.dangerous_services.is_some()
+            || self.check_docker_destructive
+    }
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+struct CloudSafetyOptions {
+    check_kubectl_delete: bool,
+    check_aws_destructive: bool,
+    check_database_dangerous: bool,
+}
+
+impl CloudSafetyOptions {
+    const fn any(&self) -> bool {
+        self.check_kubectl_delete || self.check_aws_destructive || self.check_database_dangerous
+    }
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+struct RepoSafetyOptions {
+    deny_git_config_global: bool,
+    deny_publish: bool,
+    allow_publish_dry_run: bool,
+}
+
+impl RepoSafetyOptions {
+    const fn any(&self) -> bool {
+        self.deny_git_config_global || self.deny_publish
+    }
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+struct PrivilegeSafetyOptions {
+    deny_sudo_escalation: bool,
+    deny_mount: bool,
+    deny_sensitive_read: bool,
+}
+
+impl PrivilegeSafetyOptions {
+    const fn any(&self) -> bool {
+        self.deny_sudo_escalation || self.deny_mount || self.deny_sensitive_read
+    }
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+struct PersistenceSafetyOptions {
+    deny_crontab_modification: bool,
+    deny_hosts_modification: bool,
+}
+
+impl PersistenceSafetyOptions {
+    const fn any(&self) -> bool {
+        self.deny_crontab_modification || self.deny_hosts_modification
+    }
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+struct DiskSafetyOptions {
+    deny_disk_destructive: bool,
+}
+
+impl DiskSafetyOptions {
+    const fn any(&self) -> bool {
+        self.deny_disk_destructive
+    }
+}
+
 #[derive(Debug, Default, Clone, PartialEq, Eq)]
 struct RustEditOptions {
     deny_rust_allow: bool,
@@ -90,6 +286,80 @@ struct RustEditOptions {
     additional_context: Option<String>,
 }
 
+/// How Copilot is told about a failed check.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+enum DecisionMode {
+    #[default]
+    Deny,
+    Ask,
+    /// Print the reason to stderr and let the tool call through.
+    Warn,
+}
+
+impl DecisionMode {
+    fn parse(value: &str) -> Option<Self> {
+        match value {
+            "deny" => Some(Self::Deny),
+            "ask" => Some(Self::Ask),
+            "warn" => Some(Self::Warn),
+            _ => None,
+        }
+    }
+}
+
+/// `--mode` and the per-check overrides; unset overrides fall back to `--mode`.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+struct DecisionModeOptions {
+    mode: Option<DecisionMode>,
+    rm_mode: Option<DecisionMode>,
+    dangerous_paths_mode: Option<DecisionMode>,
+    rust_allow_mode: Option<DecisionMode>,
+}
+
+impl DecisionModeOptions {
+    fn global(&self) -> DecisionMode {
+        self.mode.unwrap_or_default()
+    }
+
+    fn rm(&self) -> DecisionMode {
+        self.rm_mode.unwrap_or_else(|| self.global())
+    }
+
+    fn dangerous_paths(&self) -> DecisionMode {
+        self.dangerous_paths_mode.unwrap_or_else(|| self.global())
+    }
+
+    fn rust_allow(&self) -> DecisionMode {
+        self.rust_allow_mode.unwrap_or_else(|| self.global())
+    }
+
+    fn set(&mut self, flag: &str, value: Option<&String>) -> Result<(), String> {
+        let value = value.ok_or_else(|| format!("{flag} requires a value"))?;
+        let mode = DecisionMode::parse(value)
+            .ok_or_else(|| format!("{flag} must be one of deny, ask, warn; got: {value}"))?;
+        let slot = match flag {
+            "--mode" => &mut self.mode,
+            "--rm-mode" => &mut self.rm_mode,
+            "--dangerous-paths-mode" => &mut self.dangerous_paths_mode,
+            _ => &mut self.rust_allow_mode,
+        };
+        *slot = Some(mode);
+        Ok(())
+    }
+
+    /// The mode flags that were given, for rejecting them where they are unsupported.
+    fn given_flags(&self) -> impl Iterator<Item = &'static str> {
+        [
+            (self.mode, "--mode"),
+            (self.rm_mode, "--rm-mode"),
+            (self.dangerous_paths_mode, "--dangerous-paths-mode"),
+            (self.rust_allow_mode, "--rust-allow-mode"),
+        ]
+        .into_iter()
+        .filter_map(|(mode, flag)| mode.map(|_| flag))
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Eq)]
 struct ParsedCli {
     provider: Provider,
@@ -99,7 +369,11 @@ struct ParsedCli {
 
 enum ParseCliResult {
     Help,
+    Version,
+    BuildInfo,
     Run(ParsedCli),
+    #[cfg(feature = "serve")]
+    Serve(ParsedCli, serve::ServeOptions),
 }
 
 fn main() {
@@ -109,6 +383,28 @@ fn main() {
             println!("{USAGE}");
             return;
         }
+        Ok(ParseCliResult::Version) => {
+            println!("agent_hooks {}", env!("CARGO_PKG_VERSION"));
+            return;
+        }
+        Ok(ParseCliResult::BuildInfo) => {
+            match serde_json::to_string(&BuildInfo::current()) {
+                Ok(json) => println!("{json}"),
+                Err(err) => {
+                    eprintln!("failed to serialize build info: {err}");
+                    process::exit(1);
+                }
+            }
+            return;
+        }
+        #[cfg(feature = "serve")]
+        Ok(ParseCliResult::Serve(parsed, serve_options)) => {
+            if let Err(err) = serve::run(parsed, serve_options) {
+                eprintln!("failed to serve hooks: {err}");
+                process::exit(1);
+            }
+            return;
+        }
         Err(message) => {
             eprintln!("{message}\n\n{USAGE}");
             process::exit(2);
@@ -123,6 +419,11 @@ fn main() {
         }
     };
 
+    if let Err(err) = validate_hook_input(parsed.provider, &input) {
+        println!("{}", err.to_json());
+        process::exit(1);
+    }
+
     match execute(&parsed, &input) {
         Ok(Some(output)) => println!("{output}"),
         Ok(None) => {}
@@ -139,30 +440,27 @@ fn parse_cli(args: impl Iterator<Item = String>) -> Result<ParseCliResult, Strin
         return Ok(ParseCliResult::Help);
     }
 
-    if args.len() < 2 {
-        return Err("missing provider or event".to_string());
+    match args[0].as_str() {
+        "-V" | "--version" => return Ok(ParseCliResult::Version),
+        "--build-info" => return Ok(ParseCliResult::BuildInfo),
+        "serve" => return parse_serve(&args[1..]),
+        _ => {}
     }
 
-    let provider =
-        Provider::parse(&args[0]).ok_or_else(|| format!("unknown provider: {}", args[0]))?;
-    let event = Event::parse(&args[1]).ok_or_else(|| format!("unknown event: {}", args[1]))?;
-
-    match (provider, event) {
-        (Provider::Claude | Provider::Codex, Event::PermissionRequest | Event::PreToolUse)
-        | (Provider::Copilot, Event::PreToolUse) => {}
-        _ => {
-            return Err(format!(
-                "unsupported provider/event combination: {} {}",
-                args[0], args[1]
-            ));
-        }
-    }
+    let (provider, event) = parse_provider_event(&args)?;
 
-    let mut options = CliOptions::default();
+    // `--check-all` is applied first so that the individual flags below override it.
+    let mut options = if args[2..].iter().any(|arg| arg == "--check-all") {
+        CliOptions::check_all(provider, event)
+    } else {
+        CliOptions::default()
+    };
     let mut index = 2;
     while index < args.len() {
         match args[index].as_str() {
+            "--check-all" => {}
             "--block-rm" => options.bash_permissions.block_rm = true,
+            "--check-heredoc" => options.bash_permissions.check_heredoc = true,
             "--dangerous-paths" => {
                 index += 1;
                 let value = args
@@ -179,9 +477,46 @@ fn parse_cli(args: impl Iterator<Item = String>) -> Result<ParseCliResult, Strin
                     .ok_or_else(|| "--additional-context requires a value".to_string())?;
                 options.rust_edits.additional_context = Some(value.clone());
             }
-            "--check-package-manager" => options.bash_safety.check_package_manager = true,
+            "--check-package-manager" => options.node_tooling.check_package_manager = true,
+            "--check-node-version" => options.node_tooling.check_node_version = true,
             "--deny-destructive-find" => options.bash_safety.deny_destructive_find = true,
             "--deny-nul-redirect" => options.bash_safety.deny_nul_redirect = true,
+            "--deny-critical-kill" => options.system_safety.deny_critical_kill = true,
+            "--dangerous-services" => {
+                index += 1;
+                let value = args
+                    .get(index)
+                    .ok_or_else(|| "--dangerous-services requires a value".to_string())?;
+                options.system_safety.dangerous_services = Some(value.clone());
+            }
+            "--check-docker-destructive" => {
+                options.system_safety.check_docker_destructive = true;
+            }
+            "--check-kubectl-delete" => options.cloud_safety.check_kubectl_delete = true,
+            "--check-aws-destructive" => options.cloud_safety.check_aws_destructive = true,
+            "--check-database-dangerous" => {
+                options.cloud_safety.check_database_dangerous = true;
+            }
+            "--deny-git-config-global" => options.repo_safety.deny_git_config_global = true,
+            "--deny-publish" => options.repo_safety.deny_publish = true,
+            "--allow-publish-dry-run" => options.repo_safety.allow_publish_dry_run = true,
+            "--deny-sudo-escalation" => options.privilege_safety.deny_sudo_escalation = true,
+            "--deny-mount" => options.privilege_safety.deny_mount = true,
+            "--deny-sensitive-read" => options.privilege_safety.deny_sensitive_read = true,
+            "--deny-crontab-modification" => {
+                options.persistence_safety.deny_crontab_modification = true;
+            }
+            "--deny-hosts-modification" => {
+                options.persistence_safety.deny_hosts_modification = true;
+            }
+            "--deny-firewall-modification" => {
+                options.system_safety.deny_firewall_modification = true;
+            }
+            "--deny-disk-destructive" => options.disk_safety.deny_disk_destructive = true,
+            flag @ ("--mode" | "--rm-mode" | "--dangerous-paths-mode" | "--rust-allow-mode") => {
+                index += 1;
+                options.decision_modes.set(flag, args.get(index))?;
+            }
             other => return Err(format!("unknown flag: {other}")),
         }
         index += 1;
@@ -196,6 +531,62 @@ fn parse_cli(args: impl Iterator<Item = String>) -> Result<ParseCliResult, Strin
     }))
 }
 
+/// Parse the leading `<provider> <event>` pair, rejecting unsupported combinations.
+fn parse_provider_event(args: &[String]) -> Result<(Provider, Event), String> {
+    if args.len() < 2 {
+        return Err("missing provider or event".to_string());
+    }
+
+    let provider =
+        Provider::parse(&args[0]).ok_or_else(|| format!("unknown provider: {}", args[0]))?;
+    let event = Event::parse(&args[1]).ok_or_else(|| format!("unknown event: {}", args[1]))?;
+
+    match (provider, event) {
+        (Provider::Claude | Provider::Codex, Event::PermissionRequest | Event::PreToolUse)
+        | (Provider::Copilot, Event::PreToolUse) => Ok((provider, event)),
+        _ => Err(format!(
+            "unsupported provider/event combination: {} {}",
+            args[0], args[1]
+        )),
+    }
+}
+
+/// Parse `serve <provider> <event> [serve flags] [flags]`.
+#[cfg(feature = "serve")]
+fn parse_serve(args: &[String]) -> Result<ParseCliResult, String> {
+    fn number<T: std::str::FromStr>(flag: &str, value: Option<&String>) -> Result<T, String> {
+        let value = value.ok_or_else(|| format!("{flag} requires a value"))?;
+        value
+            .parse()
+            .map_err(|_| format!("{flag} must be a non-negative number, got: {value}"))
+    }
+
+    let mut serve_options = serve::ServeOptions::default();
+    let mut rest = Vec::with_capacity(args.len());
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--port" => serve_options.port = number(arg, args.next())?,
+            "--cache-ttl-ms" => {
+                serve_options.cache_ttl =
+                    std::time::Duration::from_millis(number(arg, args.next())?);
+            }
+            "--cache-size" => serve_options.cache_size = number(arg, args.next())?,
+            _ => rest.push(arg.clone()),
+        }
+    }
+
+    match parse_cli(rest.into_iter())? {
+        ParseCliResult::Run(parsed) => Ok(ParseCliResult::Serve(parsed, serve_options)),
+        _ => Err("serve requires a provider and event".to_string()),
+    }
+}
+
+#[cfg(not(feature = "serve"))]
+fn parse_serve(_args: &[String]) -> Result<ParseCliResult, String> {
+    Err("serve is not available: agent_hooks was built without the `serve` feature".to_string())
+}
+
 fn read_stdin() -> io::Result<String> {
     let mut input = String::new();
     io::stdin().read_to_string(&mut input)?;
@@ -233,34 +624,17 @@ fn validate_option_support(
 ) -> Result<(), String> {
     let mut unsupported = Vec::new();
 
-    let supports_block_rm = matches!(
-        (provider, event),
-        (Provider::Claude, Event::PermissionRequest)
-            | (Provider::Copilot, Event::PreToolUse)
-            | (
-                Provider::Codex,
-                Event::PermissionRequest | Event::PreToolUse
-            )
-    );
+    let supports_block_rm = supports_bash_permissions(provider, event);
     let supports_dangerous_paths = supports_block_rm;
-    let supports_rust_allow = matches!(
-        (provider, event),
-        (
-            Provider::Claude | Provider::Copilot | Provider::Codex,
-            Event::PreToolUse
-        )
-    );
+    let supports_rust_allow = supports_pre_tool_use_checks(provider, event);
     let supports_expect = supports_rust_allow;
     let supports_additional_context = supports_rust_allow;
-    let supports_pm_checks = matches!(
-        (provider, event),
-        (
-            Provider::Claude | Provider::Copilot | Provider::Codex,
-            Event::PreToolUse
-        )
-    );
+    let supports_pm_checks = supports_pre_tool_use_checks(provider, event);
     let supports_destructive_find = supports_pm_checks;
     let supports_nul_redirect = supports_pm_checks;
+    let supports_system_safety = supports_pm_checks;
+    let supports_decision_modes =
+        matches!((provider, event), (Provider::Copilot, Event::PreToolUse));
 
     if options.bash_permissions.block_rm && !supports_block_rm {
         unsupported.push("--block-rm");
@@ -277,15 +651,72 @@ fn validate_option_support(
     if options.rust_edits.additional_context.is_some() && !supports_additional_context {
         unsupported.push("--additional-context");
     }
-    if options.bash_safety.check_package_manager && !supports_pm_checks {
+    if options.node_tooling.check_package_manager && !supports_pm_checks {
         unsupported.push("--check-package-manager");
     }
+    if options.node_tooling.check_node_version && !supports_pm_checks {
+        unsupported.push("--check-node-version");
+    }
     if options.bash_safety.deny_destructive_find && !supports_destructive_find {
         unsupported.push("--deny-destructive-find");
     }
     if options.bash_safety.deny_nul_redirect && !supports_nul_redirect {
         unsupported.push("--deny-nul-redirect");
     }
+    if options.system_safety.deny_critical_kill && !supports_system_safety {
+        unsupported.push("--deny-critical-kill");
+    }
+    if options.system_safety.deny_firewall_modification && !supports_system_safety {
+        unsupported.push("--deny-firewall-modification");
+    }
+    if options.disk_safety.deny_disk_destructive && !supports_system_safety {
+        unsupported.push("--deny-disk-destructive");
+    }
+    if options.system_safety.dangerous_services.is_some() && !supports_system_safety {
+        unsupported.push("--dangerous-services");
+    }
+    if options.system_safety.check_docker_destructive && !supports_system_safety {
+        unsupported.push("--check-docker-destructive");
+    }
+    if options.cloud_safety.check_kubectl_delete && !supports_system_safety {
+        unsupported.push("--check-kubectl-delete");
+    }
+    if options.cloud_safety.check_aws_destructive && !supports_system_safety {
+        unsupported.push("--check-aws-destructive");
+    }
+    if options.cloud_safety.check_database_dangerous && !supports_system_safety {
+        unsupported.push("--check-database-dangerous");
+    }
+    if options.repo_safety.deny_git_config_global && !supports_system_safety {
+        unsupported.push("--deny-git-config-global");
+    }
+    if options.repo_safety.deny_publish && !supports_system_safety {
+        unsupported.push("--deny-publish");
+    }
+    if options.privilege_safety.deny_sudo_escalation && !supports_system_safety {
+        unsupported.push("--deny-sudo-escalation");
+    }
+    if options.privilege_safety.deny_mount && !supports_system_safety {
+        unsupported.push("--deny-mount");
+    }
+    if options.privilege_safety.deny_sensitive_read && !supports_system_safety {
+        unsupported.push("--deny-sensitive-read");
+    }
+    if options.persistence_safety.deny_crontab_modification && !supports_system_safety {
+        unsupported.push("--deny-crontab-modification");
+    }
+    if options.persistence_safety.deny_hosts_modification && !supports_system_safety {
+        unsupported.push("--deny-hosts-modification");
+    }
+    if !supports_decision_modes {
+        unsupported.extend(options.decision_modes.given_flags());
+    }
+    if options.repo_safety.allow_publish_dry_run && !options.repo_safety.deny_publish {
+        return Err("--allow-publish-dry-run requires --deny-publish".to_string());
+    }
+    if options.bash_permissions.check_heredoc && !options.bash_permissions.block_rm {
+        return Err("--check-heredoc requires --block-rm".to_string());
+    }
 
     if unsupported.is_empty() {
         return Ok(());
@@ -296,3 +727,25 @@ fn validate_option_support(
         unsupported.join(", ")
     ))
 }
+
+const fn supports_bash_permissions(provider: Provider, event: Event) -> bool {
+    matches!(
+        (provider, event),
+        (Provider::Claude, Event::PermissionRequest)
+            | (Provider::Copilot, Event::PreToolUse)
+            | (
+                Provider::Codex,
+                Event::PermissionRequest | Event::PreToolUse
+            )
+    )
+}
+
+const fn supports_pre_tool_use_checks(provider: Provider, event: Event) -> bool {
+    matches!(
+        (provider, event),
+        (
+            Provider::Claude | Provider::Copilot | Provider::Codex,
+            Event::PreToolUse
+        )
+    )
+}
@@ -0,0 +1,159 @@
+//! `agent_hooks serve`: answer hook requests over HTTP from a long-running process,
+//! avoiding a process start and regex compilation per tool call.
+
+use std::hash::{DefaultHasher, Hash, Hasher};
+use std::io;
+use std::net::Ipv4Addr;
+use std::num::NonZeroUsize;
+use std::sync::{Arc, Mutex, PoisonError};
+use std::time::{Duration, Instant};
+
+use axum::Router;
+use axum::extract::State;
+use axum::http::{HeaderName, StatusCode, header};
+use axum::routing::post;
+use lru::LruCache;
+
+use crate::hooks::validate_hook_input;
+use crate::{ParsedCli, execute};
+
+const X_CACHE: HeaderName = HeaderName::from_static("x-cache");
+
+/// Flags that only apply to `serve`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ServeOptions {
+    pub port: u16,
+    /// How long a response is reused for an identical request.
+    pub cache_ttl: Duration,
+    /// Number of distinct requests remembered; `0` disables the cache.
+    pub cache_size: usize,
+}
+
+impl Default for ServeOptions {
+    fn default() -> Self {
+        Self {
+            port: 8787,
+            cache_ttl: Duration::from_millis(100),
+            cache_size: 256,
+        }
+    }
+}
+
+struct ServerState {
+    parsed: ParsedCli,
+    cache: Option<ResponseCache>,
+}
+
+/// Recently returned responses keyed on a hash of the request body, so identical
+/// hook inputs sent in quick succession (e.g. parallel tool use) are checked once.
+pub struct ResponseCache {
+    entries: Mutex<LruCache<u64, (Instant, String)>>,
+    ttl: Duration,
+}
+
+impl ResponseCache {
+    pub fn new(size: NonZeroUsize, ttl: Duration) -> Self {
+        Self {
+            entries: Mutex::new(LruCache::new(size)),
+            ttl,
+        }
+    }
+
+    /// Hash a request body into a cache key.
+    pub fn key(input: &str) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        input.hash(&mut hasher);
+        hasher.finish()
+    }
+
+    /// The response stored for `key`, unless it is older than the TTL.
+    pub fn get(&self, key: u64) -> Option<String> {
+        let mut entries = self.entries.lock().unwrap_or_else(PoisonError::into_inner);
+        let (stored_at, body) = entries.get(&key)?;
+        if stored_at.elapsed() < self.ttl {
+            return Some(body.clone());
+        }
+        entries.pop(&key);
+        None
+    }
+
+    pub fn insert(&self, key: u64, body: String) {
+        self.entries
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .put(key, (Instant::now(), body));
+    }
+}
+
+/// Serve `parsed` on `127.0.0.1:<port>` until the process is stopped.
+pub fn run(parsed: ParsedCli, options: ServeOptions) -> io::Result<()> {
+    agent_hooks::preload_patterns();
+    let cache = NonZeroUsize::new(options.cache_size)
+        .filter(|_| !options.cache_ttl.is_zero())
+        .map(|size| ResponseCache::new(size, options.cache_ttl));
+    let state = Arc::new(ServerState { parsed, cache });
+    let port = options.port;
+
+    let runtime = tokio::runtime::Builder::new_current_thread()
+        .enable_io()
+        .build()?;
+    runtime.block_on(async move {
+        let listener = tokio::net::TcpListener::bind((Ipv4Addr::LOCALHOST, port)).await?;
+        eprintln!(
+            "agent_hooks: listening on http://{}",
+            listener.local_addr()?
+        );
+        let router = Router::new()
+            .route("/", post(handle_hook))
+            .with_state(state);
+        axum::serve(listener, router).await
+    })
+}
+
+async fn handle_hook(
+    State(state): State<Arc<ServerState>>,
+    body: String,
+) -> (StatusCode, [(HeaderName, &'static str); 2], String) {
+    let key = ResponseCache::key(&body);
+    if let Some(cached) = state.cache.as_ref().and_then(|cache| cache.get(key)) {
+        return (
+            StatusCode::OK,
+            [(header::CONTENT_TYPE, "application/json"), (X_CACHE, "HIT")],
+            cached,
+        );
+    }
+
+    // Some checks read lock files or run `node`, so keep them off the I/O thread
+    let worker_state = Arc::clone(&state);
+    let (status, output) =
+        tokio::task::spawn_blocking(move || respond(&worker_state.parsed, &body))
+            .await
+            .unwrap_or_else(|err| (StatusCode::INTERNAL_SERVER_ERROR, err.to_string()));
+    if status == StatusCode::OK
+        && let Some(cache) = &state.cache
+    {
+        cache.insert(key, output.clone());
+    }
+    (
+        status,
+        [
+            (header::CONTENT_TYPE, "application/json"),
+            (X_CACHE, "MISS"),
+        ],
+        output,
+    )
+}
+
+/// Status and body for one hook request, mirroring the CLI's exit status and stdout.
+///
+/// Rejected input gets `400` with the JSON error; an allowed tool call gets `200`
+/// with an empty body, like the CLI printing nothing.
+pub fn respond(parsed: &ParsedCli, input: &str) -> (StatusCode, String) {
+    if let Err(err) = validate_hook_input(parsed.provider, input) {
+        return (StatusCode::BAD_REQUEST, err.to_json());
+    }
+    match execute(parsed, input) {
+        Ok(output) => (StatusCode::OK, output.unwrap_or_default()),
+        Err(err) => (StatusCode::INTERNAL_SERVER_ERROR, err.to_string()),
+    }
+}
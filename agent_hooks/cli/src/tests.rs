@@ -41,6 +41,85 @@ fn parse_cli_accepts_codex_permission_request() {
     assert!(matches!(result, Ok(ParseCliResult::Run(_))));
 }
 
+fn parse_options(args: &[&str]) -> CliOptions {
+    match parse_cli(args.iter().map(|arg| (*arg).to_string())) {
+        Ok(ParseCliResult::Run(parsed)) => parsed.options,
+        _ => panic!("expected {args:?} to parse"),
+    }
+}
+
+#[test]
+fn parse_cli_check_all_enables_supported_checks() {
+    let options = parse_options(&["codex", "pre-tool-use", "--check-all"]);
+    assert_eq!(
+        options,
+        CliOptions::check_all(Provider::Codex, Event::PreToolUse)
+    );
+    assert!(options.bash_permissions.block_rm);
+    assert_eq!(
+        options.bash_permissions.dangerous_paths.as_deref(),
+        Some(DEFAULT_DANGEROUS_PATHS)
+    );
+    assert!(options.bash_safety.deny_destructive_find);
+    assert!(options.node_tooling.check_package_manager);
+    assert!(options.rust_edits.deny_rust_allow);
+    assert!(!options.rust_edits.expect);
+
+    let options = parse_options(&["claude", "pre-tool-use", "--check-all"]);
+    assert!(!options.bash_permissions.block_rm);
+    assert!(options.bash_permissions.dangerous_paths.is_none());
+    assert!(options.rust_edits.deny_rust_allow);
+
+    let options = parse_options(&["claude", "permission-request", "--check-all"]);
+    assert!(options.bash_permissions.block_rm);
+    assert!(!options.rust_edits.deny_rust_allow);
+    assert!(!options.bash_safety.deny_destructive_find);
+}
+
+#[test]
+fn parse_cli_check_all_is_overridden_by_individual_flags() {
+    let options = parse_options(&[
+        "copilot",
+        "pre-tool-use",
+        "--dangerous-paths",
+        "~/work/",
+        "--check-all",
+        "--expect",
+    ]);
+    assert_eq!(
+        options.bash_permissions.dangerous_paths.as_deref(),
+        Some("~/work/")
+    );
+    assert!(options.rust_edits.deny_rust_allow);
+    assert!(options.rust_edits.expect);
+}
+
+#[test]
+fn parse_cli_accepts_version_and_build_info() {
+    assert!(matches!(
+        parse_cli(std::iter::once("--version".to_string())),
+        Ok(ParseCliResult::Version)
+    ));
+    assert!(matches!(
+        parse_cli(std::iter::once("--build-info".to_string())),
+        Ok(ParseCliResult::BuildInfo)
+    ));
+}
+
+#[test]
+fn build_info_serializes_expected_fields() {
+    let info: Value = serde_json::to_value(BuildInfo::current()).unwrap();
+
+    assert_eq!(info["version"], env!("CARGO_PKG_VERSION"));
+    assert!(!info["target"].as_str().unwrap().is_empty());
+    assert!(!info["rustc"].as_str().unwrap().is_empty());
+    assert!(info["features"].is_array());
+    assert_eq!(
+        info["build_date"].as_str().unwrap().len(),
+        "2025-01-01".len()
+    );
+}
+
 #[test]
 fn claude_permission_request_blocks_rm() {
     let parsed = ParsedCli {
@@ -67,6 +146,127 @@ fn claude_permission_request_blocks_rm() {
     );
 }
 
+#[test]
+fn claude_permission_request_checks_heredocs() {
+    let run = |check_heredoc: bool, cmd: &str| {
+        let parsed = ParsedCli {
+            provider: Provider::Claude,
+            event: Event::PermissionRequest,
+            options: CliOptions {
+                bash_permissions: BashPermissionOptions {
+                    block_rm: true,
+                    check_heredoc,
+                    ..BashPermissionOptions::default()
+                },
+                ..CliOptions::default()
+            },
+        };
+        let input = serde_json::json!({
+            "tool_name": "Bash",
+            "tool_input": { "command": cmd },
+        });
+        run_hook(&parsed, &input.to_string())
+    };
+
+    let output = run(false, "bash <<EOF\nrm -rf build\nEOF").unwrap();
+    assert_eq!(
+        output["hookSpecificOutput"]["decision"]["behavior"],
+        Value::String("deny".to_string())
+    );
+
+    let script = "cat > cleanup.sh <<'EOF'\nrm -rf build\nEOF";
+    assert!(run(false, script).is_none());
+    let output = run(true, script).unwrap();
+    assert_eq!(
+        output["hookSpecificOutput"]["decision"]["behavior"],
+        Value::String("deny".to_string())
+    );
+}
+
+#[test]
+fn parse_cli_reads_check_heredoc() {
+    let options = parse_options(&[
+        "claude",
+        "permission-request",
+        "--block-rm",
+        "--check-heredoc",
+    ]);
+    assert!(options.bash_permissions.check_heredoc);
+    assert!(
+        parse_cli(
+            ["claude", "pre-tool-use", "--check-heredoc"]
+                .into_iter()
+                .map(String::from),
+        )
+        .is_err()
+    );
+}
+
+#[test]
+fn claude_permission_request_asks_for_dangerous_path() {
+    let parsed = ParsedCli {
+        provider: Provider::Claude,
+        event: Event::PermissionRequest,
+        options: CliOptions {
+            bash_permissions: BashPermissionOptions {
+                dangerous_paths: Some("~/".to_string()),
+                ..BashPermissionOptions::default()
+            },
+            ..CliOptions::default()
+        },
+    };
+
+    let output = run_hook(
+        &parsed,
+        r#"{"tool_name":"Bash","tool_input":{"command":"mv ~/ /tmp/home"}}"#,
+    )
+    .unwrap();
+
+    assert_eq!(
+        output["hookSpecificOutput"]["hookEventName"],
+        Value::String("PermissionRequest".to_string())
+    );
+    assert_eq!(
+        output["hookSpecificOutput"]["permissionDecision"],
+        Value::String("ask".to_string())
+    );
+    assert!(output["hookSpecificOutput"].get("decision").is_none());
+}
+
+#[test]
+fn claude_permission_request_checks_base64_payload() {
+    let parsed = ParsedCli {
+        provider: Provider::Claude,
+        event: Event::PermissionRequest,
+        options: parse_options(&[
+            "claude",
+            "permission-request",
+            "--block-rm",
+            "--dangerous-paths",
+            "/",
+        ]),
+    };
+    let run = |cmd: &str| {
+        let input = serde_json::json!({
+            "tool_name": "Bash",
+            "tool_input": { "command": cmd },
+        });
+        run_hook(&parsed, &input.to_string()).unwrap()
+    };
+
+    let output = run(r#"bash -c "$(echo cm0gLXJmIC8= | base64 -d)""#);
+    assert_eq!(output["hookSpecificOutput"]["decision"]["behavior"], "deny");
+    assert!(
+        output["hookSpecificOutput"]["decision"]["message"]
+            .as_str()
+            .unwrap()
+            .starts_with("Base64-encoded command `rm -rf /` was denied: rm is forbidden")
+    );
+
+    let output = run("echo dHJhc2ggLw== | base64 -d | sh");
+    assert_eq!(output["hookSpecificOutput"]["permissionDecision"], "ask");
+}
+
 #[test]
 fn claude_pre_tool_use_denies_rust_allow() {
     let parsed = ParsedCli {
@@ -94,6 +294,126 @@ fn claude_pre_tool_use_denies_rust_allow() {
     );
 }
 
+#[test]
+fn claude_pre_tool_use_denies_rust_allow_in_multi_edit() {
+    let parsed = ParsedCli {
+        provider: Provider::Claude,
+        event: Event::PreToolUse,
+        options: parse_options(&["claude", "pre-tool-use", "--deny-rust-allow"]),
+    };
+
+    let output = run_hook(
+        &parsed,
+        r##"{"tool_name":"MultiEdit","tool_input":{"file_path":"src/lib.rs","edits":[{"old_string":"a","new_string":"fn a() {}"},{"old_string":"b","new_string":"#[allow(unused)]"}]}}"##,
+    )
+    .unwrap();
+    assert_eq!(output["hookSpecificOutput"]["permissionDecision"], "deny");
+
+    assert!(
+        run_hook(
+            &parsed,
+            r#"{"tool_name":"NotebookEdit","tool_input":{"notebook_path":"analysis.ipynb","new_source":"print(1)"}}"#,
+        )
+        .is_none()
+    );
+}
+
+#[test]
+fn claude_pre_tool_use_checks_each_file_in_multi_edit_batch() {
+    let parsed = ParsedCli {
+        provider: Provider::Claude,
+        event: Event::PreToolUse,
+        options: parse_options(&[
+            "claude",
+            "pre-tool-use",
+            "--deny-rust-allow",
+            "--deny-crontab-modification",
+        ]),
+    };
+
+    let output = run_hook(
+        &parsed,
+        r##"{"tool_name":"MultiEdit","tool_input":{"file_path":"README.md","edits":[{"old_string":"a","new_string":"#[allow(unused)]"},{"file_path":"src/lib.rs","old_string":"b","new_string":"#[allow(unused)]"}]}}"##,
+    )
+    .unwrap();
+    assert_eq!(output["hookSpecificOutput"]["permissionDecision"], "deny");
+
+    let output = run_hook(
+        &parsed,
+        r#"{"tool_name":"MultiEdit","tool_input":{"file_path":"notes.txt","edits":[{"old_string":"a","new_string":"b"},{"file_path":"/etc/cron.d/backup","old_string":"c","new_string":"d"}]}}"#,
+    )
+    .unwrap();
+    assert!(
+        output["hookSpecificOutput"]["permissionDecisionReason"]
+            .as_str()
+            .unwrap()
+            .contains("/etc/cron.d/backup")
+    );
+
+    assert!(
+        run_hook(
+            &parsed,
+            r##"{"tool_name":"MultiEdit","tool_input":{"file_path":"README.md","edits":[{"old_string":"a","new_string":"#[allow(unused)]"}]}}"##,
+        )
+        .is_none()
+    );
+}
+
+#[test]
+fn unprocessable_input_warning_names_raw_tool() {
+    let input = r#"{"tool_name":"FutureTool","tool_input":"not an object"}"#;
+    let value: Value = serde_json::from_str(input).unwrap();
+    let err = serde_json::from_value::<Vec<String>>(value["tool_input"].clone()).unwrap_err();
+    let warning = hooks::unprocessable_input_warning(&value, &err);
+    assert!(warning.contains(r#"tool "FutureTool""#));
+    assert!(warning.contains("invalid type"));
+
+    let parsed = ParsedCli {
+        provider: Provider::Claude,
+        event: Event::PreToolUse,
+        options: parse_options(&["claude", "pre-tool-use", "--deny-rust-allow"]),
+    };
+    assert!(run_hook(&parsed, input).is_none());
+}
+
+#[test]
+fn claude_permission_request_expands_env_vars_in_paths() {
+    let parsed = ParsedCli {
+        provider: Provider::Claude,
+        event: Event::PermissionRequest,
+        options: parse_options(&["claude", "permission-request", "--dangerous-paths", "/etc"]),
+    };
+
+    let output = run_hook(
+        &parsed,
+        r#"{"tool_name":"Bash","tool_input":{"command":"trash -rf ${PROJECT_DIR}/nginx"},"env":{"PROJECT_DIR":"/etc"}}"#,
+    )
+    .unwrap();
+    let reason = output["hookSpecificOutput"]["permissionDecisionReason"]
+        .as_str()
+        .unwrap();
+    assert!(reason.contains("trash command targeting protected path '/etc'"));
+
+    let output = run_hook(
+        &parsed,
+        r#"{"tool_name":"Bash","tool_input":{"command":"mv ./hosts ${PROJECT_DIR}"},"env":{"PROJECT_DIR":"/etc"}}"#,
+    )
+    .unwrap();
+    let reason = output["hookSpecificOutput"]["permissionDecisionReason"]
+        .as_str()
+        .unwrap();
+    assert!(reason.contains("mv command overwriting protected path '/etc'"));
+
+    // Single-quoted references are not expanded by the shell
+    assert!(
+        run_hook(
+            &parsed,
+            r#"{"tool_name":"Bash","tool_input":{"command":"trash '$PROJECT_DIR'"},"env":{"PROJECT_DIR":"/etc"}}"#,
+        )
+        .is_none()
+    );
+}
+
 #[test]
 fn copilot_pre_tool_use_blocks_rm() {
     let parsed = ParsedCli {
@@ -209,9 +529,9 @@ fn codex_pre_tool_use_denies_package_manager_mismatch() {
         provider: Provider::Codex,
         event: Event::PreToolUse,
         options: CliOptions {
-            bash_safety: BashSafetyOptions {
+            node_tooling: NodeToolingOptions {
                 check_package_manager: true,
-                ..BashSafetyOptions::default()
+                ..NodeToolingOptions::default()
             },
             ..CliOptions::default()
         },
@@ -235,6 +555,111 @@ fn codex_pre_tool_use_denies_package_manager_mismatch() {
     let _ = std::fs::remove_dir(&temp_dir);
 }
 
+#[test]
+fn codex_pre_tool_use_mentions_pnpm_workspace() {
+    let temp_dir = std::env::temp_dir().join("agent_hooks_cli_codex_pnpm_workspace");
+    let package_dir = temp_dir.join("packages").join("web");
+    let _ = std::fs::create_dir_all(&package_dir);
+    std::fs::write(temp_dir.join("pnpm-lock.yaml"), "").unwrap();
+    std::fs::write(temp_dir.join("pnpm-workspace.yaml"), "").unwrap();
+
+    let parsed = ParsedCli {
+        provider: Provider::Codex,
+        event: Event::PreToolUse,
+        options: CliOptions {
+            node_tooling: NodeToolingOptions {
+                check_package_manager: true,
+                ..NodeToolingOptions::default()
+            },
+            ..CliOptions::default()
+        },
+    };
+    let escaped_cwd = package_dir.display().to_string().replace('\\', "\\\\");
+
+    let output = run_hook(
+        &parsed,
+        &format!(
+            r#"{{"cwd":"{escaped_cwd}","tool_name":"Bash","tool_input":{{"command":"npm install lodash"}}}}"#
+        ),
+    )
+    .unwrap();
+
+    let reason = output["hookSpecificOutput"]["permissionDecisionReason"]
+        .as_str()
+        .unwrap();
+    assert!(reason.contains("Package manager mismatch"));
+    assert!(reason.contains(&format!(
+        "(detected {})",
+        temp_dir.join("pnpm-lock.yaml").display()
+    )));
+    assert!(reason.contains("This is a pnpm workspace"));
+
+    let _ = std::fs::remove_dir_all(&temp_dir);
+}
+
+#[test]
+fn parse_cli_rejects_check_node_version_for_permission_request() {
+    let result = parse_cli(
+        ["claude", "permission-request", "--check-node-version"]
+            .into_iter()
+            .map(String::from),
+    );
+
+    assert!(result.is_err());
+}
+
+#[test]
+fn copilot_pre_tool_use_denies_node_version_mismatch() {
+    let temp_dir = std::env::temp_dir().join("agent_hooks_cli_copilot_node_version");
+    let _ = std::fs::create_dir_all(&temp_dir);
+    // No released Node version satisfies this range
+    std::fs::write(temp_dir.join(".nvmrc"), "<0.1").unwrap();
+
+    let parsed = ParsedCli {
+        provider: Provider::Copilot,
+        event: Event::PreToolUse,
+        options: CliOptions {
+            node_tooling: NodeToolingOptions {
+                check_node_version: true,
+                ..NodeToolingOptions::default()
+            },
+            ..CliOptions::default()
+        },
+    };
+    let escaped_cwd = temp_dir.display().to_string().replace('\\', "\\\\");
+
+    let output = run_hook(
+        &parsed,
+        &format!(
+            r#"{{"toolName":"bash","toolArgs":"{{\"command\":\"npm install\"}}","cwd":"{escaped_cwd}"}}"#
+        ),
+    );
+
+    // Without a `node` binary on PATH the check is skipped
+    if std::process::Command::new("node")
+        .arg("--version")
+        .output()
+        .is_ok()
+    {
+        let output = output.unwrap();
+        assert_eq!(
+            output["permissionDecision"],
+            Value::String("deny".to_string())
+        );
+        assert!(
+            output["permissionDecisionReason"]
+                .as_str()
+                .unwrap()
+                .contains("Node version mismatch")
+        );
+    } else {
+        assert!(output.is_none());
+    }
+
+    let _ = std::fs::remove_file(temp_dir.join(".nvmrc"));
+    let _ = std::fs::remove_dir(&temp_dir);
+}
+
 #[test]
 fn codex_permission_request_blocks_rm() {
     let parsed = ParsedCli {
@@ -264,3 +689,813 @@ fn codex_permission_request_blocks_rm() {
         Value::String("deny".to_string())
     );
 }
+
+#[test]
+fn claude_pre_tool_use_denies_critical_kill() {
+    let parsed = ParsedCli {
+        provider: Provider::Claude,
+        event: Event::PreToolUse,
+        options: CliOptions {
+            system_safety: SystemSafetyOptions {
+                deny_critical_kill: true,
+                ..SystemSafetyOptions::default()
+            },
+            ..CliOptions::default()
+        },
+    };
+
+    let output = run_hook(
+        &parsed,
+        r#"{"tool_name":"Bash","tool_input":{"command":"sudo killall sshd"}}"#,
+    )
+    .unwrap();
+
+    assert_eq!(
+        output["hookSpecificOutput"]["permissionDecision"],
+        Value::String("deny".to_string())
+    );
+    let reason = output["hookSpecificOutput"]["permissionDecisionReason"]
+        .as_str()
+        .unwrap();
+    assert!(reason.contains("Critical process kill detected: killall targeting 'sshd'"));
+
+    assert!(
+        run_hook(
+            &parsed,
+            r#"{"tool_name":"Bash","tool_input":{"command":"pkill -f vite"}}"#,
+        )
+        .is_none()
+    );
+}
+
+#[test]
+fn copilot_pre_tool_use_denies_firewall_modification() {
+    let parsed = ParsedCli {
+        provider: Provider::Copilot,
+        event: Event::PreToolUse,
+        options: CliOptions {
+            system_safety: SystemSafetyOptions {
+                deny_firewall_modification: true,
+                ..SystemSafetyOptions::default()
+            },
+            ..CliOptions::default()
+        },
+    };
+
+    let output = run_hook(
+        &parsed,
+        r#"{"toolName":"bash","toolArgs":"{\"command\":\"sudo firewall-cmd --panic-on\"}"}"#,
+    )
+    .unwrap();
+
+    assert_eq!(
+        output["permissionDecision"],
+        Value::String("deny".to_string())
+    );
+    let reason = output["permissionDecisionReason"].as_str().unwrap();
+    assert!(reason.contains("firewall-cmd --panic-on (critical severity)"));
+}
+
+#[test]
+fn codex_pre_tool_use_denies_dangerous_service() {
+    let parsed = ParsedCli {
+        provider: Provider::Codex,
+        event: Event::PreToolUse,
+        options: parse_options(&[
+            "codex",
+            "pre-tool-use",
+            "--dangerous-services",
+            "sshd, nginx",
+        ]),
+    };
+
+    let output = run_hook(
+        &parsed,
+        r#"{"cwd":"/repo","tool_name":"Bash","tool_input":{"command":"sudo systemctl stop nginx.service"}}"#,
+    )
+    .unwrap();
+
+    let reason = output["hookSpecificOutput"]["permissionDecisionReason"]
+        .as_str()
+        .unwrap();
+    assert!(reason.contains("systemctl stop nginx.service"));
+
+    assert!(
+        run_hook(
+            &parsed,
+            r#"{"cwd":"/repo","tool_name":"Bash","tool_input":{"command":"systemctl stop docker"}}"#,
+        )
+        .is_none()
+    );
+}
+
+#[test]
+fn claude_pre_tool_use_denies_docker_destructive() {
+    let parsed = ParsedCli {
+        provider: Provider::Claude,
+        event: Event::PreToolUse,
+        options: CliOptions {
+            system_safety: SystemSafetyOptions {
+                check_docker_destructive: true,
+                ..SystemSafetyOptions::default()
+            },
+            ..CliOptions::default()
+        },
+    };
+
+    let output = run_hook(
+        &parsed,
+        r#"{"tool_name":"Bash","tool_input":{"command":"docker system prune -af"}}"#,
+    )
+    .unwrap();
+
+    let reason = output["hookSpecificOutput"]["permissionDecisionReason"]
+        .as_str()
+        .unwrap();
+    assert!(reason.contains("docker system prune with --force"));
+}
+
+#[test]
+fn codex_pre_tool_use_denies_kubectl_delete() {
+    let parsed = ParsedCli {
+        provider: Provider::Codex,
+        event: Event::PreToolUse,
+        options: CliOptions {
+            cloud_safety: CloudSafetyOptions {
+                check_kubectl_delete: true,
+                ..CloudSafetyOptions::default()
+            },
+            ..CliOptions::default()
+        },
+    };
+
+    let output = run_hook(
+        &parsed,
+        r#"{"cwd":"/repo","tool_name":"Bash","tool_input":{"command":"kubectl delete namespace production"}}"#,
+    )
+    .unwrap();
+
+    let reason = output["hookSpecificOutput"]["permissionDecisionReason"]
+        .as_str()
+        .unwrap();
+    assert!(reason.contains("deleting namespace"));
+}
+
+#[test]
+fn copilot_pre_tool_use_denies_aws_destructive() {
+    let parsed = ParsedCli {
+        provider: Provider::Copilot,
+        event: Event::PreToolUse,
+        options: CliOptions {
+            cloud_safety: CloudSafetyOptions {
+                check_aws_destructive: true,
+                ..CloudSafetyOptions::default()
+            },
+            ..CliOptions::default()
+        },
+    };
+
+    let output = run_hook(
+        &parsed,
+        r#"{"toolName":"bash","toolArgs":"{\"command\":\"aws s3 rm s3://backups --recursive\"}"}"#,
+    )
+    .unwrap();
+
+    assert_eq!(
+        output["permissionDecision"],
+        Value::String("deny".to_string())
+    );
+    let reason = output["permissionDecisionReason"].as_str().unwrap();
+    assert!(reason.contains("aws s3 rm"));
+}
+
+#[test]
+fn claude_pre_tool_use_denies_database_dangerous() {
+    let parsed = ParsedCli {
+        provider: Provider::Claude,
+        event: Event::PreToolUse,
+        options: CliOptions {
+            cloud_safety: CloudSafetyOptions {
+                check_database_dangerous: true,
+                ..CloudSafetyOptions::default()
+            },
+            ..CliOptions::default()
+        },
+    };
+
+    let output = run_hook(
+        &parsed,
+        r#"{"tool_name":"Bash","tool_input":{"command":"psql -c 'TRUNCATE orders; VACUUM'"}}"#,
+    )
+    .unwrap();
+
+    let reason = output["hookSpecificOutput"]["permissionDecisionReason"]
+        .as_str()
+        .unwrap();
+    assert!(reason.contains("TRUNCATE via psql"));
+}
+
+#[test]
+fn codex_pre_tool_use_denies_git_config_global() {
+    let parsed = ParsedCli {
+        provider: Provider::Codex,
+        event: Event::PreToolUse,
+        options: CliOptions {
+            repo_safety: RepoSafetyOptions {
+                deny_git_config_global: true,
+                ..RepoSafetyOptions::default()
+            },
+            ..CliOptions::default()
+        },
+    };
+
+    let output = run_hook(
+        &parsed,
+        r#"{"cwd":"/repo","tool_name":"Bash","tool_input":{"command":"git config --global user.email bot@example.com"}}"#,
+    )
+    .unwrap();
+
+    let reason = output["hookSpecificOutput"]["permissionDecisionReason"]
+        .as_str()
+        .unwrap();
+    assert!(reason.contains("Global git config modification detected"));
+
+    assert!(
+        run_hook(
+            &parsed,
+            r#"{"cwd":"/repo","tool_name":"Bash","tool_input":{"command":"git config user.email bot@example.com"}}"#,
+        )
+        .is_none()
+    );
+}
+
+#[test]
+fn claude_pre_tool_use_denies_sudo_escalation() {
+    let parsed = ParsedCli {
+        provider: Provider::Claude,
+        event: Event::PreToolUse,
+        options: parse_options(&["claude", "pre-tool-use", "--deny-sudo-escalation"]),
+    };
+
+    let output = run_hook(
+        &parsed,
+        r#"{"tool_name":"Bash","tool_input":{"command":"sudo su -"}}"#,
+    )
+    .unwrap();
+
+    let reason = output["hookSpecificOutput"]["permissionDecisionReason"]
+        .as_str()
+        .unwrap();
+    assert!(reason.contains("Root shell escalation detected: sudo su"));
+
+    assert!(
+        run_hook(
+            &parsed,
+            r#"{"tool_name":"Bash","tool_input":{"command":"sudo systemctl status nginx"}}"#,
+        )
+        .is_none()
+    );
+}
+
+#[test]
+fn codex_pre_tool_use_denies_mount() {
+    let parsed = ParsedCli {
+        provider: Provider::Codex,
+        event: Event::PreToolUse,
+        options: parse_options(&["codex", "pre-tool-use", "--deny-mount"]),
+    };
+
+    let output = run_hook(
+        &parsed,
+        r#"{"cwd":"/repo","tool_name":"Bash","tool_input":{"command":"sudo mount --bind /etc ./etc"}}"#,
+    )
+    .unwrap();
+
+    let reason = output["hookSpecificOutput"]["permissionDecisionReason"]
+        .as_str()
+        .unwrap();
+    assert!(reason.contains("Mount operation detected: mount on './etc' (high severity)"));
+
+    assert!(
+        run_hook(
+            &parsed,
+            r#"{"cwd":"/repo","tool_name":"Bash","tool_input":{"command":"mount"}}"#,
+        )
+        .is_none()
+    );
+}
+
+#[test]
+fn copilot_pre_tool_use_denies_sensitive_read() {
+    let parsed = ParsedCli {
+        provider: Provider::Copilot,
+        event: Event::PreToolUse,
+        options: parse_options(&["copilot", "pre-tool-use", "--deny-sensitive-read"]),
+    };
+
+    let output = run_hook(
+        &parsed,
+        r#"{"toolName":"bash","toolArgs":"{\"command\":\"cat /etc/shadow\"}"}"#,
+    )
+    .unwrap();
+
+    let reason = output["permissionDecisionReason"].as_str().unwrap();
+    assert!(reason.contains("Sensitive file read detected: cat '/etc/shadow'"));
+
+    assert!(
+        run_hook(
+            &parsed,
+            r#"{"toolName":"bash","toolArgs":"{\"command\":\"cat /etc/hosts\"}"}"#,
+        )
+        .is_none()
+    );
+}
+
+#[test]
+fn claude_pre_tool_use_denies_crontab_modification() {
+    let parsed = ParsedCli {
+        provider: Provider::Claude,
+        event: Event::PreToolUse,
+        options: parse_options(&["claude", "pre-tool-use", "--deny-crontab-modification"]),
+    };
+
+    let output = run_hook(
+        &parsed,
+        r#"{"tool_name":"Bash","tool_input":{"command":"crontab -r"}}"#,
+    )
+    .unwrap();
+    let reason = output["hookSpecificOutput"]["permissionDecisionReason"]
+        .as_str()
+        .unwrap();
+    assert!(reason.contains("Crontab removal detected"));
+
+    let output = run_hook(
+        &parsed,
+        r#"{"tool_name":"Write","tool_input":{"file_path":"/etc/cron.d/job","content":"* * * * * root /tmp/x"}}"#,
+    )
+    .unwrap();
+    let reason = output["hookSpecificOutput"]["permissionDecisionReason"]
+        .as_str()
+        .unwrap();
+    assert!(reason.contains("Cron file modification detected: '/etc/cron.d/job'"));
+
+    assert!(
+        run_hook(
+            &parsed,
+            r#"{"tool_name":"Bash","tool_input":{"command":"crontab -l"}}"#,
+        )
+        .is_none()
+    );
+}
+
+#[test]
+fn codex_pre_tool_use_denies_cron_file_patch() {
+    let parsed = ParsedCli {
+        provider: Provider::Codex,
+        event: Event::PreToolUse,
+        options: parse_options(&["codex", "pre-tool-use", "--deny-crontab-modification"]),
+    };
+
+    let output = run_hook(
+        &parsed,
+        r#"{"cwd":"/repo","tool_name":"apply_patch","tool_input":{"command":"*** Begin Patch\n*** Update File: /etc/crontab\n+* * * * * root /tmp/x\n*** End Patch"}}"#,
+    )
+    .unwrap();
+    let reason = output["hookSpecificOutput"]["permissionDecisionReason"]
+        .as_str()
+        .unwrap();
+    assert!(reason.contains("Cron file modification detected: '/etc/crontab'"));
+
+    assert!(
+        run_hook(
+            &parsed,
+            r#"{"cwd":"/repo","tool_name":"apply_patch","tool_input":{"command":"*** Begin Patch\n*** Update File: src/cron.rs\n+fn main() {}\n*** End Patch"}}"#,
+        )
+        .is_none()
+    );
+}
+
+#[test]
+fn copilot_pre_tool_use_denies_hosts_modification() {
+    let parsed = ParsedCli {
+        provider: Provider::Copilot,
+        event: Event::PreToolUse,
+        options: parse_options(&["copilot", "pre-tool-use", "--deny-hosts-modification"]),
+    };
+
+    let output = run_hook(
+        &parsed,
+        r#"{"toolName":"bash","toolArgs":"{\"command\":\"echo '1.2.3.4 github.com' | sudo tee -a /etc/hosts\"}"}"#,
+    )
+    .unwrap();
+    let reason = output["permissionDecisionReason"].as_str().unwrap();
+    assert!(reason.contains("Hosts or DNS configuration modification detected"));
+
+    let output = run_hook(
+        &parsed,
+        r#"{"toolName":"edit","toolArgs":"{\"filePath\":\"/etc/resolv.conf\",\"newString\":\"nameserver 1.2.3.4\"}"}"#,
+    )
+    .unwrap();
+    assert_eq!(output["permissionDecision"], "deny");
+
+    assert!(
+        run_hook(
+            &parsed,
+            r#"{"toolName":"bash","toolArgs":"{\"command\":\"cat /etc/hosts\"}"}"#,
+        )
+        .is_none()
+    );
+}
+
+#[test]
+fn copilot_pre_tool_use_denies_publish() {
+    let parsed = ParsedCli {
+        provider: Provider::Copilot,
+        event: Event::PreToolUse,
+        options: parse_options(&["copilot", "pre-tool-use", "--deny-publish"]),
+    };
+
+    let output = run_hook(
+        &parsed,
+        r#"{"toolName":"bash","toolArgs":"{\"command\":\"cargo publish --dry-run\"}"}"#,
+    )
+    .unwrap();
+    assert_eq!(
+        output["permissionDecision"],
+        Value::String("deny".to_string())
+    );
+
+    let parsed = ParsedCli {
+        options: parse_options(&[
+            "copilot",
+            "pre-tool-use",
+            "--deny-publish",
+            "--allow-publish-dry-run",
+        ]),
+        ..parsed
+    };
+    assert!(
+        run_hook(
+            &parsed,
+            r#"{"toolName":"bash","toolArgs":"{\"command\":\"npm publish --dry-run\"}"}"#,
+        )
+        .is_none()
+    );
+    assert!(
+        run_hook(
+            &parsed,
+            r#"{"toolName":"bash","toolArgs":"{\"command\":\"npm publish\"}"}"#,
+        )
+        .is_some()
+    );
+}
+
+#[test]
+fn parse_cli_rejects_allow_publish_dry_run_without_deny_publish() {
+    let result = parse_cli(
+        ["claude", "pre-tool-use", "--allow-publish-dry-run"]
+            .into_iter()
+            .map(String::from),
+    );
+
+    assert!(result.is_err());
+}
+
+#[test]
+fn codex_pre_tool_use_checks_base64_encoded_commands() {
+    let parsed = ParsedCli {
+        provider: Provider::Codex,
+        event: Event::PreToolUse,
+        options: CliOptions {
+            bash_permissions: BashPermissionOptions {
+                block_rm: true,
+                ..BashPermissionOptions::default()
+            },
+            ..CliOptions::default()
+        },
+    };
+
+    let output = run_hook(
+        &parsed,
+        r#"{"cwd":"/repo","tool_name":"Bash","tool_input":{"command":"echo cm0gLXJmIC8= | base64 -d | sh"}}"#,
+    )
+    .unwrap();
+
+    let reason = output["hookSpecificOutput"]["permissionDecisionReason"]
+        .as_str()
+        .unwrap();
+    assert!(reason.starts_with("Base64-encoded command `rm -rf /` was denied: rm is forbidden."));
+}
+
+#[test]
+fn validate_hook_input_reports_missing_tool_name() {
+    let err = hooks::validate_hook_input(Provider::Claude, r#"{"tool_input":{"command":"ls"}}"#)
+        .unwrap_err();
+    assert_eq!(err, hooks::HookInputError::MissingField("tool_name"));
+
+    let output: Value = serde_json::from_str(&err.to_json()).unwrap();
+    assert_eq!(output["error"]["kind"], "missing_field");
+    assert_eq!(output["error"]["field"], "tool_name");
+    assert_eq!(
+        output["error"]["message"],
+        "hook input is missing required field `tool_name`"
+    );
+}
+
+#[test]
+fn validate_hook_input_accepts_provider_field_names() {
+    assert!(hooks::validate_hook_input(Provider::Copilot, r#"{"toolName":"bash"}"#).is_ok());
+    assert!(hooks::validate_hook_input(Provider::Codex, r#"{"tool_name":"shell"}"#).is_ok());
+    assert_eq!(
+        hooks::validate_hook_input(Provider::Copilot, r#"{"toolName":""}"#),
+        Err(hooks::HookInputError::MissingField("toolName"))
+    );
+}
+
+#[test]
+fn validate_hook_input_rejects_malformed_json() {
+    assert!(matches!(
+        hooks::validate_hook_input(Provider::Claude, "{not json"),
+        Err(hooks::HookInputError::InvalidJson(_))
+    ));
+    assert_eq!(
+        hooks::validate_hook_input(Provider::Claude, "[]"),
+        Err(hooks::HookInputError::NotAnObject)
+    );
+}
+
+#[test]
+fn copilot_pre_tool_use_mode_overrides_decision() {
+    let parsed = ParsedCli {
+        provider: Provider::Copilot,
+        event: Event::PreToolUse,
+        options: parse_options(&[
+            "copilot",
+            "pre-tool-use",
+            "--block-rm",
+            "--deny-rust-allow",
+            "--deny-destructive-find",
+            "--mode",
+            "ask",
+            "--rm-mode",
+            "deny",
+            "--rust-allow-mode",
+            "warn",
+        ]),
+    };
+
+    let output = run_hook(
+        &parsed,
+        r#"{"toolName":"bash","toolArgs":"{\"command\":\"rm -rf build\"}"}"#,
+    )
+    .unwrap();
+    assert_eq!(output["permissionDecision"], "deny");
+
+    let output = run_hook(
+        &parsed,
+        r#"{"toolName":"bash","toolArgs":"{\"command\":\"find . -delete\"}"}"#,
+    )
+    .unwrap();
+    assert_eq!(output["permissionDecision"], "ask");
+
+    assert!(
+        run_hook(
+            &parsed,
+            r##"{"toolName":"edit","toolArgs":"{\"filePath\":\"src/main.rs\",\"content\":\"#[allow(dead_code)]\"}"}"##,
+        )
+        .is_none()
+    );
+}
+
+#[test]
+fn copilot_pre_tool_use_warn_mode_continues_to_later_checks() {
+    let parsed = ParsedCli {
+        provider: Provider::Copilot,
+        event: Event::PreToolUse,
+        options: parse_options(&[
+            "copilot",
+            "pre-tool-use",
+            "--block-rm",
+            "--deny-destructive-find",
+            "--rm-mode",
+            "warn",
+            "--mode",
+            "deny",
+        ]),
+    };
+
+    let output = run_hook(
+        &parsed,
+        r#"{"toolName":"bash","toolArgs":"{\"command\":\"rm x; find / -delete\"}"}"#,
+    )
+    .unwrap();
+    assert_eq!(output["permissionDecision"], "deny");
+    assert!(
+        output["permissionDecisionReason"]
+            .as_str()
+            .unwrap()
+            .contains("Destructive find command detected")
+    );
+
+    assert!(
+        run_hook(
+            &parsed,
+            r#"{"toolName":"bash","toolArgs":"{\"command\":\"rm -rf build\"}"}"#,
+        )
+        .is_none()
+    );
+}
+
+#[test]
+fn copilot_pre_tool_use_mode_follows_encoded_check() {
+    let parsed = ParsedCli {
+        provider: Provider::Copilot,
+        event: Event::PreToolUse,
+        options: parse_options(&["copilot", "pre-tool-use", "--block-rm", "--rm-mode", "warn"]),
+    };
+
+    assert!(
+        run_hook(
+            &parsed,
+            r#"{"toolName":"bash","toolArgs":"{\"command\":\"echo cm0gLXJmIC8= | base64 -d | sh\"}"}"#,
+        )
+        .is_none()
+    );
+}
+
+#[test]
+fn parse_cli_rejects_invalid_or_unsupported_modes() {
+    let parse = |args: &[&str]| parse_cli(args.iter().map(|arg| (*arg).to_string()));
+
+    assert!(parse(&["copilot", "pre-tool-use", "--mode", "block"]).is_err());
+    assert!(parse(&["copilot", "pre-tool-use", "--rm-mode"]).is_err());
+    assert!(parse(&["claude", "pre-tool-use", "--mode", "ask"]).is_err());
+    assert!(matches!(
+        parse(&["copilot", "pre-tool-use", "--dangerous-paths-mode", "warn"]),
+        Ok(ParseCliResult::Run(_))
+    ));
+}
+
+#[cfg(feature = "serve")]
+#[test]
+fn parse_cli_accepts_serve_with_port() {
+    let result = parse_cli(
+        [
+            "serve",
+            "claude",
+            "pre-tool-use",
+            "--port",
+            "9000",
+            "--deny-rust-allow",
+        ]
+        .into_iter()
+        .map(String::from),
+    );
+    let Ok(ParseCliResult::Serve(parsed, serve_options)) = result else {
+        panic!("expected serve to parse");
+    };
+    assert_eq!(serve_options.port, 9000);
+    assert_eq!(
+        serve_options.cache_ttl,
+        serve::ServeOptions::default().cache_ttl
+    );
+    assert_eq!(parsed.provider, Provider::Claude);
+    assert!(parsed.options.rust_edits.deny_rust_allow);
+
+    assert!(parse_cli(["serve", "claude"].into_iter().map(String::from)).is_err());
+    assert!(
+        parse_cli(
+            ["serve", "claude", "pre-tool-use", "--port", "http"]
+                .into_iter()
+                .map(String::from)
+        )
+        .is_err()
+    );
+}
+
+#[cfg(feature = "serve")]
+#[test]
+fn serve_responds_like_cli() {
+    use axum::http::StatusCode;
+
+    let parsed = ParsedCli {
+        provider: Provider::Copilot,
+        event: Event::PreToolUse,
+        options: parse_options(&["copilot", "pre-tool-use", "--block-rm"]),
+    };
+
+    let (status, body) = serve::respond(
+        &parsed,
+        r#"{"toolName":"bash","toolArgs":"{\"command\":\"rm -rf build\"}"}"#,
+    );
+    assert_eq!(status, StatusCode::OK);
+    let output: Value = serde_json::from_str(&body).unwrap();
+    assert_eq!(output["permissionDecision"], "deny");
+
+    let (status, body) = serve::respond(
+        &parsed,
+        r#"{"toolName":"bash","toolArgs":"{\"command\":\"ls\"}"}"#,
+    );
+    assert_eq!((status, body.as_str()), (StatusCode::OK, ""));
+
+    let (status, body) = serve::respond(&parsed, r#"{"toolArgs":"{}"}"#);
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert!(body.contains("missing_field"));
+}
+
+#[cfg(feature = "serve")]
+#[test]
+fn parse_cli_accepts_serve_cache_flags() {
+    let result = parse_cli(
+        [
+            "serve",
+            "codex",
+            "pre-tool-use",
+            "--cache-ttl-ms",
+            "250",
+            "--cache-size",
+            "0",
+        ]
+        .into_iter()
+        .map(String::from),
+    );
+    let Ok(ParseCliResult::Serve(_, serve_options)) = result else {
+        panic!("expected serve to parse");
+    };
+    assert_eq!(
+        serve_options.cache_ttl,
+        std::time::Duration::from_millis(250)
+    );
+    assert_eq!(serve_options.cache_size, 0);
+
+    assert!(
+        parse_cli(
+            ["serve", "codex", "pre-tool-use", "--cache-size", "-1"]
+                .into_iter()
+                .map(String::from)
+        )
+        .is_err()
+    );
+}
+
+#[cfg(feature = "serve")]
+#[test]
+fn serve_response_cache_expires_and_evicts() {
+    use std::num::NonZeroUsize;
+    use std::time::Duration;
+
+    let cache = serve::ResponseCache::new(NonZeroUsize::new(1).unwrap(), Duration::from_mins(1));
+    let first = serve::ResponseCache::key(r#"{"tool_name":"Bash"}"#);
+    let second = serve::ResponseCache::key(r#"{"tool_name":"Edit"}"#);
+    assert_ne!(first, second);
+
+    cache.insert(first, "deny".to_string());
+    assert_eq!(cache.get(first).as_deref(), Some("deny"));
+
+    cache.insert(second, String::new());
+    assert_eq!(cache.get(first), None);
+    assert_eq!(cache.get(second).as_deref(), Some(""));
+
+    let expired = serve::ResponseCache::new(NonZeroUsize::new(1).unwrap(), Duration::ZERO);
+    expired.insert(first, "deny".to_string());
+    assert_eq!(expired.get(first), None);
+}
+
+#[cfg(not(feature = "serve"))]
+#[test]
+fn parse_cli_rejects_serve_without_feature() {
+    let result = parse_cli(
+        ["serve", "claude", "pre-tool-use"]
+            .into_iter()
+            .map(String::from),
+    );
+    assert!(matches!(result, Err(message) if message.contains("`serve` feature")));
+}
+
+#[cfg(windows)]
+#[test]
+fn codex_pre_tool_use_denies_disk_destructive() {
+    let parsed = ParsedCli {
+        provider: Provider::Codex,
+        event: Event::PreToolUse,
+        options: parse_options(&["codex", "pre-tool-use", "--deny-disk-destructive"]),
+    };
+
+    let output = run_hook(
+        &parsed,
+        r#"{"cwd":"C:\\repo","tool_name":"Bash","tool_input":{"command":"Format-Volume -DriveLetter D -Confirm:$false"}}"#,
+    )
+    .unwrap();
+    let reason = output["hookSpecificOutput"]["permissionDecisionReason"]
+        .as_str()
+        .unwrap();
+    assert!(reason.contains("Disk-destructive command detected: Format-Volume targeting 'D'"));
+
+    assert!(
+        run_hook(
+            &parsed,
+            r#"{"cwd":"C:\\repo","tool_name":"Bash","tool_input":{"command":"Get-Volume -DriveLetter D"}}"#,
+        )
+        .is_none()
+    );
+}
@@ -0,0 +1,20 @@
+//! Feed arbitrary bytes to the comment/string scanner and make sure it never panics.
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|data: &[u8]| {
+    let content = String::from_utf8_lossy(data);
+
+    // The scanner slices `content` at `match_start`, so probe every char boundary
+    // (including the end of the input) rather than arbitrary byte offsets.
+    for match_start in content
+        .char_indices()
+        .map(|(idx, _)| idx)
+        .chain(std::iter::once(content.len()))
+    {
+        let _ = agent_hooks::fuzz_is_in_comment_or_string(&content, match_start);
+    }
+
+    let _ = agent_hooks::check_rust_allow_attributes(&content);
+});
@@ -4,7 +4,11 @@
 //! any AI coding agent (Claude Code, `OpenCode`, etc.) to implement safety hooks.
 
 use regex::Regex;
-use std::sync::LazyLock;
+use std::collections::HashMap;
+use std::ops::Range;
+use std::path::PathBuf;
+use std::sync::{LazyLock, Mutex, PoisonError};
+use std::time::SystemTime;
 
 // ============================================================================
 // rm command detection
@@ -12,38 +16,159 @@ use std::sync::LazyLock;
 
 #[cfg(not(windows))]
 static RM_PATTERN: LazyLock<Regex> = LazyLock::new(|| {
-    // Match: rm command (direct) or xargs rm/rmdir (piped)
+    // Match: rm command (direct) or xargs rm/rmdir (piped), allowing xargs options
+    // before the command, including a separate value for `-I FILE`, `-n 1`, etc.
+    // The trailing `(\s|$)` stands in for a lookahead (unsupported by `regex`), so
+    // commands that merely start with `rm` (`rmmod`, `rman`, `rmpkg`) don't match.
     Regex::new(
-        r"(^|[;&|()]\s*)(sudo\s+)?(command\s+)?(\\)?(\S*/)?(rm|xargs\s+(sudo\s+)?(rm|rmdir))(\s|$)",
+        r"(^|[;&|()]\s*)(sudo\s+)?(command\s+)?(\\)?(\S*/)?(rm|xargs(\s+(-[IEdLnPsa]\s+\S+|-\S+))*\s+(sudo\s+)?(rm|rmdir))(\s|$)",
     )
     .unwrap()
 });
 
 #[cfg(windows)]
 static RM_PATTERN: LazyLock<Regex> = LazyLock::new(|| {
-    // Match: rm/del/rd/rmdir/remove-item command (direct) or xargs rm/rmdir (piped)
+    // Match: rm/del/erase/rd/rmdir/remove-item/ri command (direct, piped, or inside a
+    // PowerShell script block such as `% { Remove-Item $_ }`) or xargs rm/rmdir (piped,
+    // optionally after xargs options).
+    // Remove-Item/ri with an explicit -Path/-LiteralPath (or their -PSPath/-LP aliases)
+    // also matches inside quotes, e.g. `powershell -Command "Remove-Item -LiteralPath x"`.
     Regex::new(
-        r"(?i)(^|[;&|()]\s*)(sudo\s+)?(command\s+)?(\\)?(\S*[\\/])?(rm|del|rd|rmdir|remove-item|xargs\s+(sudo\s+)?(rm|rmdir))(\s|$)",
+        r#"(?i)(^|[;&|(){]\s*)(sudo\s+)?(command\s+)?(\\)?(\S*[\\/])?(rm|ri|del|erase|rd|rmdir|remove-item|xargs(\s+(-[IEdLnPsa]\s+\S+|-\S+))*\s+(sudo\s+)?(rm|rmdir))(\s|$)|(^|[\s"'{(;&|])(remove-item|ri)\s+([^;&|]*\s)?-(path|literalpath|pspath|lp)\b"#,
     )
     .unwrap()
 });
 
+/// Opener of a heredoc: `<<EOF`, `<<-EOF`, `<< 'EOF'`, or `<<"EOF"`. Here-strings
+/// (`<<<`) never match, since a delimiter must follow the `<<`.
+static HEREDOC_PATTERN: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r#"(?:^|[^<])(?P<op><<)(?P<strip>-)?\s*['"]?(?P<delim>[A-Za-z_][A-Za-z0-9_]*)['"]?"#)
+        .unwrap()
+});
+
+/// A shell reading the heredoc on its stdin (`bash <<EOF`, `sudo sh -s <<EOF`), so the
+/// body runs as commands. Matched against the text before the heredoc opener.
+static HEREDOC_SHELL_PATTERN: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(^|[;&|()]\s*)(sudo\s+)?(\S*/)?(sh|bash|zsh|dash|ksh)(\s+-\S+)*\s*$").unwrap()
+});
+
+/// A heredoc piped into a shell (`cat <<EOF | bash`). Matched against the rest of the
+/// opener's line.
+static HEREDOC_PIPE_TO_SHELL_PATTERN: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"\|\s*(sudo\s+)?(\S*/)?(sh|bash|zsh|dash|ksh)(\s|$)").unwrap());
+
+/// A heredoc body and whether a shell executes it.
+struct Heredoc<'a> {
+    feeds_shell: bool,
+    body: &'a str,
+}
+
+/// Split out the heredocs of a command. A body runs from the line after its opener to
+/// a line holding only the delimiter (after leading tabs for `<<-`), or to the end of
+/// the command if that line is missing.
+fn find_heredocs(cmd: &str) -> Vec<Heredoc<'_>> {
+    let mut heredocs = Vec::new();
+    let mut pos = 0;
+    while let Some(caps) = HEREDOC_PATTERN.captures_at(cmd, pos) {
+        let opener = caps.get(0).expect("capture group 0 is always present");
+        let op_start = caps.name("op").expect("`op` always participates").start();
+        let line_start = cmd[..op_start].rfind('\n').map_or(0, |i| i + 1);
+        let Some(line_end) = cmd[opener.end()..].find('\n').map(|i| opener.end() + i) else {
+            break;
+        };
+        let feeds_shell = HEREDOC_SHELL_PATTERN.is_match(&cmd[line_start..op_start])
+            || HEREDOC_PIPE_TO_SHELL_PATTERN.is_match(&cmd[opener.end()..line_end]);
+
+        let strip_tabs = caps.name("strip").is_some();
+        let delim = &caps["delim"];
+        let body_start = line_end + 1;
+        let mut body_end = cmd.len();
+        pos = cmd.len();
+        let mut offset = body_start;
+        for line in cmd[body_start..].split_inclusive('\n') {
+            let content = line.trim_end_matches(['\n', '\r']);
+            let content = if strip_tabs {
+                content.trim_start_matches('\t')
+            } else {
+                content
+            };
+            if content == delim {
+                body_end = offset;
+                pos = offset + line.len();
+                break;
+            }
+            offset += line.len();
+        }
+        heredocs.push(Heredoc {
+            feeds_shell,
+            body: &cmd[body_start..body_end],
+        });
+    }
+    heredocs
+}
+
+/// Whether any line of a heredoc body is an rm command.
+fn heredoc_has_rm(body: &str) -> bool {
+    body.lines()
+        .any(|line| RM_PATTERN.is_match(line.trim_start()))
+}
+
 /// Check if a command contains an rm (or equivalent) command.
 ///
+/// Heredoc bodies are checked line by line when a shell runs them (`bash <<EOF`,
+/// `cat <<EOF | sh`). Bodies passed to other commands, such as `cat > script.sh <<EOF`,
+/// are data and only checked by [`is_rm_command_with_heredocs`].
+///
 /// Returns `true` if the command should be blocked.
 #[must_use]
 pub fn is_rm_command(cmd: &str) -> bool {
     RM_PATTERN.is_match(cmd)
+        || find_heredocs(cmd)
+            .iter()
+            .any(|heredoc| heredoc.feeds_shell && heredoc_has_rm(heredoc.body))
+}
+
+/// Like [`is_rm_command`], but also checks heredoc bodies that no shell runs, e.g. a
+/// script written with `cat > cleanup.sh <<EOF` to be run later.
+#[must_use]
+pub fn is_rm_command_with_heredocs(cmd: &str) -> bool {
+    RM_PATTERN.is_match(cmd)
+        || find_heredocs(cmd)
+            .iter()
+            .any(|heredoc| heredoc_has_rm(heredoc.body))
 }
 
 // ============================================================================
 // Destructive find command detection
 // ============================================================================
 
+/// Spotlight's `mdfind` lists files like `find`, so its output piped to `rm` is just as
+/// destructive. Checked before the `find` patterns, which would also match `mdfind`.
+#[cfg(target_os = "macos")]
+const MDFIND_DESTRUCTIVE_PATTERNS: &[(&str, &str)] = &[
+    (
+        r"mdfind\s+.*\|\s*(sudo\s+)?xargs\s+(-\S+\s+)*(sudo\s+)?(rm|rmdir)\b",
+        "mdfind piped to xargs rm/rmdir",
+    ),
+    // Whole-disk results fed into another command can reach far outside the project.
+    (
+        r"mdfind\s+(.*\s)?-onlyin\s+/(\s.*)?\|",
+        "mdfind -onlyin / piped to another command",
+    ),
+];
+
+#[cfg(all(not(windows), not(target_os = "macos")))]
+const MDFIND_DESTRUCTIVE_PATTERNS: &[(&str, &str)] = &[];
+
 #[cfg(not(windows))]
 static DESTRUCTIVE_REGEXES: LazyLock<Vec<(Regex, &'static str)>> = LazyLock::new(|| {
-    [
-        (r"find\s+.*-delete", "find with -delete option"),
+    MDFIND_DESTRUCTIVE_PATTERNS.iter().copied().chain([
+        // `-delete` only counts as a standalone flag: quoted arguments are skipped
+        // whole, so `find . -name "-delete"` (a file literally named so) is safe.
+        (
+            r#"find\s+((?:[^"']|"[^"]*"|'[^']*')*\s)?-delete([\s;&|)]|$)"#,
+            "find with -delete option",
+        ),
         (
             r"find\s+.*-exec\s+(sudo\s+)?(rm|rmdir)\s",
             "find with -exec rm/rmdir",
@@ -53,34 +178,81 @@ static DESTRUCTIVE_REGEXES: LazyLock<Vec<(Regex, &'static str)>> = LazyLock::new
             "find with -execdir rm/rmdir",
         ),
         (
-            r"find\s+.*\|\s*(sudo\s+)?xargs\s+(sudo\s+)?(rm|rmdir)",
+            r"find\s+.*\|\s*(sudo\s+)?xargs(\s+(-[IEdLnPsa]\s+\S+|-\S+))*\s+(sudo\s+)?(rm|rmdir)",
             "find piped to xargs rm/rmdir",
         ),
         (r"find\s+.*-exec\s+(sudo\s+)?mv\s", "find with -exec mv"),
+        (r"find\s+.*-execdir\s+(sudo\s+)?mv\s", "find with -execdir mv"),
+        (
+            r"find\s+.*-exec(dir)?\s+(sudo\s+)?truncate\s",
+            "find with -exec truncate",
+        ),
+        (
+            r"find\s+.*-exec(dir)?\s+(sudo\s+)?shred\s",
+            "find with -exec shred",
+        ),
+        // `cp` is only destructive when many found files land on a system
+        // directory, the home directory itself, or a dotfile in it.
+        (
+            r"find\s+.*-exec\s+(sudo\s+)?cp\s+(.*\s)?(/|/(etc|usr|bin|sbin|lib\w*|boot|System)(/\S*)?|(~|\$HOME)/?|(~|\$HOME)/\.\S*)(\s+\\?;|\s*$)",
+            "find with -exec cp to a dangerous path",
+        ),
+        (
+            r"find\s+.*-execdir\s+(sudo\s+)?cp\s+(.*\s)?(/|/(etc|usr|bin|sbin|lib\w*|boot|System)(/\S*)?|(~|\$HOME)/?|(~|\$HOME)/\.\S*)(\s+\\?;|\s*$)",
+            "find with -execdir cp to a dangerous path",
+        ),
         (
             r"find\s+.*-ok\s+(sudo\s+)?(rm|rmdir)\s",
             "find with -ok rm/rmdir",
         ),
-    ]
-    .into_iter()
+        // Best-effort: `FILES=$(find ...); rm $FILES` hides the find output
+        // behind a variable, so flag `rm` with a bare `$VAR` argument.
+        (
+            r#"(?s)find\s.*[;&|\n]\s*((do|then|else)\s+)?(sudo\s+)?rm\s+(-\S+\s+)*"?\$\{?[A-Za-z_][A-Za-z0-9_]*\}?"?(\s|[;&|]|$)"#,
+            "find via variable",
+        ),
+    ])
     .map(|(pattern, desc)| (Regex::new(&format!("(?i){pattern}")).unwrap(), desc))
     .collect()
 });
 
 #[cfg(windows)]
 static DESTRUCTIVE_REGEXES: LazyLock<Vec<(Regex, &'static str)>> = LazyLock::new(|| {
-    let pattern = r"\|\s*(move|move-item)\b";
-    let desc = "piped to move/move-item";
-    vec![(Regex::new(&format!("(?i){pattern}")).unwrap(), desc)]
+    [
+        (
+            r"\b(get-childitem|gci|dir|ls)\b.*\|\s*(remove-item|ri|rm|del|erase)\b",
+            "Get-ChildItem piped to Remove-Item",
+        ),
+        (
+            r"\b(get-childitem|gci|dir|ls)\b.*\|\s*(foreach-object|%)\s*\{[^}]*(remove-item|\.delete\()",
+            "Get-ChildItem piped to ForEach-Object deleting each item",
+        ),
+        (r"\|\s*(move|move-item)\b", "piped to move/move-item"),
+    ]
+    .into_iter()
+    .map(|(pattern, desc)| (Regex::new(&format!("(?i){pattern}")).unwrap(), desc))
+    .collect()
 });
 
-#[cfg(not(windows))]
-static FIND_CHECK: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"(^|[;&|()]\s*)find\s").unwrap());
+#[cfg(all(not(windows), not(target_os = "macos")))]
+static FIND_CHECK: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(^|[;&|()`]\s*)find\s").unwrap());
+
+#[cfg(target_os = "macos")]
+static FIND_CHECK: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(^|[;&|()`]\s*)(md)?find\s").unwrap());
 
 /// Check if a command is a destructive find command.
 ///
+/// On macOS, Spotlight's `mdfind` piped to `xargs rm` is detected as well.
+///
 /// Returns `Some(description)` if the command is destructive and should be confirmed,
 /// or `None` if the command is safe.
+///
+/// On Unix, `find` output stored in a variable and later passed to `rm` as a bare
+/// `$VARIABLE` argument is reported as `"find via variable"`. This is a heuristic:
+/// variables are not tracked, so any `rm $VAR` after a `find` is flagged, while
+/// indirection through arrays, functions, or separate commands is not detected.
 #[must_use]
 pub fn check_destructive_find(cmd: &str) -> Option<&'static str> {
     #[cfg(not(windows))]
@@ -140,79 +312,203 @@ static RUST_ALLOW_PATTERN: LazyLock<Regex> =
 static RUST_EXPECT_PATTERN: LazyLock<Regex> =
     LazyLock::new(|| Regex::new(r"#!?\[expect\s*\(").unwrap());
 
-/// Check if a position in the content is inside a line comment or string literal.
-fn is_in_comment_or_string(content: &str, match_start: usize) -> bool {
-    let before = &content[..match_start];
+static RUST_OUTER_ALLOW_PATTERN: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"#\[allow\s*\(").unwrap());
 
-    // Check if in line comment (// ...)
-    let line_start = before.rfind('\n').map_or(0, |p| p + 1);
-    let current_line = &before[line_start..];
-    if current_line.contains("//") {
-        return true;
-    }
+static RUST_OUTER_EXPECT_PATTERN: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"#\[expect\s*\(").unwrap());
 
-    // Check if inside a block comment (/* ... */)
-    let block_open = before.matches("/*").count();
-    let block_close = before.matches("*/").count();
-    if block_open > block_close {
-        return true;
-    }
+static RUST_INNER_ALLOW_PATTERN: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"#!\[allow\s*\(").unwrap());
 
-    // Check if inside a string literal
-    let mut in_raw_string = false;
+static RUST_INNER_EXPECT_PATTERN: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"#!\[expect\s*\(").unwrap());
+
+static RUST_CFG_ATTR_PATTERN: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"#!?\[cfg_attr\s*\(").unwrap());
+
+/// Start of a `quote!` / `quote_spanned!` invocation, up to and including its opening delimiter.
+static RUST_QUOTE_MACRO_PATTERN: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"\bquote(?:_spanned)?\s*!\s*[({\[]").unwrap());
+
+/// An attribute wrapped by `cfg_attr` that can suppress lints, matched at the start of an argument.
+static RUST_CFG_ATTR_ITEM_PATTERN: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"^(?P<name>allow|expect|cfg_attr)\s*\(").unwrap());
+
+/// Lexical state of the scanner in `is_in_comment_or_string`.
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum ScanState {
+    Code,
+    LineComment,
+    /// Nesting depth of `/* ... */` comments.
+    BlockComment(usize),
+    Str,
+    /// Number of `#` marks that opened the raw string (`r"..."` is 0, `r##"..."##` is 2).
+    RawStr(usize),
+}
+
+/// Check if a position in the content is inside a comment or string literal.
+///
+/// Comments and strings are tracked in a single forward pass, so `//` or `/*`
+/// inside a string literal does not start a comment, and quotes inside a
+/// comment do not start a string. Doc comments (`///`, `//!`) are line comments.
+fn is_in_comment_or_string(content: &str, match_start: usize) -> bool {
+    let bytes = &content.as_bytes()[..match_start];
+    let mut state = ScanState::Code;
     let mut i = 0;
-    let bytes = before.as_bytes();
     while i < bytes.len() {
-        if in_raw_string {
-            if bytes[i] == b'"' {
-                in_raw_string = false;
-            }
-        } else {
-            if bytes[i] == b'r' && i + 1 < bytes.len() {
-                let mut j = i + 1;
-                while j < bytes.len() && bytes[j] == b'#' {
-                    j += 1;
+        let next = bytes.get(i + 1).copied();
+        match state {
+            ScanState::Code => match (bytes[i], next) {
+                (b'/', Some(b'/')) => {
+                    state = ScanState::LineComment;
+                    i += 2;
+                    continue;
                 }
-                if j < bytes.len() && bytes[j] == b'"' {
-                    in_raw_string = true;
-                    i = j + 1;
+                (b'/', Some(b'*')) => {
+                    state = ScanState::BlockComment(1);
+                    i += 2;
                     continue;
                 }
-            }
-            if bytes[i] == b'"' && (i == 0 || bytes[i - 1] != b'\\') {
-                let mut k = i + 1;
-                while k < bytes.len() {
-                    if bytes[k] == b'"' && bytes[k - 1] != b'\\' {
-                        break;
+                (b'r', Some(b'#' | b'"')) => {
+                    let mut j = i + 1;
+                    while j < bytes.len() && bytes[j] == b'#' {
+                        j += 1;
+                    }
+                    if j < bytes.len() && bytes[j] == b'"' {
+                        state = ScanState::RawStr(j - i - 1);
+                        i = j + 1;
+                        continue;
                     }
-                    k += 1;
                 }
-                if k >= bytes.len() {
-                    return true;
+                (b'"', _) => state = ScanState::Str,
+                _ => {}
+            },
+            ScanState::LineComment => {
+                if bytes[i] == b'\n' {
+                    state = ScanState::Code;
+                }
+            }
+            ScanState::BlockComment(depth) => match (bytes[i], next) {
+                (b'/', Some(b'*')) => {
+                    state = ScanState::BlockComment(depth + 1);
+                    i += 2;
+                    continue;
+                }
+                (b'*', Some(b'/')) => {
+                    state = if depth == 1 {
+                        ScanState::Code
+                    } else {
+                        ScanState::BlockComment(depth - 1)
+                    };
+                    i += 2;
+                    continue;
+                }
+                _ => {}
+            },
+            ScanState::Str => match bytes[i] {
+                // Skip escape sequences as a pair so that `\\` never escapes the
+                // following quote: a quote is escaped only by an odd run of backslashes.
+                b'\\' => {
+                    i += 2;
+                    continue;
+                }
+                b'"' => state = ScanState::Code,
+                _ => {}
+            },
+            ScanState::RawStr(hashes) => {
+                if bytes[i] == b'"'
+                    && bytes
+                        .get(i + 1..i + 1 + hashes)
+                        .is_some_and(|closing| closing.iter().all(|&b| b == b'#'))
+                {
+                    state = ScanState::Code;
+                    i += 1 + hashes;
+                    continue;
                 }
-                i = k + 1;
-                continue;
             }
         }
         i += 1;
     }
 
-    in_raw_string
+    state != ScanState::Code
+}
+
+/// Fuzzing entry point for the private comment/string scanner.
+///
+/// Only compiled under `cargo fuzz`, which sets `--cfg fuzzing`.
+#[cfg(fuzzing)]
+#[doc(hidden)]
+#[must_use]
+pub fn fuzz_is_in_comment_or_string(content: &str, match_start: usize) -> bool {
+    is_in_comment_or_string(content, match_start)
 }
 
 /// Find if there are real matches of a pattern (not in comments or strings).
+///
+/// Returns `(direct, quoted)`: whether a match was written directly in the code, and
+/// whether one sits inside one of the `quoted` ranges (see [`quote_macro_ranges`]).
 #[inline]
-fn find_real_matches(content: &str, pattern: &Regex) -> bool {
+fn find_real_matches(content: &str, pattern: &Regex, quoted: &[Range<usize>]) -> (bool, bool) {
+    let mut found = (false, false);
     for m in pattern.find_iter(content) {
-        if !is_in_comment_or_string(content, m.start()) {
-            return true;
+        if is_in_comment_or_string(content, m.start()) {
+            continue;
+        }
+        if quoted.iter().any(|range| range.contains(&m.start())) {
+            found.1 = true;
+        } else {
+            found.0 = true;
+        }
+        if found == (true, true) {
+            break;
+        }
+    }
+    found
+}
+
+/// Byte ranges of the bodies of `quote! { ... }` and `quote_spanned! { ... }` invocations,
+/// whose attributes end up in macro-generated code rather than in the file itself.
+///
+/// Delimiters are balanced without regard to string literals inside the body, so this is
+/// best effort. An unterminated body extends to the end of the content.
+fn quote_macro_ranges(content: &str) -> Vec<Range<usize>> {
+    let mut ranges: Vec<Range<usize>> = Vec::new();
+    for m in RUST_QUOTE_MACRO_PATTERN.find_iter(content) {
+        if ranges
+            .last()
+            .is_some_and(|range| range.contains(&m.start()))
+            || is_in_comment_or_string(content, m.start())
+        {
+            continue;
+        }
+        let mut depth = 0usize;
+        let mut end = content.len();
+        for (i, byte) in content.bytes().enumerate().skip(m.end() - 1) {
+            match byte {
+                b'(' | b'{' | b'[' => depth += 1,
+                b')' | b'}' | b']' => {
+                    depth -= 1;
+                    if depth == 0 {
+                        end = i + 1;
+                        break;
+                    }
+                }
+                _ => {}
+            }
         }
+        ranges.push(m.end()..end);
     }
-    false
+    ranges
 }
 
 /// Result of checking for Rust allow/expect attributes.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "snake_case")
+)]
 pub enum RustAllowCheckResult {
     /// No problematic attributes found.
     Ok,
@@ -222,23 +518,441 @@ pub enum RustAllowCheckResult {
     HasExpect,
     /// Found both #[allow(...)] and #[expect(...)] attributes.
     HasBoth,
+    /// Found only `#[cfg_attr(..., allow(...))]` attributes.
+    HasCfgAttrAllow,
+    /// Found only `#[cfg_attr(..., expect(...))]` attributes.
+    HasCfgAttrExpect,
+    /// Found only allow/expect attributes inside `quote! { ... }`, which a procedural
+    /// macro would generate at compile time.
+    HasMacroGenerated,
 }
 
-/// Check if content contains #[allow(...)] or #[expect(...)] attributes.
+impl RustAllowCheckResult {
+    /// Builds a result from direct, `cfg_attr`-wrapped, and macro-generated matches.
+    ///
+    /// The `cfg_attr` variants are only used when nothing else was found, and
+    /// `HasMacroGenerated` only when not even those were; any mix of allow and expect
+    /// attributes is reported as `HasBoth`.
+    const fn from_flags(lints: LintFlags) -> Self {
+        let any_allow = lints.allow || lints.cfg_attr_allow;
+        let any_expect = lints.expect || lints.cfg_attr_expect;
+        if any_allow && any_expect {
+            Self::HasBoth
+        } else if lints.allow {
+            Self::HasAllow
+        } else if lints.cfg_attr_allow {
+            Self::HasCfgAttrAllow
+        } else if lints.expect {
+            Self::HasExpect
+        } else if lints.cfg_attr_expect {
+            Self::HasCfgAttrExpect
+        } else if lints.macro_generated {
+            Self::HasMacroGenerated
+        } else {
+            Self::Ok
+        }
+    }
+
+    const fn lint_flags(self) -> LintFlags {
+        LintFlags {
+            allow: matches!(self, Self::HasAllow | Self::HasBoth),
+            expect: matches!(self, Self::HasExpect | Self::HasBoth),
+            cfg_attr_allow: matches!(self, Self::HasCfgAttrAllow),
+            cfg_attr_expect: matches!(self, Self::HasCfgAttrExpect),
+            macro_generated: matches!(self, Self::HasMacroGenerated),
+        }
+    }
+
+    /// Returns `true` if any allow or expect attribute was found.
+    #[must_use]
+    pub const fn has_any(self) -> bool {
+        !matches!(self, Self::Ok)
+    }
+
+    /// Returns `true` if an allow attribute (including `cfg_attr`-wrapped) was found.
+    #[must_use]
+    pub const fn has_allow(self) -> bool {
+        matches!(self, Self::HasAllow | Self::HasBoth | Self::HasCfgAttrAllow)
+    }
+
+    /// Returns `true` if an expect attribute (including `cfg_attr`-wrapped) was found.
+    #[must_use]
+    pub const fn has_expect(self) -> bool {
+        matches!(
+            self,
+            Self::HasExpect | Self::HasBoth | Self::HasCfgAttrExpect
+        )
+    }
+}
+
+/// Which kinds of lint attributes were found.
+#[derive(Debug, Clone, Copy, Default)]
+#[expect(clippy::struct_excessive_bools)]
+struct LintFlags {
+    allow: bool,
+    expect: bool,
+    cfg_attr_allow: bool,
+    cfg_attr_expect: bool,
+    macro_generated: bool,
+}
+
+impl LintFlags {
+    const fn union(self, other: Self) -> Self {
+        Self {
+            allow: self.allow || other.allow,
+            expect: self.expect || other.expect,
+            cfg_attr_allow: self.cfg_attr_allow || other.cfg_attr_allow,
+            cfg_attr_expect: self.cfg_attr_expect || other.cfg_attr_expect,
+            macro_generated: self.macro_generated || other.macro_generated,
+        }
+    }
+}
+
+impl From<RustAllowCheckResult> for bool {
+    fn from(result: RustAllowCheckResult) -> Self {
+        result.has_any()
+    }
+}
+
+/// Compact bit flags for audit logs: bit 0 is allow, bit 1 is expect, bit 2 marks
+/// the `cfg_attr`-only variants (`0b101`, `0b110`), and bit 3 is `HasMacroGenerated`.
+impl From<RustAllowCheckResult> for u8 {
+    fn from(result: RustAllowCheckResult) -> Self {
+        match result {
+            RustAllowCheckResult::Ok => 0b000,
+            RustAllowCheckResult::HasAllow => 0b001,
+            RustAllowCheckResult::HasExpect => 0b010,
+            RustAllowCheckResult::HasBoth => 0b011,
+            RustAllowCheckResult::HasCfgAttrAllow => 0b101,
+            RustAllowCheckResult::HasCfgAttrExpect => 0b110,
+            RustAllowCheckResult::HasMacroGenerated => 0b1000,
+        }
+    }
+}
+
+impl From<RustAllowCheckResult> for u32 {
+    fn from(result: RustAllowCheckResult) -> Self {
+        Self::from(u8::from(result))
+    }
+}
+
+/// Error returned when converting bits that do not encode a [`RustAllowCheckResult`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct InvalidRustAllowBitsError {
+    /// The rejected bit pattern.
+    pub bits: u8,
+}
+
+impl std::fmt::Display for InvalidRustAllowBitsError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "invalid rust allow check bits: {:#05b}", self.bits)
+    }
+}
+
+impl std::error::Error for InvalidRustAllowBitsError {}
+
+impl TryFrom<u8> for RustAllowCheckResult {
+    type Error = InvalidRustAllowBitsError;
+
+    fn try_from(bits: u8) -> Result<Self, Self::Error> {
+        match bits {
+            0b000 => Ok(Self::Ok),
+            0b001 => Ok(Self::HasAllow),
+            0b010 => Ok(Self::HasExpect),
+            0b011 => Ok(Self::HasBoth),
+            0b101 => Ok(Self::HasCfgAttrAllow),
+            0b110 => Ok(Self::HasCfgAttrExpect),
+            0b1000 => Ok(Self::HasMacroGenerated),
+            _ => Err(InvalidRustAllowBitsError { bits }),
+        }
+    }
+}
+
+/// Result of checking for Rust allow/expect attributes, split by attribute style.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct RustAllowCheckDetails {
+    /// Outer (item-level) attributes: `#[allow(...)]`, `#[expect(...)]`.
+    pub outer: RustAllowCheckResult,
+    /// Inner (module/crate-level) attributes: `#![allow(...)]`, `#![expect(...)]`.
+    pub inner: RustAllowCheckResult,
+}
+
+impl RustAllowCheckDetails {
+    /// Combines outer and inner results into a single result.
+    #[must_use]
+    pub const fn combined(self) -> RustAllowCheckResult {
+        RustAllowCheckResult::from_flags(self.outer.lint_flags().union(self.inner.lint_flags()))
+    }
+}
+
+/// Check content for #[allow(...)] / #[expect(...)] and #![allow(...)] / #![expect(...)]
+/// attributes, tracking outer and inner attributes separately.
 ///
 /// This function ignores attributes in comments and string literals.
+#[must_use]
+pub fn check_rust_allow_attributes_detailed(content: &str) -> RustAllowCheckDetails {
+    let (cfg_attr_outer, _) = find_cfg_attr_lints(content);
+    let quoted = quote_macro_ranges(content);
+    let (allow, quoted_allow) = find_real_matches(content, &RUST_OUTER_ALLOW_PATTERN, &quoted);
+    let (expect, quoted_expect) = find_real_matches(content, &RUST_OUTER_EXPECT_PATTERN, &quoted);
+    RustAllowCheckDetails {
+        outer: RustAllowCheckResult::from_flags(LintFlags {
+            allow,
+            expect,
+            macro_generated: quoted_allow || quoted_expect,
+            ..cfg_attr_outer
+        }),
+        inner: check_rust_crate_allow_attributes(content),
+    }
+}
+
+/// Check if content contains #[allow(...)] or #[expect(...)] attributes.
+///
+/// `cfg_attr`-wrapped attributes such as `#[cfg_attr(test, allow(dead_code))]` are
+/// detected as well. Attributes inside `quote! { ... }` are reported separately as
+/// [`RustAllowCheckResult::HasMacroGenerated`], since a procedural macro would emit
+/// them. This function ignores attributes in comments and string literals.
 /// It does NOT check if the file is a Rust file - the caller should do that.
 #[must_use]
 pub fn check_rust_allow_attributes(content: &str) -> RustAllowCheckResult {
-    let has_allow = find_real_matches(content, &RUST_ALLOW_PATTERN);
-    let has_expect = find_real_matches(content, &RUST_EXPECT_PATTERN);
+    check_rust_allow_attributes_detailed(content).combined()
+}
+
+/// Check if content contains inner #![allow(...)] or #![expect(...)] attributes.
+///
+/// Unlike [`check_rust_allow_attributes`], outer `#[allow(...)]` attributes are not
+/// reported, for projects that permit item-level suppression but not module- or
+/// crate-wide suppression.
+#[must_use]
+pub fn check_rust_crate_allow_attributes(content: &str) -> RustAllowCheckResult {
+    let (_, cfg_attr_inner) = find_cfg_attr_lints(content);
+    let quoted = quote_macro_ranges(content);
+    let (allow, quoted_allow) = find_real_matches(content, &RUST_INNER_ALLOW_PATTERN, &quoted);
+    let (expect, quoted_expect) = find_real_matches(content, &RUST_INNER_EXPECT_PATTERN, &quoted);
+    RustAllowCheckResult::from_flags(LintFlags {
+        allow,
+        expect,
+        macro_generated: quoted_allow || quoted_expect,
+        ..cfg_attr_inner
+    })
+}
+
+/// Build the message shown when an edit adds lint suppression attributes.
+///
+/// With `prefer_expect`, only `allow` attributes are rejected and the message points at
+/// `#[expect(...)]` instead; attributes generated by `quote!` are rejected either way.
+/// `additional_context` is appended after a space. Returns `None` when `result` does not
+/// need to be rejected.
+#[must_use]
+pub fn build_rust_allow_denial_reason(
+    result: RustAllowCheckResult,
+    prefer_expect: bool,
+    additional_context: Option<&str>,
+) -> Option<String> {
+    let base_message = if prefer_expect && result != RustAllowCheckResult::HasMacroGenerated {
+        result.has_allow().then_some(
+            "Adding #[allow(...)] or #![allow(...)] attributes is not permitted. Use #[expect(...)] instead, which will warn when the lint is no longer triggered.",
+        )
+    } else {
+        match result {
+            RustAllowCheckResult::Ok => None,
+            RustAllowCheckResult::HasBoth => Some(
+                "Adding #[allow(...)] or #[expect(...)] attributes is not permitted. Fix the underlying issue instead of suppressing the warning.",
+            ),
+            RustAllowCheckResult::HasAllow => Some(
+                "Adding #[allow(...)] or #![allow(...)] attributes is not permitted. Fix the underlying issue instead of suppressing the warning.",
+            ),
+            RustAllowCheckResult::HasExpect => Some(
+                "Adding #[expect(...)] or #![expect(...)] attributes is not permitted. Fix the underlying issue instead of suppressing the warning.",
+            ),
+            RustAllowCheckResult::HasCfgAttrAllow => Some(
+                "Adding #[cfg_attr(..., allow(...))] attributes is not permitted. Fix the underlying issue instead of suppressing the warning.",
+            ),
+            RustAllowCheckResult::HasCfgAttrExpect => Some(
+                "Adding #[cfg_attr(..., expect(...))] attributes is not permitted. Fix the underlying issue instead of suppressing the warning.",
+            ),
+            RustAllowCheckResult::HasMacroGenerated => Some(
+                "Generating #[allow(...)] or #[expect(...)] attributes with quote! is not permitted. Fix the underlying issue instead of suppressing the warning.",
+            ),
+        }
+    }?;
+
+    let mut reason = base_message.to_string();
+    if let Some(extra_context) = additional_context {
+        reason.push(' ');
+        reason.push_str(extra_context);
+    }
+    Some(reason)
+}
+
+/// Classify an attribute inside `cfg_attr(...)` as allow or expect.
+///
+/// Nested `cfg_attr` attributes are followed. Returns the kind and the offset of
+/// the wrapped attribute's argument list.
+fn cfg_attr_lint_kinds(content: &str, args_start: usize) -> Vec<(AttributeKind, usize)> {
+    let (_, args) = scan_attribute_args(content, args_start);
+    let mut kinds = Vec::new();
+    // The first argument is the predicate; the rest are the wrapped attributes
+    for range in args.into_iter().skip(1) {
+        let arg = &content[range.clone()];
+        let trimmed = arg.trim_start();
+        let offset = range.start + (arg.len() - trimmed.len());
+        if let Some(caps) = RUST_CFG_ATTR_ITEM_PATTERN.captures(trimmed) {
+            let inner_start = offset + caps.get(0).map_or(0, |m| m.end());
+            match &caps["name"] {
+                "allow" => kinds.push((AttributeKind::Allow, inner_start)),
+                "expect" => kinds.push((AttributeKind::Expect, inner_start)),
+                _ => kinds.extend(cfg_attr_lint_kinds(content, inner_start)),
+            }
+        }
+    }
+    kinds
+}
+
+/// Find `cfg_attr`-wrapped allow/expect attributes, split into outer and inner attributes.
+fn find_cfg_attr_lints(content: &str) -> (LintFlags, LintFlags) {
+    let mut outer = LintFlags::default();
+    let mut inner = LintFlags::default();
+    for m in RUST_CFG_ATTR_PATTERN.find_iter(content) {
+        if is_in_comment_or_string(content, m.start()) {
+            continue;
+        }
+        let flags = if m.as_str().starts_with("#!") {
+            &mut inner
+        } else {
+            &mut outer
+        };
+        for (kind, _) in cfg_attr_lint_kinds(content, m.end()) {
+            match kind {
+                AttributeKind::Allow => flags.cfg_attr_allow = true,
+                AttributeKind::Expect => flags.cfg_attr_expect = true,
+            }
+        }
+    }
+    (outer, inner)
+}
+
+/// Kind of lint attribute found by [`check_rust_allow_attributes_spans`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "snake_case")
+)]
+pub enum AttributeKind {
+    /// `#[allow(...)]` or `#![allow(...)]`.
+    Allow,
+    /// `#[expect(...)]` or `#![expect(...)]`.
+    Expect,
+}
+
+/// Location of a lint attribute in the checked content.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct RustAttributeSpan {
+    /// Whether this is an allow or expect attribute.
+    pub kind: AttributeKind,
+    /// Byte offset of the leading `#`.
+    pub byte_start: usize,
+    /// Byte offset just past the closing `]` (or the end of the content if unterminated).
+    pub byte_end: usize,
+    /// Lint names listed in the attribute, e.g. `dead_code` or `clippy::unwrap_used`.
+    pub lint_names: Vec<String>,
+}
+
+/// Scan an attribute whose argument list starts at `args_start`.
+///
+/// Returns the byte offset just past the closing `]` and the byte ranges of the
+/// top-level comma-separated arguments. Parentheses and commas inside string
+/// literals (e.g. `reason = "a, b"`) are skipped.
+fn scan_attribute_args(content: &str, args_start: usize) -> (usize, Vec<Range<usize>>) {
+    let bytes = content.as_bytes();
+    let mut args = Vec::new();
+    let mut current = args_start;
+    let mut depth = 1;
+    let mut in_string = false;
+    let mut i = args_start;
+    while i < bytes.len() {
+        match (in_string, bytes[i]) {
+            (true, b'\\') => i += 1,
+            (_, b'"') => in_string = !in_string,
+            (false, b'(') => depth += 1,
+            (false, b')') => {
+                depth -= 1;
+                if depth == 0 {
+                    break;
+                }
+            }
+            (false, b',') if depth == 1 => {
+                args.push(current..i);
+                current = i + 1;
+            }
+            _ => {}
+        }
+        i += 1;
+    }
+    let args_end = i.min(bytes.len());
+    args.push(current..args_end);
+    while i < bytes.len() && bytes[i] != b']' {
+        i += 1;
+    }
+    ((i + 1).min(bytes.len()), args)
+}
+
+/// Collect the lint names from the argument list of an allow/expect attribute.
+fn attribute_lint_names(content: &str, args_start: usize) -> Vec<String> {
+    let (_, args) = scan_attribute_args(content, args_start);
+    args.into_iter()
+        .map(|range| content[range].trim())
+        .filter(|arg| !arg.is_empty() && !arg.contains('='))
+        .map(str::to_string)
+        .collect()
+}
+
+/// Check content for #[allow(...)] and #[expect(...)] attributes and return their locations.
+///
+/// Like [`check_rust_allow_attributes`], attributes in comments and string literals
+/// are ignored, and `cfg_attr`-wrapped attributes are included (spanning the whole
+/// `#[cfg_attr(...)]`). Spans are returned in order of appearance. Arguments that are
+/// not plain lint paths (such as `reason = "..."`) are not included in `lint_names`.
+#[must_use]
+pub fn check_rust_allow_attributes_spans(content: &str) -> Vec<RustAttributeSpan> {
+    let mut spans: Vec<RustAttributeSpan> = [
+        (AttributeKind::Allow, &*RUST_ALLOW_PATTERN),
+        (AttributeKind::Expect, &*RUST_EXPECT_PATTERN),
+    ]
+    .into_iter()
+    .flat_map(|(kind, pattern)| {
+        pattern
+            .find_iter(content)
+            .filter(|m| !is_in_comment_or_string(content, m.start()))
+            .map(move |m| RustAttributeSpan {
+                kind,
+                byte_start: m.start(),
+                byte_end: scan_attribute_args(content, m.end()).0,
+                lint_names: attribute_lint_names(content, m.end()),
+            })
+    })
+    .collect();
 
-    match (has_allow, has_expect) {
-        (true, true) => RustAllowCheckResult::HasBoth,
-        (true, false) => RustAllowCheckResult::HasAllow,
-        (false, true) => RustAllowCheckResult::HasExpect,
-        (false, false) => RustAllowCheckResult::Ok,
+    for m in RUST_CFG_ATTR_PATTERN.find_iter(content) {
+        if is_in_comment_or_string(content, m.start()) {
+            continue;
+        }
+        let (byte_end, _) = scan_attribute_args(content, m.end());
+        for (kind, args_start) in cfg_attr_lint_kinds(content, m.end()) {
+            spans.push(RustAttributeSpan {
+                kind,
+                byte_start: m.start(),
+                byte_end,
+                lint_names: attribute_lint_names(content, args_start),
+            });
+        }
     }
+
+    spans.sort_by_key(|span| span.byte_start);
+    spans
 }
 
 /// Check if a file path is a Rust file.
@@ -250,30 +964,120 @@ pub fn is_rust_file(file_path: &str) -> bool {
 }
 
 // ============================================================================
-// Dangerous path detection for rm/trash/mv commands
+// Dangerous path detection for rm/trash/mv/rsync/tar/ln commands
 // ============================================================================
 
 /// Result of checking for dangerous path operations.
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct DangerousPathCheck {
     /// The dangerous path that was matched.
     pub matched_path: String,
-    /// The command type (rm, trash, mv).
+    /// The command type (rm, trash, mv, rsync, tar, ln).
     pub command_type: String,
+    /// For `mv`, which side of the move hit a dangerous path.
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    pub mv_direction: Option<MvDirection>,
+}
+
+/// The dangerous paths matched by the sources and the destination of an `mv`.
+///
+/// Moving a dangerous path away removes it, while moving onto one overwrites it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct MvDirection {
+    /// The dangerous path matched by a source argument.
+    pub from: Option<String>,
+    /// The dangerous path matched by the destination (last) argument.
+    pub to: Option<String>,
+}
+
+/// Look up a user's home directory in `/etc/passwd`.
+#[cfg(unix)]
+fn user_home_dir(user: &str) -> Option<String> {
+    let passwd = std::fs::read_to_string("/etc/passwd").ok()?;
+    passwd.lines().find_map(|line| {
+        // name:password:uid:gid:gecos:home:shell
+        let mut fields = line.split(':');
+        if fields.next()? != user {
+            return None;
+        }
+        fields.nth(4).map(str::to_string)
+    })
 }
 
 /// Expand ~ to home directory in a path.
+///
+/// On Unix, `~user` and `~user/...` are also expanded to that user's home directory,
+/// so that e.g. `~root/` cannot be used to get around a `/root` dangerous path.
 fn expand_home(path: &str) -> String {
     if path.starts_with("~/")
         && let Some(home) = std::env::var_os("HOME")
     {
         return format!("{}{}", home.to_string_lossy(), &path[1..]);
     }
+
+    #[cfg(unix)]
+    if let Some(rest) = path.strip_prefix('~') {
+        let (user, tail) = rest.split_at(rest.find('/').unwrap_or(rest.len()));
+        if !user.is_empty()
+            && user
+                .bytes()
+                .all(|b| b.is_ascii_alphanumeric() || b == b'_' || b == b'-')
+            && let Some(home) = user_home_dir(user)
+        {
+            return format!("{home}{tail}");
+        }
+    }
+
     path.to_string()
 }
 
+/// Lexically normalize a UNC path (`\\server\share\...`).
+///
+/// Resolves `.` and `..` without touching the network, and never climbs above
+/// the `\\server\share` root. Both `\` and `/` are accepted as separators.
+/// Returns `None` if the path is not a UNC path.
+#[cfg(windows)]
+fn normalize_unc_path(path: &str) -> Option<String> {
+    let rest = path
+        .strip_prefix(r"\\")
+        .or_else(|| path.strip_prefix("//"))?;
+    let mut components = rest.split(['\\', '/']).filter(|c| !c.is_empty());
+    let server = components.next()?;
+    let share = components.next()?;
+
+    let mut resolved: Vec<&str> = Vec::new();
+    for component in components {
+        match component {
+            "." => {}
+            ".." => {
+                resolved.pop();
+            }
+            _ => resolved.push(component),
+        }
+    }
+
+    let mut normalized = format!(r"\\{server}\{share}");
+    for component in resolved {
+        normalized.push('\\');
+        normalized.push_str(component);
+    }
+    Some(normalized)
+}
+
 /// Normalize a path for comparison (expand ~, resolve . and .., but don't require existence).
 fn normalize_path(path: &str) -> String {
+    // UNC paths are resolved lexically: `canonicalize` would hit the network and
+    // return a `\\?\UNC\` prefix that never compares equal to the configured path.
+    #[cfg(windows)]
+    if let Some(unc) = normalize_unc_path(path) {
+        return unc;
+    }
+
     let expanded = expand_home(path);
     // Use canonicalize if the path exists, otherwise just use the expanded path
     std::fs::canonicalize(&expanded)
@@ -281,6 +1085,31 @@ fn normalize_path(path: &str) -> String {
         .unwrap_or(expanded)
 }
 
+/// Fold the case of a normalized path for comparison.
+///
+/// macOS and Windows filesystems are case-insensitive by default, and `normalize_path`
+/// cannot fix up the case of a path that does not exist yet, so comparisons there
+/// are done in lowercase. On other platforms the path is returned unchanged.
+#[cfg_attr(
+    not(any(target_os = "macos", windows)),
+    expect(clippy::missing_const_for_fn)
+)]
+#[cfg_attr(
+    any(target_os = "macos", windows),
+    expect(clippy::needless_pass_by_value)
+)]
+fn fold_path_case(path: String) -> String {
+    #[cfg(any(target_os = "macos", windows))]
+    {
+        path.to_lowercase()
+    }
+
+    #[cfg(not(any(target_os = "macos", windows)))]
+    {
+        path
+    }
+}
+
 /// Check if a path matches a dangerous path pattern.
 ///
 /// - If dangerous path ends with `/` (e.g., `~/`), only match exact directory or wildcards
@@ -298,8 +1127,12 @@ fn is_dangerous_path(path: &str, dangerous_paths: &[&str]) -> Option<String> {
             let dangerous_base = dangerous.trim_end_matches('/');
             let path_trimmed = path.trim_end_matches('/');
 
-            // Exact match (e.g., "~" or "~/")
-            if path_trimmed == dangerous_base || path == dangerous {
+            // Exact match (e.g., "~" or "~/"), also after expansion (e.g., "~alice/" for "~/")
+            if path_trimmed == dangerous_base
+                || path == dangerous
+                || expand_home(path).trim_end_matches('/')
+                    == expand_home(dangerous).trim_end_matches('/')
+            {
                 return Some(dangerous.to_string());
             }
 
@@ -326,11 +1159,12 @@ fn is_dangerous_path(path: &str, dangerous_paths: &[&str]) -> Option<String> {
             }
         } else {
             // Exact path pattern (e.g., "/etc/passwd")
-            let normalized = normalize_path(path);
-            let dangerous_normalized = normalize_path(dangerous);
+            let normalized = fold_path_case(normalize_path(path));
+            let dangerous_normalized = fold_path_case(normalize_path(dangerous));
 
             if normalized == dangerous_normalized
                 || normalized.starts_with(&format!("{dangerous_normalized}/"))
+                || (cfg!(windows) && normalized.starts_with(&format!("{dangerous_normalized}\\")))
             {
                 return Some(dangerous.to_string());
             }
@@ -340,23 +1174,129 @@ fn is_dangerous_path(path: &str, dangerous_paths: &[&str]) -> Option<String> {
     None
 }
 
-/// Check if a bash command targets dangerous paths with rm/trash/mv.
+/// Split a command's argument string into words, removing shell quoting.
 ///
-/// Returns `Some(DangerousPathCheck)` if a dangerous operation is detected.
-#[must_use]
-pub fn check_dangerous_path_command(
+/// Handles single quotes, double quotes, and (outside Windows, where `\` is the
+/// path separator) backslash escapes. This is not a full shell parser: expansions
+/// and operators are left as-is.
+fn split_shell_args(input: &str) -> Vec<String> {
+    let escapes = !cfg!(windows);
+    let mut args = Vec::new();
+    let mut current = String::new();
+    let mut in_arg = false;
+    let mut chars = input.chars();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '\'' => {
+                in_arg = true;
+                current.extend(chars.by_ref().take_while(|&c| c != '\''));
+            }
+            '"' => {
+                in_arg = true;
+                while let Some(c) = chars.next() {
+                    match c {
+                        '"' => break,
+                        // Inside double quotes, a backslash only escapes these characters
+                        '\\' if escapes => match chars.next() {
+                            Some(next @ ('"' | '\\' | '$' | '`')) => current.push(next),
+                            Some(next) => {
+                                current.push('\\');
+                                current.push(next);
+                            }
+                            None => current.push('\\'),
+                        },
+                        _ => current.push(c),
+                    }
+                }
+            }
+            '\\' if escapes => {
+                in_arg = true;
+                current.extend(chars.next());
+            }
+            c if c.is_whitespace() => {
+                if in_arg {
+                    args.push(std::mem::take(&mut current));
+                    in_arg = false;
+                }
+            }
+            _ => {
+                in_arg = true;
+                current.push(c);
+            }
+        }
+    }
+
+    if in_arg {
+        args.push(current);
+    }
+    args
+}
+
+/// Split a command on `;`, `&`, and `|` outside of quotes.
+fn split_command_segments(cmd: &str) -> Vec<&str> {
+    let escapes = !cfg!(windows);
+    let mut segments = Vec::new();
+    let mut start = 0;
+    let mut quote = None;
+    let mut chars = cmd.char_indices();
+
+    while let Some((i, c)) = chars.next() {
+        match (quote, c) {
+            (Some('\''), '\'') | (Some('"'), '"') => quote = None,
+            (None | Some('"'), '\\') if escapes => {
+                chars.next();
+            }
+            (None, '\'' | '"') => quote = Some(c),
+            (None, ';' | '&' | '|') => {
+                segments.push(&cmd[start..i]);
+                start = i + 1;
+            }
+            _ => {}
+        }
+    }
+
+    segments.push(&cmd[start..]);
+    segments
+}
+
+/// Split a command into its `;`/`&`/`|`-separated invocations.
+///
+/// Yields each invocation's program name (without a leading `sudo` or directory)
+/// together with its unquoted arguments. Separators inside quotes do not split.
+fn command_invocations(cmd: &str) -> impl Iterator<Item = (String, Vec<String>)> + '_ {
+    split_command_segments(cmd)
+        .into_iter()
+        .filter_map(|segment| {
+            let mut words = split_shell_args(segment).into_iter();
+            let mut program = words.next()?;
+            if program == "sudo" {
+                program = words.next()?;
+            }
+            let program = program
+                .rsplit(['/', '\\'])
+                .next()
+                .unwrap_or_default()
+                .to_string();
+            Some((program, words.collect()))
+        })
+}
+
+/// Check if a bash command targets dangerous paths with rm/trash/mv/rsync/tar/ln.
+///
+/// Returns `Some(DangerousPathCheck)` if a dangerous operation is detected.
+#[must_use]
+pub fn check_dangerous_path_command(
     cmd: &str,
     dangerous_paths: &[&str],
 ) -> Option<DangerousPathCheck> {
-    // Patterns to match rm, trash, mv commands and extract their arguments
+    // Patterns to match rm, trash, mv, rsync, tar, ln commands and extract their arguments
     // We look for these commands and then check their path arguments
 
     let cmd_trimmed = cmd.trim();
 
-    // Split by common command separators to handle chained commands
-    let segments: Vec<&str> = cmd_trimmed.split([';', '&', '|']).collect();
-
-    for segment in segments {
+    // Split by common command separators (outside quotes) to handle chained commands
+    for segment in split_command_segments(cmd_trimmed) {
         let segment = segment.trim();
         if segment.is_empty() {
             continue;
@@ -365,42 +1305,203 @@ pub fn check_dangerous_path_command(
         // Remove leading sudo if present
         let segment = segment.strip_prefix("sudo ").unwrap_or(segment).trim();
 
-        // Check for rm, trash, or mv commands
+        // Check for rm, trash, mv, rsync, tar, or ln commands
         let (cmd_type, args) = if let Some(rest) = segment.strip_prefix("rm ") {
             ("rm", rest)
         } else if let Some(rest) = segment.strip_prefix("trash ") {
             ("trash", rest)
         } else if let Some(rest) = segment.strip_prefix("mv ") {
             ("mv", rest)
+        } else if let Some(rest) = segment.strip_prefix("rsync ") {
+            ("rsync", rest)
+        } else if let Some(rest) = segment.strip_prefix("tar ") {
+            ("tar", rest)
+        } else if let Some(rest) = segment.strip_prefix("ln ") {
+            ("ln", rest)
         } else {
             continue;
         };
 
-        // Parse arguments, skipping flags (starting with -)
-        for arg in args.split_whitespace() {
-            if arg.starts_with('-') {
-                continue;
+        let args = split_shell_args(args);
+        let mut mv_direction = None;
+        let matched = match cmd_type {
+            "rsync" => check_rsync_args(&args, dangerous_paths),
+            "tar" => check_tar_args(&args, dangerous_paths),
+            "ln" => check_ln_args(&args, dangerous_paths),
+            "mv" => {
+                mv_direction = check_mv_args(&args, dangerous_paths);
+                mv_direction
+                    .as_ref()
+                    .and_then(|direction| direction.from.clone().or_else(|| direction.to.clone()))
             }
+            // Check every path argument, skipping flags (starting with -)
+            _ => args
+                .iter()
+                .filter(|arg| !arg.starts_with('-'))
+                .find_map(|arg| is_dangerous_path(arg, dangerous_paths)),
+        };
 
-            // Check if this path is dangerous
-            if let Some(matched) = is_dangerous_path(arg, dangerous_paths) {
-                return Some(DangerousPathCheck {
-                    matched_path: matched,
-                    command_type: cmd_type.to_string(),
-                });
-            }
+        if let Some(matched) = matched {
+            return Some(DangerousPathCheck {
+                matched_path: matched,
+                command_type: cmd_type.to_string(),
+                mv_direction,
+            });
+        }
+    }
+
+    None
+}
+
+/// Check the sources and the destination of an `mv` command against the dangerous paths.
+///
+/// Handles `mv [flags] source... dest`: the destination is the last positional
+/// argument and every earlier one is a source. A lone positional argument is treated
+/// as a source.
+fn check_mv_args(args: &[String], dangerous_paths: &[&str]) -> Option<MvDirection> {
+    let positional: Vec<&String> = args.iter().filter(|arg| !arg.starts_with('-')).collect();
+    let (sources, destination) = match positional.split_last() {
+        Some((destination, sources)) if !sources.is_empty() => (sources, Some(*destination)),
+        _ => (positional.as_slice(), None),
+    };
+
+    let from = sources
+        .iter()
+        .find_map(|source| is_dangerous_path(source, dangerous_paths));
+    let to = destination.and_then(|destination| is_dangerous_path(destination, dangerous_paths));
+    (from.is_some() || to.is_some()).then_some(MvDirection { from, to })
+}
+
+/// Check the destination of an `rsync` command against the dangerous paths.
+///
+/// The destination is the last non-flag argument; remote destinations (`host:path`)
+/// are ignored. With `--delete` (or `--delete-before`, `--delete-during`, ...), rsync
+/// removes destination files missing from the source, so a destination that
+/// contains a dangerous path is dangerous as well.
+fn check_rsync_args(args: &[String], dangerous_paths: &[&str]) -> Option<String> {
+    let dest = args.iter().rfind(|arg| !arg.starts_with('-'))?;
+    if is_remote_rsync_path(dest) {
+        return None;
+    }
+    if let Some(matched) = is_dangerous_path(dest, dangerous_paths) {
+        return Some(matched);
+    }
+
+    let deletes = args.iter().any(|arg| arg.starts_with("--delete"));
+    if !deletes {
+        return None;
+    }
+    find_dangerous_path_within(dest, dangerous_paths)
+}
+
+/// Find a dangerous path located at or below the directory `dir`.
+///
+/// Used for commands that write or delete recursively inside a directory, where
+/// targeting a parent of a dangerous path reaches the dangerous path too.
+fn find_dangerous_path_within(dir: &str, dangerous_paths: &[&str]) -> Option<String> {
+    let dir_prefix = format!(
+        "{}/",
+        fold_path_case(normalize_path(dir)).trim_end_matches('/')
+    );
+    dangerous_paths
+        .iter()
+        .find(|dangerous| {
+            let dangerous_normalized = fold_path_case(normalize_path(dangerous));
+            format!("{}/", dangerous_normalized.trim_end_matches('/')).starts_with(&dir_prefix)
+        })
+        .map(|dangerous| (*dangerous).to_string())
+}
+
+/// Find a dangerous path that `path` is equal to or located below.
+///
+/// Unlike [`is_dangerous_path`], directory patterns such as `~/.ssh/` match any
+/// path inside the directory. Used for commands that create new files.
+fn find_dangerous_path_containing(path: &str, dangerous_paths: &[&str]) -> Option<String> {
+    let path_prefix = format!(
+        "{}/",
+        fold_path_case(normalize_path(path)).trim_end_matches('/')
+    );
+    dangerous_paths
+        .iter()
+        .find(|dangerous| {
+            let dangerous_normalized = fold_path_case(normalize_path(dangerous));
+            path_prefix.starts_with(&format!("{}/", dangerous_normalized.trim_end_matches('/')))
+        })
+        .map(|dangerous| (*dangerous).to_string())
+}
+
+/// Check the link name of an `ln` command against the dangerous paths.
+///
+/// Handles `ln [-s] [-f] target linkname`: the link name is the last positional
+/// argument. With a single positional argument the link is created in the current
+/// directory, which is not checked.
+fn check_ln_args(args: &[String], dangerous_paths: &[&str]) -> Option<String> {
+    let mut positional = args.iter().filter(|arg| !arg.starts_with('-'));
+    positional.next()?;
+    let link_name = positional.next_back()?;
+    is_dangerous_path(link_name, dangerous_paths)
+        .or_else(|| find_dangerous_path_containing(link_name, dangerous_paths))
+}
+
+/// Check the extraction directory of a `tar` command against the dangerous paths.
+///
+/// Only extraction (`-x`, `--extract`, `--get`, or old-style `tar xf`) is checked.
+/// The target comes from `-C <dir>`, `--directory <dir>`, or `--directory=<dir>`.
+/// Extracting into `/` is always dangerous, even if `/` is not listed.
+fn check_tar_args(args: &[String], dangerous_paths: &[&str]) -> Option<String> {
+    let is_short_cluster = |arg: &str| arg.starts_with('-') && !arg.starts_with("--");
+    let extracts = args.iter().enumerate().any(|(i, arg)| {
+        arg == "--extract"
+            || arg == "--get"
+            || (is_short_cluster(arg) && arg.contains('x'))
+            || (i == 0 && !arg.starts_with('-') && arg.contains('x'))
+    });
+    if !extracts {
+        return None;
+    }
+
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        let dir = if arg == "-C" || arg == "--directory" {
+            args.next().map(String::as_str)
+        } else {
+            arg.strip_prefix("--directory=")
+        };
+        let Some(dir) = dir else {
+            continue;
+        };
+
+        if dir.trim_end_matches('/').is_empty() {
+            return Some("/".to_string());
+        }
+        if let Some(matched) = is_dangerous_path(dir, dangerous_paths)
+            .or_else(|| find_dangerous_path_within(dir, dangerous_paths))
+        {
+            return Some(matched);
         }
     }
 
     None
 }
 
+/// Check if an rsync path refers to a remote host (`host:path` or `user@host:path`).
+fn is_remote_rsync_path(path: &str) -> bool {
+    // A single-letter prefix is a Windows drive (`C:\...`), not a host.
+    path.find(':')
+        .is_some_and(|colon| colon > 1 && !path[..colon].contains('/'))
+}
+
 // ============================================================================
 // Package manager mismatch detection
 // ============================================================================
 
 /// Represents a JavaScript/Node.js package manager.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "lowercase")
+)]
 pub enum PackageManager {
     Npm,
     Pnpm,
@@ -430,6 +1531,73 @@ impl PackageManager {
             Self::Bun => &["bun.lockb", "bun.lock"],
         }
     }
+
+    /// Returns the package manager that owns the given lock file name, if any.
+    ///
+    /// The name must match exactly (e.g. `package-lock.json`), without any directory.
+    #[must_use]
+    pub fn from_lock_file(filename: &str) -> Option<Self> {
+        ALL_PACKAGE_MANAGERS
+            .iter()
+            .copied()
+            .find(|pm| pm.lock_files().contains(&filename))
+    }
+
+    /// Returns all supported package managers.
+    #[must_use]
+    pub const fn all() -> &'static [Self] {
+        ALL_PACKAGE_MANAGERS
+    }
+}
+
+impl std::fmt::Display for PackageManager {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl From<PackageManager> for &'static str {
+    fn from(pm: PackageManager) -> Self {
+        pm.name()
+    }
+}
+
+/// Error returned when parsing an unknown package manager name.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParsePackageManagerError {
+    /// The input that did not name a known package manager.
+    pub input: String,
+}
+
+impl std::fmt::Display for ParsePackageManagerError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "unknown package manager: {}", self.input)
+    }
+}
+
+impl std::error::Error for ParsePackageManagerError {}
+
+impl std::str::FromStr for PackageManager {
+    type Err = ParsePackageManagerError;
+
+    /// Parses a package manager name (`npm`, `pnpm`, `yarn`, `bun`), ignoring ASCII case.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        ALL_PACKAGE_MANAGERS
+            .iter()
+            .copied()
+            .find(|pm| pm.name().eq_ignore_ascii_case(s))
+            .ok_or_else(|| ParsePackageManagerError {
+                input: s.to_string(),
+            })
+    }
+}
+
+impl TryFrom<&str> for PackageManager {
+    type Error = ParsePackageManagerError;
+
+    fn try_from(s: &str) -> Result<Self, Self::Error> {
+        s.parse()
+    }
 }
 
 const ALL_PACKAGE_MANAGERS: &[PackageManager] = &[
@@ -441,6 +1609,11 @@ const ALL_PACKAGE_MANAGERS: &[PackageManager] = &[
 
 /// Result of checking for package manager mismatch.
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(tag = "result", rename_all = "snake_case")
+)]
 pub enum PackageManagerCheckResult {
     /// No package manager command detected or no lock file found.
     Ok,
@@ -475,18 +1648,88 @@ static PM_COMMAND_PATTERN: LazyLock<Regex> = LazyLock::new(|| {
     .unwrap()
 });
 
+/// Regex pattern for package runners that implicitly install packages.
+static PM_EXEC_PATTERN: LazyLock<Regex> = LazyLock::new(|| {
+    // Match npx/bunx, or npm exec / pnpm exec / pnpm dlx / yarn dlx / bun x,
+    // followed by the package or binary to run
+    Regex::new(
+        r"(?:^|[;&|()]\s*)(?:sudo\s+)?(?P<exec>npx|bunx|npm\s+exec|pnpm\s+(?:exec|dlx)|yarn\s+dlx|bun\s+x)\s+\S",
+    )
+    .unwrap()
+});
+
 /// Detect which package manager a command is trying to use.
+///
+/// Besides package management and script commands, package runners such as
+/// `npx <pkg>`, `pnpm dlx <pkg>`, or `bunx <pkg>` are detected, since they
+/// install packages on the fly.
 #[must_use]
 pub fn detect_package_manager_command(cmd: &str) -> Option<PackageManager> {
-    PM_COMMAND_PATTERN.captures(cmd).and_then(|caps| {
-        caps.name("pm").map(|m| match m.as_str() {
-            "npm" => PackageManager::Npm,
-            "pnpm" => PackageManager::Pnpm,
-            "yarn" => PackageManager::Yarn,
-            "bun" => PackageManager::Bun,
-            _ => unreachable!(),
+    PM_COMMAND_PATTERN
+        .captures(cmd)
+        .and_then(|caps| caps.name("pm")?.as_str().parse().ok())
+        .or_else(|| {
+            let caps = PM_EXEC_PATTERN.captures(cmd)?;
+            match caps.name("exec")?.as_str() {
+                "npx" => Some(PackageManager::Npm),
+                "bunx" => Some(PackageManager::Bun),
+                exec => exec.split_whitespace().next()?.parse().ok(),
+            }
         })
-    })
+}
+
+/// A JavaScript runtime that runs project scripts through its own task runner.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "lowercase")
+)]
+pub enum ScriptRuntime {
+    Node,
+    Bun,
+    Deno,
+}
+
+impl ScriptRuntime {
+    /// Returns the display name of the runtime.
+    #[must_use]
+    pub const fn name(self) -> &'static str {
+        match self {
+            Self::Node => "node",
+            Self::Bun => "bun",
+            Self::Deno => "deno",
+        }
+    }
+}
+
+impl std::fmt::Display for ScriptRuntime {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+/// Regex pattern for script execution through a runtime's task runner.
+static SCRIPT_RUNTIME_PATTERN: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?:^|[;&|()]\s*)(?:sudo\s+)?(?P<cmd>deno\s+task|bun\s+run|node\s+--run)(?:\s|$)")
+        .unwrap()
+});
+
+/// Detect which runtime a script execution command (`deno task`, `bun run`,
+/// `node --run`) uses.
+///
+/// Unlike [`detect_package_manager_command`], this is informational only (e.g.
+/// for showing the active runtime in a statusline) and is not used by
+/// [`check_package_manager`], so it never causes a mismatch. Deno has no
+/// [`PackageManager`] counterpart, hence the separate [`ScriptRuntime`] type.
+#[must_use]
+pub fn detect_package_manager_runtime(cmd: &str) -> Option<ScriptRuntime> {
+    let caps = SCRIPT_RUNTIME_PATTERN.captures(cmd)?;
+    match caps.name("cmd")?.as_str().split_whitespace().next()? {
+        "deno" => Some(ScriptRuntime::Deno),
+        "bun" => Some(ScriptRuntime::Bun),
+        _ => Some(ScriptRuntime::Node),
+    }
 }
 
 /// Find lock files starting from `start_dir` and searching up to parent directories.
@@ -494,17 +1737,28 @@ pub fn detect_package_manager_command(cmd: &str) -> Option<PackageManager> {
 /// Returns a list of package managers whose lock files were found.
 #[must_use]
 pub fn find_lock_files(start_dir: &std::path::Path) -> Vec<PackageManager> {
+    find_lock_files_with_paths(start_dir)
+        .into_iter()
+        .map(|(pm, _)| pm)
+        .collect()
+}
+
+/// Like [`find_lock_files`], but also returns where each lock file was found,
+/// e.g. for naming `/home/user/project/pnpm-lock.yaml` in a mismatch message.
+#[must_use]
+pub fn find_lock_files_with_paths(start_dir: &std::path::Path) -> Vec<(PackageManager, PathBuf)> {
     let mut current = Some(start_dir);
     while let Some(dir) = current {
-        let mut found = Vec::new();
-        for &pm in ALL_PACKAGE_MANAGERS {
-            for &lock_file in pm.lock_files() {
-                if dir.join(lock_file).exists() {
-                    found.push(pm);
-                    break;
-                }
-            }
-        }
+        let found: Vec<(PackageManager, PathBuf)> = ALL_PACKAGE_MANAGERS
+            .iter()
+            .filter_map(|&pm| {
+                pm.lock_files()
+                    .iter()
+                    .map(|lock_file| dir.join(lock_file))
+                    .find(|path| path.exists())
+                    .map(|path| (pm, path))
+            })
+            .collect();
         if !found.is_empty() {
             return found;
         }
@@ -513,8 +1767,88 @@ pub fn find_lock_files(start_dir: &std::path::Path) -> Vec<PackageManager> {
     Vec::new()
 }
 
+/// Cache key for lock file lookups: the start directory and the modification times
+/// of it and each of its ancestors.
+type LockFileCacheKey = (PathBuf, Vec<Option<SystemTime>>);
+
+/// Lookups already done by [`find_lock_files_cached`]. The map is cleared if it ever
+/// reaches [`LOCK_FILE_CACHE_LIMIT`] entries, so a long-running hook server that sees
+/// many directories does not grow it without bound.
+static LOCK_FILE_CACHE: LazyLock<Mutex<HashMap<LockFileCacheKey, Vec<PackageManager>>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+const LOCK_FILE_CACHE_LIMIT: usize = 1024;
+
+/// Cached variant of [`find_lock_files`].
+///
+/// Results are keyed on `start_dir` and the modification times of it and its parent
+/// directories, so adding or removing a lock file anywhere the lookup would search
+/// invalidates the entry.
+#[must_use]
+pub fn find_lock_files_cached(start_dir: &std::path::Path) -> Vec<PackageManager> {
+    let mtimes = start_dir
+        .ancestors()
+        .map(|dir| {
+            std::fs::metadata(dir)
+                .and_then(|metadata| metadata.modified())
+                .ok()
+        })
+        .collect();
+    let key = (start_dir.to_path_buf(), mtimes);
+
+    if let Some(found) = LOCK_FILE_CACHE
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+        .get(&key)
+    {
+        return found.clone();
+    }
+
+    let found = find_lock_files(start_dir);
+    let mut cache = LOCK_FILE_CACHE
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner);
+    if cache.len() >= LOCK_FILE_CACHE_LIMIT {
+        cache.clear();
+    }
+    cache.insert(key, found.clone());
+    found
+}
+
+/// Clear all cached lock file lookups.
+pub fn invalidate_lock_file_cache() {
+    LOCK_FILE_CACHE
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+        .clear();
+}
+
+/// Check if a directory is the root of a pnpm workspace (contains `pnpm-workspace.yaml`).
+#[must_use]
+pub fn is_pnpm_workspace_root(dir: &std::path::Path) -> bool {
+    dir.join("pnpm-workspace.yaml").is_file()
+}
+
+static WORKSPACES_FIELD_PATTERN: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r#""workspaces"\s*:"#).unwrap());
+
+/// Check if a directory is the root of a Bun workspace: a `bun.lock` or `bun.lockb`
+/// next to a `package.json` with a `"workspaces"` field.
+#[must_use]
+pub fn is_bun_workspace_root(dir: &std::path::Path) -> bool {
+    PackageManager::Bun
+        .lock_files()
+        .iter()
+        .any(|lock_file| dir.join(lock_file).is_file())
+        && std::fs::read_to_string(dir.join("package.json"))
+            .is_ok_and(|content| WORKSPACES_FIELD_PATTERN.is_match(&content))
+}
+
 /// Check if a bash command uses a mismatched package manager.
 ///
+/// Inside a Bun workspace, `bun` commands follow the workspace root's lock file, so
+/// a package directory with a stray lock file of its own does not cause a mismatch.
+///
 /// # Arguments
 /// * `cmd` - The bash command to check.
 /// * `start_dir` - The directory to start searching for lock files.
@@ -530,8 +1864,134 @@ pub fn check_package_manager(cmd: &str, start_dir: &std::path::Path) -> PackageM
         return PackageManagerCheckResult::Ok;
     };
 
-    let detected_pms = find_lock_files(start_dir);
+    let result = package_manager_check_result(command_pm, find_lock_files_cached(start_dir));
+    if is_bun_workspace_conflict(command_pm, &result)
+        && start_dir.ancestors().any(is_bun_workspace_root)
+    {
+        PackageManagerCheckResult::Matching
+    } else {
+        result
+    }
+}
+
+/// Async variant of [`check_package_manager`] for hook runners that serve requests
+/// concurrently. Lock files are looked up with `tokio::fs` and are not cached.
+#[cfg(feature = "tokio")]
+pub async fn check_package_manager_async(
+    cmd: &str,
+    start_dir: &std::path::Path,
+) -> PackageManagerCheckResult {
+    let Some(command_pm) = detect_package_manager_command(cmd) else {
+        return PackageManagerCheckResult::Ok;
+    };
+
+    let result = package_manager_check_result(command_pm, find_lock_files_async(start_dir).await);
+    if is_bun_workspace_conflict(command_pm, &result) {
+        for dir in start_dir.ancestors() {
+            if is_bun_workspace_root_async(dir).await {
+                return PackageManagerCheckResult::Matching;
+            }
+        }
+    }
+    result
+}
+
+/// Async variant of [`is_bun_workspace_root`] using `tokio::fs`.
+#[cfg(feature = "tokio")]
+pub async fn is_bun_workspace_root_async(dir: &std::path::Path) -> bool {
+    let mut has_lock_file = false;
+    for lock_file in PackageManager::Bun.lock_files() {
+        if tokio::fs::metadata(dir.join(lock_file))
+            .await
+            .is_ok_and(|metadata| metadata.is_file())
+        {
+            has_lock_file = true;
+            break;
+        }
+    }
+    has_lock_file
+        && tokio::fs::read_to_string(dir.join("package.json"))
+            .await
+            .is_ok_and(|content| WORKSPACES_FIELD_PATTERN.is_match(&content))
+}
+
+/// Async variant of [`find_lock_files`] using `tokio::fs`.
+///
+/// Each directory is listed once; if it cannot be listed, the lock files are probed
+/// individually, which still works in directories without read permission.
+#[cfg(feature = "tokio")]
+pub async fn find_lock_files_async(start_dir: &std::path::Path) -> Vec<PackageManager> {
+    let mut current = Some(start_dir);
+    while let Some(dir) = current {
+        let found = match lock_files_in_dir_async(dir).await {
+            Ok(found) => found,
+            Err(_) => probe_lock_files_async(dir).await,
+        };
+        if !found.is_empty() {
+            return found;
+        }
+        current = dir.parent();
+    }
+    Vec::new()
+}
+
+#[cfg(feature = "tokio")]
+async fn lock_files_in_dir_async(dir: &std::path::Path) -> std::io::Result<Vec<PackageManager>> {
+    let mut entries = tokio::fs::read_dir(dir).await?;
+    let mut present = Vec::new();
+    while let Some(entry) = entries.next_entry().await? {
+        if let Some(pm) = entry
+            .file_name()
+            .to_str()
+            .and_then(PackageManager::from_lock_file)
+        {
+            present.push(pm);
+        }
+    }
+    // Keep the order of `find_lock_files` regardless of directory listing order
+    Ok(ALL_PACKAGE_MANAGERS
+        .iter()
+        .copied()
+        .filter(|pm| present.contains(pm))
+        .collect())
+}
+
+#[cfg(feature = "tokio")]
+async fn probe_lock_files_async(dir: &std::path::Path) -> Vec<PackageManager> {
+    let mut found = Vec::new();
+    for &pm in ALL_PACKAGE_MANAGERS {
+        for &lock_file in pm.lock_files() {
+            if tokio::fs::try_exists(dir.join(lock_file))
+                .await
+                .unwrap_or(false)
+            {
+                found.push(pm);
+                break;
+            }
+        }
+    }
+    found
+}
+
+/// Whether a `bun` command conflicts with the nearest lock files, in which case a Bun
+/// workspace root above the start directory still makes it match.
+const fn is_bun_workspace_conflict(
+    command_pm: PackageManager,
+    result: &PackageManagerCheckResult,
+) -> bool {
+    matches!(command_pm, PackageManager::Bun)
+        && matches!(
+            result,
+            PackageManagerCheckResult::Mismatch { .. }
+                | PackageManagerCheckResult::Ambiguous { .. }
+        )
+}
 
+/// Compare the command's package manager with the ones whose lock files were found.
+fn package_manager_check_result(
+    command_pm: PackageManager,
+    detected_pms: Vec<PackageManager>,
+) -> PackageManagerCheckResult {
     if detected_pms.is_empty() {
         return PackageManagerCheckResult::Ok;
     }
@@ -554,5 +2014,2064 @@ pub fn check_package_manager(cmd: &str, start_dir: &std::path::Path) -> PackageM
     }
 }
 
+/// Format the message shown when a command uses the wrong package manager.
+///
+/// `lock_file` is the lock file that decided `expected_pm`, as returned by
+/// [`find_lock_files_with_paths`].
+#[must_use]
+pub fn format_package_manager_mismatch(
+    command_pm: PackageManager,
+    expected_pm: PackageManager,
+    lock_file: Option<&std::path::Path>,
+) -> String {
+    let detected = lock_file
+        .map(|lock_file| format!(" (detected {})", lock_file.display()))
+        .unwrap_or_default();
+    format!(
+        "Package manager mismatch: This project uses {expected_pm}{detected}, but you are trying to use {command_pm}. Please use {expected_pm} instead."
+    )
+}
+
+// ============================================================================
+// Node version pinning detection
+// ============================================================================
+
+/// Result of checking the running Node version against the project's pinned version.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(tag = "result", rename_all = "snake_case")
+)]
+pub enum NodeVersionCheckResult {
+    /// No package manager command, no usable pinned version, or Node is not available.
+    Ok,
+    /// The running Node version satisfies the pinned version.
+    Compatible,
+    /// The running Node version does not satisfy the pinned version.
+    /// Should deny this operation.
+    Incompatible {
+        /// The pinned version or range (e.g. `18`, `>=18 <21`).
+        required: String,
+        /// Where the pinned version was read from (e.g. `.nvmrc`, `package.json#engines.node`).
+        source: String,
+        /// The running Node version (e.g. `20.11.0`).
+        current: String,
+    },
+}
+
+static VOLTA_NODE_PATTERN: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r#""volta"\s*:\s*\{[^}]*?"node"\s*:\s*"(?P<version>[^"]*)""#).unwrap()
+});
+
+static ENGINES_NODE_PATTERN: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r#""engines"\s*:\s*\{[^}]*?"node"\s*:\s*"(?P<version>[^"]*)""#).unwrap()
+});
+
+/// Find the pinned Node version starting from `start_dir` and searching up to parent directories.
+///
+/// In each directory, `.nvmrc`, `.node-version`, `package.json#volta.node`, and
+/// `package.json#engines.node` are checked in that order. Returns the requirement
+/// and where it was found.
+fn find_node_version_requirement(start_dir: &std::path::Path) -> Option<(String, &'static str)> {
+    let mut current = Some(start_dir);
+    while let Some(dir) = current {
+        for file in [".nvmrc", ".node-version"] {
+            if let Ok(content) = std::fs::read_to_string(dir.join(file))
+                && let Some(version) = content
+                    .lines()
+                    .map(|line| line.split('#').next().unwrap_or_default().trim())
+                    .find(|line| !line.is_empty())
+            {
+                return Some((version.to_string(), file));
+            }
+        }
+
+        if let Ok(content) = std::fs::read_to_string(dir.join("package.json")) {
+            for (pattern, source) in [
+                (&*VOLTA_NODE_PATTERN, "package.json#volta.node"),
+                (&*ENGINES_NODE_PATTERN, "package.json#engines.node"),
+            ] {
+                if let Some(caps) = pattern.captures(&content) {
+                    return Some((caps["version"].trim().to_string(), source));
+                }
+            }
+        }
+
+        current = dir.parent();
+    }
+    None
+}
+
+/// Get the running Node version from `node --version`, without the leading `v`.
+fn current_node_version() -> Option<String> {
+    let output = std::process::Command::new("node")
+        .arg("--version")
+        .output()
+        .ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    let version = String::from_utf8_lossy(&output.stdout);
+    Some(version.trim().trim_start_matches('v').to_string())
+}
+
+/// Parse a possibly partial version (`18`, `v18.17`, `18.x`) into its numeric components.
+///
+/// Parsing stops at the first wildcard (`x`, `X`, `*`), so `18.x` yields `[18]`
+/// and `*` yields `[]`. Prerelease and build suffixes are ignored.
+fn parse_partial_version(version: &str) -> Option<Vec<u64>> {
+    let version = version.trim().trim_start_matches(['v', 'V', '=']);
+    let version = version.split(['-', '+']).next().unwrap_or_default();
+    if version.is_empty() {
+        return Some(Vec::new());
+    }
+
+    let mut parts = Vec::new();
+    for component in version.split('.').take(3) {
+        if matches!(component, "x" | "X" | "*") {
+            break;
+        }
+        parts.push(component.parse().ok()?);
+    }
+    Some(parts)
+}
+
+/// Check a full version against a single comparator such as `>=18`, `^18.2`, or `18.x`.
+fn satisfies_comparator(current: [u64; 3], comparator: &str) -> Option<bool> {
+    let (op, version) = [">=", "<=", ">", "<", "=", "^", "~"]
+        .into_iter()
+        .find_map(|op| comparator.strip_prefix(op).map(|rest| (op, rest)))
+        .unwrap_or(("", comparator));
+    let parts = parse_partial_version(version)?;
+
+    // Comparing only the given components gives partial versions their range meaning:
+    // `<18` excludes all of 18.x, `>=18.2` includes 18.2.0.
+    let ord = current[..parts.len()].cmp(&parts);
+    let same_prefix = |len: usize| {
+        let len = len.min(parts.len());
+        current[..len] == parts[..len]
+    };
+
+    Some(match op {
+        ">=" => ord.is_ge(),
+        ">" => ord.is_gt(),
+        "<=" => ord.is_le(),
+        "<" => ord.is_lt(),
+        "^" => ord.is_ge() && same_prefix(1),
+        "~" => ord.is_ge() && same_prefix(2),
+        _ => ord.is_eq(),
+    })
+}
+
+/// Check if a Node version satisfies a requirement.
+///
+/// Supports exact and partial versions (`18`, `18.17.0`, `18.x`), comparators
+/// (`>=`, `>`, `<=`, `<`, `=`, `^`, `~`), space-separated intersections, hyphen
+/// ranges (`16 - 18`), and `||` unions. Returns `None` if the requirement cannot
+/// be interpreted, e.g. nvm aliases like `lts/*` or `node`.
+fn node_version_satisfies(version: &str, requirement: &str) -> Option<bool> {
+    let parts = parse_partial_version(version)?;
+    let mut current = [0; 3];
+    for (slot, part) in current.iter_mut().zip(parts) {
+        *slot = part;
+    }
+
+    let mut any_satisfied = false;
+    for alternative in requirement.split("||") {
+        let satisfied = if let Some((low, high)) = alternative.split_once(" - ") {
+            satisfies_comparator(current, &format!(">={}", low.trim()))?
+                && satisfies_comparator(current, &format!("<={}", high.trim()))?
+        } else {
+            // Join operators separated from their version by spaces (`>= 18`)
+            let mut comparators: Vec<String> = Vec::new();
+            for token in alternative.split_whitespace() {
+                match comparators.last_mut() {
+                    Some(last) if last.chars().all(|c| "<>=^~".contains(c)) => last.push_str(token),
+                    _ => comparators.push(token.to_string()),
+                }
+            }
+            let mut all = true;
+            for comparator in &comparators {
+                all &= satisfies_comparator(current, comparator)?;
+            }
+            all
+        };
+        any_satisfied |= satisfied;
+    }
+    Some(any_satisfied)
+}
+
+/// Compare a running Node version against a requirement read from `source`.
+fn compare_node_version(required: String, source: &str, current: String) -> NodeVersionCheckResult {
+    match node_version_satisfies(&current, &required) {
+        Some(true) => NodeVersionCheckResult::Compatible,
+        Some(false) => NodeVersionCheckResult::Incompatible {
+            required,
+            source: source.to_string(),
+            current,
+        },
+        None => NodeVersionCheckResult::Ok,
+    }
+}
+
+/// Check if a package manager command runs under the Node version the project pins.
+///
+/// The pinned version is read from `.nvmrc`, `.node-version`, or `package.json`
+/// (`volta.node` or `engines.node`), searching from `start_dir` up to parent
+/// directories, and compared against the output of `node --version`.
+#[must_use]
+pub fn check_node_version_compatibility(
+    cmd: &str,
+    start_dir: &std::path::Path,
+) -> NodeVersionCheckResult {
+    if detect_package_manager_command(cmd).is_none() {
+        return NodeVersionCheckResult::Ok;
+    }
+    let Some((required, source)) = find_node_version_requirement(start_dir) else {
+        return NodeVersionCheckResult::Ok;
+    };
+    let Some(current) = current_node_version() else {
+        return NodeVersionCheckResult::Ok;
+    };
+    compare_node_version(required, source, current)
+}
+
+// ============================================================================
+// Critical process kill detection
+// ============================================================================
+
+/// Process names protected by [`check_process_kill_dangerous`].
+pub const DEFAULT_CRITICAL_PROCESSES: &[&str] = &[
+    "sshd", "systemd", "init", "dockerd", "kubelet", "nginx", "postgres", "mysql",
+];
+
+/// `killall`/`pkill` options that take a value, which must not be mistaken for a process name.
+const KILL_VALUE_OPTIONS: &[&str] = &[
+    "-s",
+    "--signal",
+    "-u",
+    "--user",
+    "-U",
+    "--uid",
+    "-g",
+    "--pgroup",
+    "-G",
+    "--group",
+    "-P",
+    "--parent",
+    "-t",
+    "--terminal",
+    "-o",
+    "--older-than",
+    "-y",
+    "--younger-than",
+];
+
+static PID_LOOKUP_PATTERN: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?:\$\(|`)\s*(?:pidof|pgrep)\s+(?:-\S+\s+)*(?P<name>[\w.-]+)").unwrap()
+});
+
+/// Result of detecting a kill of a critical process.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ProcessKillMatch {
+    /// The kill command (kill, killall, pkill).
+    pub command: String,
+    /// The critical process that is targeted.
+    pub process_name: String,
+}
+
+/// Check if a bash command kills a critical system process.
+///
+/// Uses [`DEFAULT_CRITICAL_PROCESSES`]; see [`check_process_kill_dangerous_with`].
+#[must_use]
+pub fn check_process_kill_dangerous(cmd: &str) -> Option<ProcessKillMatch> {
+    check_process_kill_dangerous_with(cmd, DEFAULT_CRITICAL_PROCESSES)
+}
+
+/// Check if a bash command kills one of `critical_processes`.
+///
+/// Detects `killall <name>` and `pkill <name>`, and `kill -9` (or `-KILL`, `-s KILL`)
+/// when the PID is `1`, `-1` (every process), or looked up with `$(pidof <name>)` or
+/// `$(pgrep <name>)`. Plain PIDs cannot be resolved to a name and are not flagged.
+#[must_use]
+pub fn check_process_kill_dangerous_with(
+    cmd: &str,
+    critical_processes: &[&str],
+) -> Option<ProcessKillMatch> {
+    let is_critical = |name: &str| {
+        let name = name.rsplit('/').next().unwrap_or_default();
+        critical_processes.contains(&name)
+    };
+
+    for (program, args) in command_invocations(cmd) {
+        let process_name = match program.as_str() {
+            "killall" | "pkill" => {
+                let mut names = Vec::new();
+                let mut args = args.iter();
+                while let Some(arg) = args.next() {
+                    if KILL_VALUE_OPTIONS.contains(&arg.as_str()) {
+                        args.next();
+                    } else if !arg.starts_with('-') {
+                        names.push(arg.as_str());
+                    }
+                }
+                names.into_iter().find(|name| is_critical(name))
+            }
+            "kill" if is_sigkill(&args) => {
+                let targets: Vec<&str> = args
+                    .iter()
+                    .map(String::as_str)
+                    .skip_while(|arg| arg.starts_with('-') && *arg != "-1")
+                    .collect();
+                if targets.contains(&"1") {
+                    Some("init")
+                } else if targets.contains(&"-1") {
+                    Some("all processes")
+                } else {
+                    PID_LOOKUP_PATTERN
+                        .captures_iter(cmd)
+                        .filter_map(|caps| caps.name("name"))
+                        .map(|name| name.as_str())
+                        .find(|name| is_critical(name))
+                }
+            }
+            _ => None,
+        };
+
+        if let Some(process_name) = process_name {
+            return Some(ProcessKillMatch {
+                command: program,
+                process_name: process_name.to_string(),
+            });
+        }
+    }
+
+    None
+}
+
+/// Check if `kill` arguments send `SIGKILL`.
+fn is_sigkill(args: &[String]) -> bool {
+    let is_kill_signal = |signal: &str| {
+        let signal = signal.to_ascii_uppercase();
+        matches!(signal.as_str(), "9" | "KILL" | "SIGKILL")
+    };
+    match args {
+        [flag, signal, ..] if matches!(flag.as_str(), "-s" | "--signal" | "-n") => {
+            is_kill_signal(signal)
+        }
+        [flag, ..] if flag.starts_with("--signal=") => is_kill_signal(&flag["--signal=".len()..]),
+        [flag, ..] if flag.starts_with('-') && flag != "-1" => is_kill_signal(&flag[1..]),
+        _ => false,
+    }
+}
+
+// ============================================================================
+// Firewall modification detection
+// ============================================================================
+
+/// How severe a detected operation is.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "lowercase")
+)]
+pub enum Severity {
+    /// Unlikely to cause lasting harm.
+    Low,
+    /// Disruptive but recoverable.
+    Medium,
+    /// Leaves the host exposed or breaks a service.
+    High,
+    /// May lock out remote access to the host.
+    Critical,
+}
+
+impl Severity {
+    /// Returns the lowercase name of the severity.
+    #[must_use]
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::Low => "low",
+            Self::Medium => "medium",
+            Self::High => "high",
+            Self::Critical => "critical",
+        }
+    }
+}
+
+impl std::fmt::Display for Severity {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Result of detecting a firewall modification.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct FirewallModificationMatch {
+    /// The offending command and operation (e.g. `iptables -F`, `ufw disable`).
+    pub command: String,
+    /// How severe the modification is.
+    pub severity: Severity,
+}
+
+/// Check if a bash command flushes or disables the host firewall.
+///
+/// Detects `iptables -F`/`--flush` (and `ip6tables`), `nft flush`, `ufw disable`,
+/// `ufw reset`, and `firewall-cmd --panic-on`. Panic mode drops all traffic,
+/// including the current SSH session, so it is reported as [`Severity::Critical`];
+/// the others leave the host unprotected and are [`Severity::High`].
+#[must_use]
+pub fn check_firewall_modification(cmd: &str) -> Option<FirewallModificationMatch> {
+    for (program, args) in command_invocations(cmd) {
+        let matched = match program.as_str() {
+            p if p.starts_with("iptables") || p.starts_with("ip6tables") => args
+                .iter()
+                .find(|arg| {
+                    *arg == "--flush"
+                        || (arg.starts_with('-') && !arg.starts_with("--") && arg.contains('F'))
+                })
+                .map(|arg| (arg.as_str(), Severity::High)),
+            "nft" => args
+                .iter()
+                .find(|arg| *arg == "flush")
+                .map(|arg| (arg.as_str(), Severity::High)),
+            "ufw" => args
+                .iter()
+                .find(|arg| !arg.starts_with('-'))
+                .filter(|arg| matches!(arg.as_str(), "disable" | "reset"))
+                .map(|arg| (arg.as_str(), Severity::High)),
+            "firewall-cmd" => args
+                .iter()
+                .find(|arg| *arg == "--panic-on")
+                .map(|arg| (arg.as_str(), Severity::Critical)),
+            _ => None,
+        };
+
+        if let Some((operation, severity)) = matched {
+            return Some(FirewallModificationMatch {
+                command: format!("{program} {operation}"),
+                severity,
+            });
+        }
+    }
+
+    None
+}
+
+// ============================================================================
+// Critical systemd service detection
+// ============================================================================
+
+/// Service names protected by [`check_systemctl_dangerous`].
+pub const DEFAULT_CRITICAL_SERVICES: &[&str] = &[
+    "sshd",
+    "networking",
+    "NetworkManager",
+    "docker",
+    "containerd",
+    "kubelet",
+    "wpa_supplicant",
+];
+
+/// Result of detecting a `systemctl` operation on a critical service.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SystemctlMatch {
+    /// The systemctl action (stop, disable, mask, kill).
+    pub action: String,
+    /// The critical service that is targeted.
+    pub service: String,
+}
+
+/// Check if a bash command stops or disables a critical service.
+///
+/// Uses [`DEFAULT_CRITICAL_SERVICES`]; see [`check_systemctl_dangerous_with`].
+#[must_use]
+pub fn check_systemctl_dangerous(cmd: &str) -> Option<SystemctlMatch> {
+    check_systemctl_dangerous_with(cmd, DEFAULT_CRITICAL_SERVICES)
+}
+
+/// Check if a bash command runs `systemctl stop/disable/mask/kill` on one of `critical_services`.
+///
+/// Unit names match with or without a `.service` suffix (e.g. `sshd.service`).
+#[must_use]
+pub fn check_systemctl_dangerous_with(
+    cmd: &str,
+    critical_services: &[&str],
+) -> Option<SystemctlMatch> {
+    for (program, args) in command_invocations(cmd) {
+        if program != "systemctl" {
+            continue;
+        }
+
+        let mut positional = args.iter().filter(|arg| !arg.starts_with('-'));
+        let Some(action) = positional.next() else {
+            continue;
+        };
+        if !matches!(action.as_str(), "stop" | "disable" | "mask" | "kill") {
+            continue;
+        }
+
+        if let Some(service) = positional.find(|unit| {
+            let name = unit.strip_suffix(".service").unwrap_or(unit);
+            critical_services.contains(&name)
+        }) {
+            return Some(SystemctlMatch {
+                action: action.clone(),
+                service: service.clone(),
+            });
+        }
+    }
+
+    None
+}
+
+// ============================================================================
+// Destructive Docker command detection
+// ============================================================================
+
+/// Global `docker` options that take a value, which must not be mistaken for the subcommand.
+const DOCKER_VALUE_OPTIONS: &[&str] = &[
+    "-H",
+    "--host",
+    "-c",
+    "--context",
+    "--config",
+    "-l",
+    "--log-level",
+    "--tlscacert",
+    "--tlscert",
+    "--tlskey",
+];
+
+/// `docker compose` (and `docker-compose`) options that take a value.
+const DOCKER_COMPOSE_VALUE_OPTIONS: &[&str] = &[
+    "-f",
+    "--file",
+    "-p",
+    "--project-name",
+    "--project-directory",
+    "--env-file",
+    "--profile",
+    "--ansi",
+    "--progress",
+    "--parallel",
+];
+
+/// Result of detecting a destructive Docker command.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct DockerDestructiveMatch {
+    /// The Docker subcommand (e.g. `system prune`, `rm`, `compose down`).
+    pub subcommand: String,
+    /// Whether `-f`/`--force` was passed.
+    pub has_force_flag: bool,
+}
+
+/// Check if a bash command removes Docker images, containers, or volumes, or stops containers.
+///
+/// Detects `docker system prune`, `docker image prune -a`, `docker rmi -a`/`-f`,
+/// `docker rm -f`, `docker stop`, `docker kill`, and `docker compose down --volumes`
+/// (or `docker-compose down -v`). Global options such as `-H` or `--context` and
+/// compose options such as `-f` or `-p` are skipped when finding the subcommand.
+#[must_use]
+pub fn check_docker_destructive(cmd: &str) -> Option<DockerDestructiveMatch> {
+    for (program, args) in command_invocations(cmd) {
+        if program != "docker" && program != "docker-compose" {
+            continue;
+        }
+
+        let mut positional = Vec::new();
+        let mut flags = Vec::new();
+        let mut iter = args.iter().map(String::as_str);
+        while let Some(arg) = iter.next() {
+            // Global options come before the subcommand, compose options before its action
+            let value_options = match (program.as_str(), positional.as_slice()) {
+                ("docker", []) => DOCKER_VALUE_OPTIONS,
+                ("docker", ["compose"]) | ("docker-compose", []) => DOCKER_COMPOSE_VALUE_OPTIONS,
+                _ => &[],
+            };
+            if value_options.contains(&arg) {
+                iter.next();
+            } else if arg.starts_with('-') {
+                flags.push(arg);
+            } else {
+                positional.push(arg);
+            }
+        }
+
+        let has_flag = |short: char, long: &str| {
+            flags
+                .iter()
+                .any(|arg| *arg == long || (!arg.starts_with("--") && arg.contains(short)))
+        };
+        let has_force_flag = has_flag('f', "--force");
+
+        let subcommand = match (program.as_str(), positional.as_slice()) {
+            ("docker", ["system", "prune", ..]) => Some("system prune"),
+            ("docker", ["image", "prune", ..]) if has_flag('a', "--all") => Some("image prune"),
+            ("docker", ["rmi", ..]) if has_flag('a', "--all") || has_force_flag => Some("rmi"),
+            ("docker", ["rm", ..] | ["container", "rm", ..]) if has_force_flag => Some("rm"),
+            ("docker", ["stop", ..] | ["container", "stop", ..]) => Some("stop"),
+            ("docker", ["kill", ..] | ["container", "kill", ..]) => Some("kill"),
+            ("docker", ["compose", "down", ..]) | ("docker-compose", ["down", ..])
+                if has_flag('v', "--volumes") =>
+            {
+                Some("compose down")
+            }
+            _ => None,
+        };
+
+        if let Some(subcommand) = subcommand {
+            return Some(DockerDestructiveMatch {
+                subcommand: subcommand.to_string(),
+                has_force_flag,
+            });
+        }
+    }
+
+    None
+}
+
+// ============================================================================
+// Dangerous kubectl delete detection
+// ============================================================================
+
+/// `kubectl` options that take a value, which must not be mistaken for a resource type.
+const KUBECTL_VALUE_OPTIONS: &[&str] = &[
+    "-n",
+    "--namespace",
+    "-l",
+    "--selector",
+    "-f",
+    "--filename",
+    "-k",
+    "--kustomize",
+    "--context",
+    "--cluster",
+    "--user",
+    "--kubeconfig",
+    "-o",
+    "--output",
+    "--field-selector",
+    "--grace-period",
+    "--timeout",
+    "--cascade",
+];
+
+/// Result of detecting a dangerous `kubectl delete`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct KubectlDeleteMatch {
+    /// The resource type argument (e.g. `namespace`, `pvc/data`), if any.
+    pub resource_type: Option<String>,
+    /// Whether `--all`, `-A`, or `--all-namespaces` was passed.
+    pub has_all_flag: bool,
+}
+
+/// Check if a bash command runs a high-risk `kubectl delete`.
+///
+/// Flags deleting namespaces or persistent volumes (claims), deleting every manifest
+/// in the current directory (`-f .`), and deleting with `--all`, `-A`, or
+/// `--all-namespaces`.
+#[must_use]
+pub fn check_kubectl_delete(cmd: &str) -> Option<KubectlDeleteMatch> {
+    for (program, args) in command_invocations(cmd) {
+        if program != "kubectl" {
+            continue;
+        }
+
+        let mut positional = Vec::new();
+        let mut files = Vec::new();
+        let mut has_all_flag = false;
+        let mut iter = args.iter().map(String::as_str);
+        while let Some(arg) = iter.next() {
+            match arg {
+                "--all" | "-A" | "--all-namespaces" => has_all_flag = true,
+                "-f" | "--filename" => files.extend(iter.next()),
+                _ if KUBECTL_VALUE_OPTIONS.contains(&arg) => {
+                    iter.next();
+                }
+                _ if arg.starts_with("--filename=") => files.push(&arg["--filename=".len()..]),
+                _ if arg.starts_with('-') => {}
+                _ => positional.push(arg),
+            }
+        }
+
+        if positional.first() != Some(&"delete") {
+            continue;
+        }
+
+        let resource_type = positional.get(1).map(|resource| (*resource).to_string());
+        let is_high_risk_resource = resource_type.as_deref().is_some_and(|resource| {
+            // `kind/name` or a comma-separated list of kinds
+            let kind = resource.split('/').next().unwrap_or_default();
+            kind.split(',').any(|kind| {
+                matches!(
+                    kind.to_ascii_lowercase().as_str(),
+                    "namespace"
+                        | "namespaces"
+                        | "ns"
+                        | "persistentvolumeclaim"
+                        | "persistentvolumeclaims"
+                        | "pvc"
+                        | "persistentvolume"
+                        | "persistentvolumes"
+                        | "pv"
+                )
+            })
+        });
+        let deletes_current_dir = files.iter().any(|file| matches!(*file, "." | "./"));
+
+        if is_high_risk_resource || has_all_flag || deletes_current_dir {
+            return Some(KubectlDeleteMatch {
+                resource_type,
+                has_all_flag,
+            });
+        }
+    }
+
+    None
+}
+
+// ============================================================================
+// Destructive AWS CLI detection
+// ============================================================================
+
+/// Global `aws` options that take a value, which must not be mistaken for the service.
+const AWS_VALUE_OPTIONS: &[&str] = &[
+    "--profile",
+    "--region",
+    "--output",
+    "--endpoint-url",
+    "--query",
+    "--color",
+    "--ca-bundle",
+    "--cli-read-timeout",
+    "--cli-connect-timeout",
+];
+
+/// Result of detecting a destructive AWS CLI command.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct AwsDestructiveMatch {
+    /// The AWS service (e.g. `s3`, `ec2`).
+    pub service: String,
+    /// The operation (e.g. `rm`, `terminate-instances`).
+    pub operation: String,
+}
+
+/// Check if a bash command runs a destructive AWS CLI operation.
+///
+/// Detects `aws s3 rm --recursive`, `aws s3 sync --delete`, `aws ec2 terminate-instances`,
+/// `aws rds delete-db-instance`, `aws dynamodb delete-table`, and `aws iam delete-role`.
+#[must_use]
+pub fn check_aws_destructive(cmd: &str) -> Option<AwsDestructiveMatch> {
+    for (program, args) in command_invocations(cmd) {
+        if program != "aws" {
+            continue;
+        }
+
+        let mut positional = Vec::new();
+        let mut iter = args.iter().map(String::as_str);
+        while let Some(arg) = iter.next() {
+            if positional.len() < 2 && AWS_VALUE_OPTIONS.contains(&arg) {
+                iter.next();
+            } else if !arg.starts_with('-') {
+                positional.push(arg);
+            }
+        }
+        let has_flag = |flag: &str| args.iter().any(|arg| arg == flag);
+
+        let destructive = match positional.as_slice() {
+            ["s3", "rm", ..] => has_flag("--recursive"),
+            ["s3", "sync", ..] => has_flag("--delete"),
+            ["ec2", "terminate-instances", ..]
+            | ["rds", "delete-db-instance", ..]
+            | ["dynamodb", "delete-table", ..]
+            | ["iam", "delete-role", ..] => true,
+            _ => false,
+        };
+
+        if destructive {
+            return Some(AwsDestructiveMatch {
+                service: positional[0].to_string(),
+                operation: positional[1].to_string(),
+            });
+        }
+    }
+
+    None
+}
+
+// ============================================================================
+// Dangerous SQL detection for database CLIs
+// ============================================================================
+
+static SQL_DROP_PATTERN: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?i)^DROP\s+(?P<object>TABLE|DATABASE|SCHEMA|VIEW|INDEX|USER|ROLE)\b").unwrap()
+});
+
+static SQL_TRUNCATE_PATTERN: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(?i)^TRUNCATE\b").unwrap());
+
+static SQL_DELETE_PATTERN: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(?i)^DELETE\s+FROM\b").unwrap());
+
+static SQL_WHERE_PATTERN: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"(?i)\bWHERE\b").unwrap());
+
+/// Result of detecting a destructive SQL statement passed to a database CLI.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct DatabaseDestructiveMatch {
+    /// The database CLI (psql, mysql, mariadb, sqlite3, duckdb).
+    pub db_tool: String,
+    /// The destructive operation (e.g. `DROP TABLE`, `TRUNCATE`, `DELETE without WHERE`).
+    pub sql_operation: String,
+}
+
+/// Check if a bash command passes a destructive SQL statement to a database CLI.
+///
+/// Reads SQL from `psql -c`/`--command`, `mysql`/`mariadb` `-e`/`--execute`, and the
+/// positional SQL argument of `sqlite3` and `duckdb`, then flags `DROP`, `TRUNCATE`,
+/// and `DELETE FROM` without a `WHERE` clause. SQL read from files or stdin is not checked.
+#[must_use]
+pub fn check_database_dangerous(cmd: &str) -> Option<DatabaseDestructiveMatch> {
+    for (program, args) in command_invocations(cmd) {
+        let sql: Vec<&str> = match program.as_str() {
+            "psql" => sql_option_values(&args, "-c", "--command"),
+            "mysql" | "mariadb" => sql_option_values(&args, "-e", "--execute"),
+            // `sqlite3 [options] FILE [SQL]`
+            "sqlite3" | "duckdb" => args
+                .iter()
+                .filter(|arg| !arg.starts_with('-'))
+                .skip(1)
+                .map(String::as_str)
+                .collect(),
+            _ => continue,
+        };
+
+        if let Some(sql_operation) = sql.into_iter().find_map(destructive_sql_operation) {
+            return Some(DatabaseDestructiveMatch {
+                db_tool: program,
+                sql_operation,
+            });
+        }
+    }
+
+    None
+}
+
+/// Collect the values of a SQL option given as `-c SQL`, `-cSQL`, `--command SQL`, or `--command=SQL`.
+fn sql_option_values<'a>(args: &'a [String], short: &str, long: &str) -> Vec<&'a str> {
+    let mut values = Vec::new();
+    let mut iter = args.iter().map(String::as_str);
+    while let Some(arg) = iter.next() {
+        if arg == short || arg == long {
+            values.extend(iter.next());
+        } else if let Some(value) = arg
+            .strip_prefix(long)
+            .and_then(|rest| rest.strip_prefix('='))
+        {
+            values.push(value);
+        } else if let Some(value) = arg.strip_prefix(short).filter(|value| !value.is_empty())
+            && !arg.starts_with("--")
+        {
+            values.push(value);
+        }
+    }
+    values
+}
+
+/// Find the first destructive statement in a SQL string.
+fn destructive_sql_operation(sql: &str) -> Option<String> {
+    sql.split(';').map(str::trim).find_map(|statement| {
+        if let Some(caps) = SQL_DROP_PATTERN.captures(statement) {
+            return Some(format!("DROP {}", caps["object"].to_ascii_uppercase()));
+        }
+        if SQL_TRUNCATE_PATTERN.is_match(statement) {
+            return Some("TRUNCATE".to_string());
+        }
+        (SQL_DELETE_PATTERN.is_match(statement) && !SQL_WHERE_PATTERN.is_match(statement))
+            .then(|| "DELETE without WHERE".to_string())
+    })
+}
+
+// ============================================================================
+// Global git config modification detection
+// ============================================================================
+
+/// Global and system git config files, with the home directory written as `~`.
+const GLOBAL_GIT_CONFIG_FILES: &[&str] =
+    &["~/.gitconfig", "~/.config/git/config", "/etc/gitconfig"];
+
+/// Check if a bash command modifies the global or system git config.
+///
+/// Detects `git config --global`, `git config --system`, and `git config -f`/`--file`
+/// pointing at `~/.gitconfig`, `~/.config/git/config`, or `/etc/gitconfig`. Reads
+/// (`--get`, `--list`, `git config get`, or a single key without a value) are allowed.
+#[must_use]
+pub fn check_git_config_global(cmd: &str) -> bool {
+    command_invocations(cmd).any(|(program, args)| {
+        program == "git"
+            && parse_git_config(&args).is_some_and(|config| config.global && config.writes)
+    })
+}
+
+/// A parsed `git config` invocation.
+struct GitConfigInvocation<'a> {
+    /// Whether the global or system config is targeted.
+    global: bool,
+    /// Whether the invocation modifies the config.
+    writes: bool,
+    /// The key, or the section for `rename-section`/`remove-section`, if one was given.
+    key: Option<&'a str>,
+}
+
+/// Parse the arguments of `git` as a `git config` invocation, or `None` for other subcommands.
+fn parse_git_config(args: &[String]) -> Option<GitConfigInvocation<'_>> {
+    let mut iter = args.iter().map(String::as_str);
+
+    // Skip git's own options (e.g. `git -C dir config ...`)
+    loop {
+        match iter.next() {
+            Some("-C" | "-c") => {
+                iter.next();
+            }
+            Some("config") => break,
+            Some(arg) if arg.starts_with('-') => {}
+            _ => return None,
+        }
+    }
+
+    let mut global = false;
+    let mut reads = false;
+    let mut modifies = false;
+    let mut positional = Vec::new();
+    while let Some(arg) = iter.next() {
+        match arg {
+            "--global" | "--system" => global = true,
+            "-f" | "--file" => global |= iter.next().is_some_and(is_global_git_config_file),
+            "--get" | "--get-all" | "--get-regexp" | "--get-urlmatch" | "--get-color"
+            | "--get-colorbool" | "--list" | "-l" => reads = true,
+            "--add" | "--replace-all" | "--unset" | "--unset-all" | "--rename-section"
+            | "--remove-section" | "--edit" | "-e" => modifies = true,
+            "--type" | "--default" | "--comment" | "--blob" => {
+                iter.next();
+            }
+            _ if arg.starts_with("--file=") => {
+                global |= is_global_git_config_file(&arg["--file=".len()..]);
+            }
+            _ if arg.starts_with('-') => {}
+            _ => positional.push(arg),
+        }
+    }
+
+    let (writes, key) = match positional.as_slice() {
+        ["edit", ..] => (true, None),
+        ["get" | "list", ..] | [] => (false, None),
+        // `git config <key> <value>` sets; `git config <key>` reads
+        [
+            "set" | "unset" | "rename-section" | "remove-section",
+            key,
+            ..,
+        ]
+        | [key, _, ..] => (true, Some(*key)),
+        [key] => (false, Some(*key)),
+    };
+    Some(GitConfigInvocation {
+        global,
+        writes: !reads && (modifies || writes),
+        key,
+    })
+}
+
+fn is_global_git_config_file(path: &str) -> bool {
+    let path = ["$HOME/", "${HOME}/"]
+        .iter()
+        .find_map(|prefix| path.strip_prefix(prefix))
+        .map_or_else(|| path.to_string(), |rest| format!("~/{rest}"));
+    GLOBAL_GIT_CONFIG_FILES
+        .iter()
+        .any(|file| *file == path || expand_home(file) == expand_home(&path))
+}
+
+// ============================================================================
+// Git credential helper modification detection
+// ============================================================================
+
+/// Check if a bash command changes how git authenticates or where it connects.
+///
+/// Detects `git config` writes (at any scope) to `credential.helper` (including
+/// `credential.<url>.helper`), `core.sshCommand`, and `url.<base>.insteadOf` or
+/// `pushInsteadOf`, removal of the `credential` section, and one-off
+/// overrides of those keys with `git -c <key>=<value>`. A credential helper or SSH
+/// command runs arbitrary programs, and URL rewrites can send credentials elsewhere.
+#[must_use]
+pub fn check_git_credentials_modification(cmd: &str) -> bool {
+    command_invocations(cmd).any(|(program, args)| {
+        if program != "git" {
+            return false;
+        }
+        if git_config_overrides(&args).any(is_git_credential_key) {
+            return true;
+        }
+        parse_git_config(&args)
+            .is_some_and(|config| config.writes && config.key.is_some_and(is_git_credential_key))
+    })
+}
+
+/// Keys set with `git -c <key>=<value>` before the subcommand.
+fn git_config_overrides(args: &[String]) -> impl Iterator<Item = &str> {
+    let mut iter = args.iter().map(String::as_str);
+    std::iter::from_fn(move || {
+        loop {
+            match iter.next()? {
+                "-c" => {
+                    return iter
+                        .next()
+                        .map(|arg| arg.split('=').next().unwrap_or_default());
+                }
+                "-C" => {
+                    iter.next();
+                }
+                arg if arg.starts_with('-') => {}
+                _ => return None,
+            }
+        }
+    })
+}
+
+/// Whether a git config key (or the `credential` section) controls credentials, SSH, or URL rewriting.
+fn is_git_credential_key(key: &str) -> bool {
+    let key = key.to_ascii_lowercase();
+    key == "credential"
+        || key == "core.sshcommand"
+        || (key.starts_with("credential.") && key.ends_with(".helper"))
+        || (key.starts_with("url.")
+            && (key.ends_with(".insteadof") || key.ends_with(".pushinsteadof")))
+}
+
+// ============================================================================
+// Package publishing detection
+// ============================================================================
+
+/// Check if a bash command publishes an npm package (`npm`, `pnpm`, or `yarn publish`).
+#[must_use]
+pub fn check_npm_publish(cmd: &str) -> bool {
+    check_npm_publish_with(cmd, false)
+}
+
+/// Like [`check_npm_publish`], but ignores `--dry-run` publishes when `allow_dry_run` is set.
+#[must_use]
+pub fn check_npm_publish_with(cmd: &str, allow_dry_run: bool) -> bool {
+    command_invocations(cmd).any(|(program, args)| {
+        let positional: Vec<&str> = args
+            .iter()
+            .map(String::as_str)
+            .filter(|arg| !arg.starts_with('-'))
+            .collect();
+        let publishes = matches!(
+            (program.as_str(), positional.as_slice()),
+            ("npm" | "pnpm" | "yarn", ["publish", ..]) | ("yarn", ["npm", "publish", ..])
+        );
+        publishes && !(allow_dry_run && args.iter().any(|arg| arg == "--dry-run"))
+    })
+}
+
+/// Check if a bash command publishes a crate with `cargo publish`.
+#[must_use]
+pub fn check_cargo_publish(cmd: &str) -> bool {
+    check_cargo_publish_with(cmd, false)
+}
+
+/// Like [`check_cargo_publish`], but ignores `--dry-run`/`-n` publishes when `allow_dry_run` is set.
+#[must_use]
+pub fn check_cargo_publish_with(cmd: &str, allow_dry_run: bool) -> bool {
+    command_invocations(cmd).any(|(program, args)| {
+        // Skip a `+toolchain` override and cargo's own flags before the subcommand
+        let publishes = program == "cargo"
+            && args
+                .iter()
+                .find(|arg| !arg.starts_with('-') && !arg.starts_with('+'))
+                .is_some_and(|subcommand| subcommand == "publish");
+        publishes && !(allow_dry_run && args.iter().any(|arg| arg == "--dry-run" || arg == "-n"))
+    })
+}
+
+// ============================================================================
+// Base64-obfuscated command detection
+// ============================================================================
+
+static BASE64_DECODER_PATTERN: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(
+        r"(?:^|[\s;&|(`])(?P<tool>openssl\s+(?:base64|enc\b[^|;&)`]*-base64)|python[0-9.]*\s+-m\s+base64|base64)\b[^|;&)`]*\s(?:-d|--decode|-D)\b",
+    )
+    .unwrap()
+});
+
+static BASE64_EXEC_PATTERN: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(
+        r#"\|\s*(?:sudo\s+)?(?:ba|z|da|k)?sh\b|(?:^|[\s;&|(])(?:eval|source|\.|(?:ba|z|da|k)?sh\s+-c)\s+["']?(?:\$\(|`)|(?:ba|z|da|k)?sh\s+<\("#,
+    )
+    .unwrap()
+});
+
+static BASE64_PAYLOAD_PATTERN: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r#"(?:echo(?:\s+-n)?|printf(?:\s+%s)?)\s+["']?(?P<echo>[A-Za-z0-9+/]{4,}={0,2})["']?\s*\||<<<\s*["']?(?P<herestring>[A-Za-z0-9+/]{4,}={0,2})"#)
+        .unwrap()
+});
+
+/// Result of detecting a base64-decoded command that is executed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Base64CommandMatch {
+    /// The decoded payload, if it is a literal in the command and valid UTF-8.
+    ///
+    /// Nested encodings are decoded down to the innermost command.
+    pub decoded_command: Option<String>,
+    /// The tool used to decode the payload (base64, openssl, python).
+    pub decoder_tool: String,
+}
+
+/// Check if a bash command decodes a base64 payload and executes it.
+///
+/// Recognizes `base64 -d`, `openssl base64 -d`, `openssl enc -base64 -d`, and
+/// `python -m base64 -d` when their output is piped to a shell or run through
+/// `bash -c "$(...)"`, `eval "$(...)"`, or `sh <(...)`. Literal payloads passed via
+/// `echo ... |` or `<<<` are decoded so the hidden command can be checked as well.
+#[must_use]
+pub fn check_base64_encoded_command(cmd: &str) -> Option<Base64CommandMatch> {
+    let decoder = BASE64_DECODER_PATTERN.captures(cmd)?;
+    if !BASE64_EXEC_PATTERN.is_match(cmd) {
+        return None;
+    }
+
+    let tool = &decoder["tool"];
+    let decoder_tool = if tool.starts_with("openssl") {
+        "openssl"
+    } else if tool.starts_with("python") {
+        "python"
+    } else {
+        "base64"
+    };
+
+    let decoded_command = BASE64_PAYLOAD_PATTERN
+        .captures(cmd)
+        .and_then(|caps| caps.name("echo").or_else(|| caps.name("herestring")))
+        .and_then(|payload| decode_base64(payload.as_str()))
+        .and_then(|bytes| String::from_utf8(bytes).ok())
+        .map(|decoded| {
+            // Unwrap nested encodings to reach the command that actually runs
+            check_base64_encoded_command(&decoded)
+                .and_then(|inner| inner.decoded_command)
+                .unwrap_or(decoded)
+        });
+
+    Some(Base64CommandMatch {
+        decoded_command,
+        decoder_tool: decoder_tool.to_string(),
+    })
+}
+
+/// Decode standard base64, ignoring whitespace and padding.
+fn decode_base64(input: &str) -> Option<Vec<u8>> {
+    let mut output = Vec::with_capacity(input.len() * 3 / 4);
+    let mut buffer = 0u32;
+    let mut bits = 0;
+
+    for byte in input
+        .bytes()
+        .filter(|b| !b.is_ascii_whitespace() && *b != b'=')
+    {
+        let value = match byte {
+            b'A'..=b'Z' => byte - b'A',
+            b'a'..=b'z' => byte - b'a' + 26,
+            b'0'..=b'9' => byte - b'0' + 52,
+            b'+' => 62,
+            b'/' => 63,
+            _ => return None,
+        };
+        buffer = (buffer << 6) | u32::from(value);
+        bits += 6;
+        if bits >= 8 {
+            bits -= 8;
+            output.push(u8::try_from((buffer >> bits) & 0xFF).ok()?);
+        }
+    }
+
+    Some(output)
+}
+
+// ============================================================================
+// Root shell escalation detection
+// ============================================================================
+
+/// `sudo` options that take a value, which must not be mistaken for the command.
+const SUDO_VALUE_OPTIONS: &[&str] = &[
+    "-u",
+    "--user",
+    "-g",
+    "--group",
+    "-h",
+    "--host",
+    "-p",
+    "--prompt",
+    "-C",
+    "--close-from",
+    "-D",
+    "--chdir",
+    "-r",
+    "--role",
+    "-t",
+    "--type",
+    "-U",
+    "--other-user",
+    "-T",
+    "--command-timeout",
+];
+
+const INTERACTIVE_SHELLS: &[&str] = &["bash", "sh", "zsh", "dash", "ksh", "fish"];
+
+/// Result of detecting an interactive root shell escalation.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct SudoEscalationMatch {
+    /// The kind of escalation: `sudo -i`, `sudo -s`, `sudo shell`, `sudo su`, or `su`.
+    pub escalation_type: &'static str,
+}
+
+/// Check if a bash command opens an interactive root shell.
+///
+/// Detects `sudo -i`/`--login`, `sudo -s`/`--shell`, `sudo bash` (or another shell)
+/// without a script or `-c`, `sudo su`, and `su`/`su -`/`su root` without `-c`.
+/// Running a single command through `sudo` or `su -c` is not flagged.
+#[must_use]
+pub fn check_sudo_escalation(cmd: &str) -> Option<SudoEscalationMatch> {
+    split_command_segments(cmd)
+        .into_iter()
+        .find_map(|segment| {
+            let words = split_shell_args(segment);
+            match words.first().map(String::as_str) {
+                Some("sudo") => sudo_escalation(&words[1..]),
+                Some("su") => su_escalation(&words[1..]).then_some("su"),
+                _ => None,
+            }
+        })
+        .map(|escalation_type| SudoEscalationMatch { escalation_type })
+}
+
+fn sudo_escalation(args: &[String]) -> Option<&'static str> {
+    let mut iter = args.iter().map(String::as_str);
+    while let Some(arg) = iter.next() {
+        match arg {
+            "-i" | "--login" => return Some("sudo -i"),
+            "-s" | "--shell" => return Some("sudo -s"),
+            _ if SUDO_VALUE_OPTIONS.contains(&arg) => {
+                iter.next();
+            }
+            _ if arg.starts_with("--") => {}
+            // Combined short flags such as `-Hi` or `-Es`
+            _ if arg.starts_with('-') => {
+                if arg.contains('i') {
+                    return Some("sudo -i");
+                }
+                if arg.contains('s') {
+                    return Some("sudo -s");
+                }
+            }
+            program => {
+                let rest: Vec<&str> = iter.collect();
+                let program = program.rsplit('/').next().unwrap_or_default();
+                if program == "su" {
+                    return su_escalation_args(&rest).then_some("sudo su");
+                }
+                let interactive = rest
+                    .iter()
+                    .all(|arg| matches!(*arg, "-l" | "-i" | "--login" | "--norc" | "--noprofile"));
+                return (INTERACTIVE_SHELLS.contains(&program) && interactive)
+                    .then_some("sudo shell");
+            }
+        }
+    }
+    None
+}
+
+fn su_escalation(args: &[String]) -> bool {
+    let args: Vec<&str> = args.iter().map(String::as_str).collect();
+    su_escalation_args(&args)
+}
+
+/// `su` opens a shell unless it is given a command with `-c`.
+fn su_escalation_args(args: &[&str]) -> bool {
+    !args
+        .iter()
+        .any(|arg| matches!(*arg, "-c" | "--command") || arg.starts_with("--command="))
+}
+
+// ============================================================================
+// Mount operation detection
+// ============================================================================
+
+/// Paths that make a bind mount [`Severity::High`] when used as its source or target.
+const DANGEROUS_MOUNT_PATHS: &[&str] = &[
+    "/",
+    "~/",
+    "/etc",
+    "/proc",
+    "/sys",
+    "/dev",
+    "/boot",
+    "/root",
+    "/var/run/docker.sock",
+    "/run/docker.sock",
+];
+
+/// Options of `mount` and `losetup` that take a value.
+const MOUNT_VALUE_OPTIONS: &[&str] = &[
+    "-t",
+    "--types",
+    "-o",
+    "--options",
+    "-O",
+    "--test-opts",
+    "-L",
+    "--label",
+    "-U",
+    "--uuid",
+    "--sizelimit",
+    "--offset",
+];
+
+/// Options of `bindfs` that take a value.
+const BINDFS_VALUE_OPTIONS: &[&str] = &["-o", "-u", "-g", "-p", "-m", "--mirror"];
+
+/// Result of detecting a mount operation.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct MountOperationMatch {
+    /// The mount command (`mount`, `umount`, `losetup`, `bindfs`, or `fusermount`).
+    pub command: String,
+    /// The mount point or device operated on, if one was given.
+    pub target: Option<String>,
+    /// How severe the operation is.
+    pub severity: Severity,
+}
+
+/// Check if a bash command mounts, unmounts, or attaches a filesystem or loop device.
+///
+/// Detects `mount`, `umount`, `losetup`, `bindfs`, and `fusermount`. Listing mounts
+/// or loop devices (`mount` without arguments, `losetup -a`) is not flagged. Bind
+/// mounts (`mount --bind`, `mount -o bind`, `bindfs`) with a system directory, the
+/// home directory, or the Docker socket as source or target are [`Severity::High`];
+/// everything else is [`Severity::Medium`].
+#[must_use]
+pub fn check_mount_operation(cmd: &str) -> Option<MountOperationMatch> {
+    for (program, args) in command_invocations(cmd) {
+        let command = match program.as_str() {
+            "mount" | "umount" | "losetup" | "bindfs" => program,
+            "fusermount" | "fusermount3" => "fusermount".to_string(),
+            _ => continue,
+        };
+
+        let value_options = match command.as_str() {
+            "bindfs" => BINDFS_VALUE_OPTIONS,
+            "fusermount" => &[],
+            _ => MOUNT_VALUE_OPTIONS,
+        };
+        let mut positional = Vec::new();
+        let mut is_bind = command == "bindfs";
+        let mut iter = args.iter().map(String::as_str);
+        while let Some(arg) = iter.next() {
+            match arg {
+                "--bind" | "-B" | "--rbind" | "-R" if command == "mount" => is_bind = true,
+                "-o" | "--options" if command == "mount" => {
+                    let options = iter.next().unwrap_or_default();
+                    is_bind |= options
+                        .split(',')
+                        .any(|opt| opt == "bind" || opt == "rbind");
+                }
+                "--source" | "--target" => positional.extend(iter.next()),
+                _ if value_options.contains(&arg) => {
+                    iter.next();
+                }
+                _ if arg.starts_with('-') => {}
+                _ => positional.push(arg),
+            }
+        }
+
+        let is_listing = match command.as_str() {
+            "mount" => {
+                positional.is_empty() && !args.iter().any(|arg| arg == "-a" || arg == "--all")
+            }
+            "losetup" => {
+                positional.is_empty() && !args.iter().any(|arg| arg == "-f" || arg == "--find")
+            }
+            _ => false,
+        };
+        if is_listing {
+            continue;
+        }
+
+        let is_dangerous_bind = is_bind
+            && positional
+                .iter()
+                .any(|path| is_dangerous_path(path, DANGEROUS_MOUNT_PATHS).is_some());
+        let target = if command == "umount" {
+            positional.first()
+        } else {
+            positional.last()
+        };
+
+        return Some(MountOperationMatch {
+            command,
+            target: target.map(|path| (*path).to_string()),
+            severity: if is_dangerous_bind {
+                Severity::High
+            } else {
+                Severity::Medium
+            },
+        });
+    }
+
+    None
+}
+
+// ============================================================================
+// Sensitive file read detection
+// ============================================================================
+
+/// Paths protected by [`check_sensitive_file_read`].
+///
+/// A trailing `/` protects everything under a directory, and `*`/`?` are wildcards
+/// within a single path component.
+pub const DEFAULT_SENSITIVE_PATHS: &[&str] = &[
+    "~/.aws/credentials",
+    "~/.ssh/id_rsa*",
+    "/etc/shadow",
+    "/etc/sudoers",
+    "~/.gnupg/",
+];
+
+const FILE_READERS: &[&str] = &["cat", "less", "more", "head", "tail", "bat"];
+
+/// Options of the file readers that take a value, which must not be mistaken for a file.
+const FILE_READER_VALUE_OPTIONS: &[&str] = &[
+    "-n",
+    "--lines",
+    "-c",
+    "--bytes",
+    "-l",
+    "--language",
+    "-r",
+    "--line-range",
+    "-H",
+    "--highlight-line",
+    "--style",
+    "--theme",
+];
+
+/// Result of detecting a read of a sensitive file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SensitiveReadMatch {
+    /// The file reading command (cat, less, more, head, tail, bat).
+    pub command: String,
+    /// The path as written in the command.
+    pub path: String,
+    /// The sensitive path pattern that was matched.
+    pub sensitive_path: String,
+}
+
+/// Check if a bash command prints a credentials or secrets file.
+///
+/// Uses [`DEFAULT_SENSITIVE_PATHS`]; see [`check_sensitive_file_read_with`].
+#[must_use]
+pub fn check_sensitive_file_read(cmd: &str) -> Option<SensitiveReadMatch> {
+    check_sensitive_file_read_with(cmd, DEFAULT_SENSITIVE_PATHS)
+}
+
+/// Check if a bash command reads one of `sensitive_paths` with `cat`, `less`, `more`,
+/// `head`, `tail`, or `bat`.
+///
+/// Paths are compared after expanding `~` and `$HOME`, and a wildcard in the command
+/// (e.g. `cat ~/.ssh/*`) matches if it would cover a sensitive file.
+#[must_use]
+pub fn check_sensitive_file_read_with(
+    cmd: &str,
+    sensitive_paths: &[&str],
+) -> Option<SensitiveReadMatch> {
+    for (program, args) in command_invocations(cmd) {
+        if !FILE_READERS.contains(&program.as_str()) {
+            continue;
+        }
+
+        let mut iter = args.iter().map(String::as_str);
+        while let Some(arg) = iter.next() {
+            if FILE_READER_VALUE_OPTIONS.contains(&arg) {
+                iter.next();
+                continue;
+            }
+            if arg.starts_with('-') || arg == "<" {
+                continue;
+            }
+            if let Some(sensitive) = sensitive_paths
+                .iter()
+                .find(|sensitive| is_sensitive_path(arg, sensitive))
+            {
+                return Some(SensitiveReadMatch {
+                    command: program,
+                    path: arg.to_string(),
+                    sensitive_path: (*sensitive).to_string(),
+                });
+            }
+        }
+    }
+
+    None
+}
+
+fn is_sensitive_path(path: &str, sensitive: &str) -> bool {
+    let path = normalize_glob_path(path);
+    if let Some(dir) = sensitive.strip_suffix('/') {
+        let dir = normalize_glob_path(dir);
+        return path == dir || path.starts_with(&format!("{dir}/"));
+    }
+
+    let sensitive = normalize_glob_path(sensitive);
+    // Either side may be a wildcard: `~/.ssh/id_rsa*` covers `~/.ssh/id_rsa.pub`,
+    // and `cat ~/.ssh/*` covers `~/.ssh/id_rsa*`.
+    wildcard_match(&sensitive, &path) || wildcard_match(&path, &sensitive)
+}
+
+/// Normalize a path that may contain wildcards in its last component.
+fn normalize_glob_path(path: &str) -> String {
+    let path = path
+        .strip_prefix("$HOME")
+        .or_else(|| path.strip_prefix("${HOME}"))
+        .map_or_else(|| path.to_string(), |rest| format!("~{rest}"));
+    if !path.contains(['*', '?']) {
+        return fold_path_case(normalize_path(&path));
+    }
+    match path.rsplit_once('/') {
+        Some((dir, file)) if !dir.contains(['*', '?']) => fold_path_case(format!(
+            "{}/{file}",
+            normalize_path(if dir.is_empty() { "/" } else { dir }).trim_end_matches('/')
+        )),
+        _ => fold_path_case(expand_home(&path)),
+    }
+}
+
+/// Match `text` against a pattern where `*` and `?` do not cross `/`.
+fn wildcard_match(pattern: &str, text: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let text: Vec<char> = text.chars().collect();
+    let (mut p, mut t) = (0, 0);
+    let mut backtrack: Option<(usize, usize)> = None;
+
+    while t < text.len() {
+        match pattern.get(p) {
+            Some('*') => {
+                backtrack = Some((p, t));
+                p += 1;
+            }
+            Some(&c) if c == text[t] || (c == '?' && text[t] != '/') => {
+                p += 1;
+                t += 1;
+            }
+            _ => match backtrack {
+                Some((star, matched)) if text[matched] != '/' => {
+                    p = star + 1;
+                    t = matched + 1;
+                    backtrack = Some((star, t));
+                }
+                _ => return false,
+            },
+        }
+    }
+
+    pattern[p..].iter().all(|&c| c == '*')
+}
+
+// ============================================================================
+// Dangerous chmod detection
+// ============================================================================
+
+/// Shared, world-writable directories where setuid/setgid binaries can be planted.
+const WORLD_WRITABLE_DIRS: &[&str] = &["/tmp", "/var/tmp", "/dev/shm"];
+
+/// Result of detecting a dangerous `chmod`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ChmodDangerousMatch {
+    /// The mode as written in the command (e.g. `777`, `a+w`, `u+s`).
+    pub permissions: String,
+    /// The targeted path as written in the command.
+    pub target_path: String,
+    /// Whether `-R`/`--recursive` was given.
+    pub is_recursive: bool,
+}
+
+/// Check if a bash command runs a dangerous `chmod`.
+///
+/// Detects world-writable modes (`777`, `a+w`, `o+w`) on any path, setuid/setgid
+/// bits (`4755`, `u+s`, `g+s`) on paths under `/tmp`, `/var/tmp`, `/dev/shm`, or the
+/// home directory, and recursive chmod of the home directory or any of its ancestors.
+#[must_use]
+pub fn is_chmod_dangerous(cmd: &str) -> Option<ChmodDangerousMatch> {
+    for (program, args) in command_invocations(cmd) {
+        if program != "chmod" {
+            continue;
+        }
+
+        let mut is_recursive = false;
+        let mut mode = None;
+        let mut targets = Vec::new();
+        for arg in &args {
+            match arg.as_str() {
+                "-R" | "--recursive" => is_recursive = true,
+                _ if arg.starts_with("--") => {}
+                // Short options, which cannot be confused with modes like `-w`
+                _ if arg.starts_with('-') && arg[1..].chars().all(|c| "cfvR".contains(c)) => {
+                    is_recursive |= arg.contains('R');
+                }
+                _ if mode.is_none() => mode = Some(arg.as_str()),
+                _ => targets.push(arg.as_str()),
+            }
+        }
+        let Some(mode) = mode else {
+            continue;
+        };
+
+        let world_writable = chmod_grants_world_write(mode);
+        let setid = chmod_grants_setid(mode);
+        let target = targets.into_iter().find(|target| {
+            world_writable
+                || (setid && is_user_writable_location(target))
+                || (is_recursive && is_home_or_ancestor(target))
+        });
+
+        if let Some(target) = target {
+            return Some(ChmodDangerousMatch {
+                permissions: mode.to_string(),
+                target_path: target.to_string(),
+                is_recursive,
+            });
+        }
+    }
+
+    None
+}
+
+/// Whether an octal or symbolic mode makes files writable by others.
+fn chmod_grants_world_write(mode: &str) -> bool {
+    if let Ok(octal) = u32::from_str_radix(mode, 8) {
+        return mode.chars().all(|c| c.is_ascii_digit()) && octal & 0o002 != 0;
+    }
+    symbolic_mode_clauses(mode).any(|(who, perms)| who.contains(['a', 'o']) && perms.contains('w'))
+}
+
+/// Whether an octal or symbolic mode sets the setuid or setgid bit.
+fn chmod_grants_setid(mode: &str) -> bool {
+    if let Ok(octal) = u32::from_str_radix(mode, 8) {
+        return mode.chars().all(|c| c.is_ascii_digit()) && octal & 0o6000 != 0;
+    }
+    symbolic_mode_clauses(mode).any(|(_, perms)| perms.contains('s'))
+}
+
+/// Split a symbolic mode (e.g. `u+x,go=rw`) into the `(who, perms)` of each clause
+/// that adds or sets permissions. Clauses that only remove permissions are skipped.
+fn symbolic_mode_clauses(mode: &str) -> impl Iterator<Item = (&str, &str)> {
+    mode.split(',').filter_map(|clause| {
+        let op = clause.find(['+', '=', '-'])?;
+        let (who, rest) = clause.split_at(op);
+        rest.starts_with(['+', '=']).then(|| (who, &rest[1..]))
+    })
+}
+
+fn is_user_writable_location(path: &str) -> bool {
+    let path = normalize_path(&path.replace("$HOME", "~"));
+    let under = |dir: &str| path == dir || path.starts_with(&format!("{dir}/"));
+    WORLD_WRITABLE_DIRS.iter().any(|dir| under(dir))
+        || std::env::var("HOME").is_ok_and(|home| under(home.trim_end_matches('/')))
+}
+
+fn is_home_or_ancestor(path: &str) -> bool {
+    let Ok(home) = std::env::var("HOME") else {
+        return false;
+    };
+    let path = normalize_path(&path.replace("$HOME", "~"));
+    let path = path.trim_end_matches('/');
+    path.is_empty() || home.trim_end_matches('/') == path || home.starts_with(&format!("{path}/"))
+}
+
+// ============================================================================
+// Crontab modification detection
+// ============================================================================
+
+/// System cron files and directories whose entries run persistently.
+const CRON_PATHS: &[&str] = &[
+    "/etc/crontab",
+    "/etc/cron.d/",
+    "/etc/cron.hourly/",
+    "/etc/cron.daily/",
+    "/etc/cron.weekly/",
+    "/etc/cron.monthly/",
+    "/var/spool/cron/",
+];
+
+/// What a `crontab` invocation does.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "lowercase")
+)]
+pub enum CrontabAction {
+    /// `crontab -r` removes every job.
+    Remove,
+    /// `crontab -e`, or installing a crontab from a file or stdin.
+    Edit,
+    /// `crontab -l` only prints the jobs.
+    List,
+}
+
+impl CrontabAction {
+    /// Returns the lowercase name of the action.
+    #[must_use]
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::Remove => "remove",
+            Self::Edit => "edit",
+            Self::List => "list",
+        }
+    }
+}
+
+impl std::fmt::Display for CrontabAction {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Result of detecting a `crontab` command.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct CrontabMatch {
+    /// What the command does to the crontab.
+    pub action: CrontabAction,
+    /// The user whose crontab is targeted with `-u`, if given.
+    pub user: Option<String>,
+}
+
+/// Check if a bash command runs `crontab`.
+///
+/// `crontab -r` is [`CrontabAction::Remove`]; `crontab -e` and installing a crontab
+/// from a file or stdin (`crontab jobs.txt`, `... | crontab -`) are
+/// [`CrontabAction::Edit`]; `crontab -l` is [`CrontabAction::List`]. When a command
+/// runs `crontab` more than once (e.g. `crontab -l | ... | crontab -`), a
+/// modification is reported in preference to a listing.
+#[must_use]
+pub fn check_crontab_modification(cmd: &str) -> Option<CrontabMatch> {
+    let mut listing = None;
+
+    for (program, args) in command_invocations(cmd) {
+        if program != "crontab" {
+            continue;
+        }
+
+        let mut user = None;
+        let mut action = CrontabAction::Edit;
+        let mut iter = args.iter();
+        while let Some(arg) = iter.next() {
+            match arg.as_str() {
+                "-u" => user = iter.next().cloned(),
+                "-" => {}
+                flag if flag.starts_with('-') && !flag.starts_with("--") => {
+                    if flag.contains('r') {
+                        action = CrontabAction::Remove;
+                    } else if flag.contains('l') && action != CrontabAction::Remove {
+                        action = CrontabAction::List;
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        let found = CrontabMatch { action, user };
+        if action == CrontabAction::List {
+            listing.get_or_insert(found);
+        } else {
+            return Some(found);
+        }
+    }
+
+    listing
+}
+
+/// Check if a file path is a system cron file (`/etc/crontab`, `/etc/cron.d/`,
+/// `/etc/cron.{hourly,daily,weekly,monthly}/`, or a spool file under `/var/spool/cron/`).
+#[must_use]
+pub fn is_cron_file(path: &str) -> bool {
+    let path = normalize_path(path);
+    CRON_PATHS.iter().any(|cron| {
+        path == cron.trim_end_matches('/') || (cron.ends_with('/') && path.starts_with(cron))
+    })
+}
+
+// ============================================================================
+// Hosts and DNS configuration modification detection
+// ============================================================================
+
+/// Files that control name resolution. A trailing `/` protects everything under a directory.
+const NAME_RESOLUTION_FILES: &[&str] = &[
+    "/etc/hosts",
+    "/etc/resolv.conf",
+    "/etc/nsswitch.conf",
+    "/etc/systemd/resolved.conf",
+    "/etc/systemd/resolved.conf.d/",
+];
+
+static OUTPUT_REDIRECT_PATTERN: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r#">>?\|?\s*["']?(?P<path>[^\s"';|&()]+)"#).unwrap());
+
+/// Check if a bash command or a file edit modifies `/etc/hosts` or DNS configuration.
+///
+/// On the command side, detects output redirection (`> /etc/hosts`, `>> /etc/hosts`),
+/// `tee`, in-place `sed`/`perl` edits, `cp`/`mv`/`install`/`ln` onto the file, and
+/// `dd of=...`. `file_path` is the target of a file write tool, if any. Covered files
+/// are `/etc/hosts`, `/etc/resolv.conf`, `/etc/nsswitch.conf`, and the
+/// systemd-resolved configuration (`/etc/systemd/resolved.conf` and
+/// `/etc/systemd/resolved.conf.d/`).
+#[must_use]
+pub fn check_hosts_file_modification(cmd: &str, file_path: Option<&str>) -> bool {
+    if file_path.is_some_and(is_name_resolution_file) {
+        return true;
+    }
+
+    if OUTPUT_REDIRECT_PATTERN
+        .captures_iter(cmd)
+        .any(|caps| is_name_resolution_file(&caps["path"]))
+    {
+        return true;
+    }
+
+    command_invocations(cmd).any(|(program, args)| {
+        let positional = || args.iter().filter(|arg| !arg.starts_with('-'));
+        match program.as_str() {
+            "tee" => positional().any(|arg| is_name_resolution_file(arg)),
+            "sed" | "perl" => {
+                args.iter()
+                    .any(|arg| arg.starts_with("-i") || arg.starts_with("--in-place"))
+                    && positional().any(|arg| is_name_resolution_file(arg))
+            }
+            "cp" | "mv" | "install" | "ln" => positional()
+                .next_back()
+                .is_some_and(|arg| is_name_resolution_file(arg)),
+            "dd" => args
+                .iter()
+                .filter_map(|arg| arg.strip_prefix("of="))
+                .any(is_name_resolution_file),
+            _ => false,
+        }
+    })
+}
+
+fn is_name_resolution_file(path: &str) -> bool {
+    let path = normalize_path(path);
+    NAME_RESOLUTION_FILES.iter().any(|file| {
+        path == file.trim_end_matches('/') || (file.ends_with('/') && path.starts_with(file))
+    })
+}
+
+// ============================================================================
+// Disk-destructive command detection
+// ============================================================================
+
+/// `diskpart` script commands that erase a disk or format a volume.
+#[cfg(windows)]
+static DISKPART_DESTRUCTIVE_PATTERN: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(?i)\b(clean(\s+all)?|format\s+fs\s*=)").unwrap());
+
+/// A pipe into `diskpart`, which reads its script from standard input.
+#[cfg(windows)]
+static DISKPART_PIPE_PATTERN: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(?i)\|\s*diskpart(?:\.exe)?\b").unwrap());
+
+/// Result of detecting a command that formats or wipes a disk or volume.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct DiskDestructiveMatch {
+    /// The offending command (e.g. `Format-Volume`, `diskpart clean`, `del /s`).
+    pub command: String,
+    /// The drive or disk number targeted, when it could be determined.
+    pub target: Option<String>,
+}
+
+/// Check if a command formats or wipes a disk or volume.
+///
+/// Detects `Format-Volume`, `Clear-Disk`, `diskpart` fed `clean` or `format fs=...`,
+/// `format C:`, and `del /s` on a drive root.
+///
+/// This check is Windows-only. On non-Windows platforms it always returns `None`.
+#[must_use]
+#[cfg_attr(not(windows), expect(clippy::missing_const_for_fn))]
+pub fn check_disk_destructive(cmd: &str) -> Option<DiskDestructiveMatch> {
+    #[cfg(windows)]
+    {
+        command_invocations(cmd)
+            .find_map(|(program, args)| windows_disk_destructive(cmd, &program, &args))
+    }
+
+    #[cfg(not(windows))]
+    {
+        let _ = cmd;
+        None
+    }
+}
+
+#[cfg(windows)]
+fn windows_disk_destructive(
+    cmd: &str,
+    program: &str,
+    args: &[String],
+) -> Option<DiskDestructiveMatch> {
+    let option_value = |name: &str| {
+        args.iter()
+            .position(|arg| arg.eq_ignore_ascii_case(name))
+            .and_then(|index| args.get(index + 1))
+            .cloned()
+    };
+    let drive_root_arg = || args.iter().find(|arg| is_drive_root(arg)).cloned();
+
+    let program = program.to_ascii_lowercase();
+    let program = program.strip_suffix(".exe").unwrap_or(&program);
+    let (command, target) = match program {
+        "format-volume" => (
+            "Format-Volume",
+            option_value("-DriveLetter").or_else(|| option_value("-Path")),
+        ),
+        "clear-disk" => ("Clear-Disk", option_value("-Number")),
+        "diskpart" => {
+            let operation = DISKPART_DESTRUCTIVE_PATTERN
+                .find(diskpart_input(cmd)?)?
+                .as_str();
+            let command = if operation.to_ascii_lowercase().starts_with("clean") {
+                "diskpart clean"
+            } else {
+                "diskpart format"
+            };
+            (command, None)
+        }
+        "format" | "format.com" => ("format", Some(drive_root_arg()?)),
+        "del" | "erase" => {
+            if !args.iter().any(|arg| arg.eq_ignore_ascii_case("/s")) {
+                return None;
+            }
+            ("del /s", Some(drive_root_arg()?))
+        }
+        _ => return None,
+    };
+    Some(DiskDestructiveMatch {
+        command: command.to_string(),
+        target,
+    })
+}
+
+/// The command piped into `diskpart`, e.g. `(echo select disk 1 & echo clean)` in
+/// `(echo select disk 1 & echo clean) | diskpart`.
+#[cfg(windows)]
+fn diskpart_input(cmd: &str) -> Option<&str> {
+    let pipe = DISKPART_PIPE_PATTERN.find(cmd)?;
+    let before = cmd[..pipe.start()].trim_end();
+    let start = if before.ends_with(')') {
+        // A parenthesized group of `echo` commands: find its opening parenthesis
+        let mut depth = 0usize;
+        before.char_indices().rev().find_map(|(i, c)| {
+            match c {
+                ')' => depth += 1,
+                '(' => {
+                    depth = depth.saturating_sub(1);
+                    if depth == 0 {
+                        return Some(i);
+                    }
+                }
+                _ => {}
+            }
+            None
+        })?
+    } else {
+        before.rfind(['&', ';', '|']).map_or(0, |i| i + 1)
+    };
+    Some(&before[start..])
+}
+
+/// Whether `path` is a bare drive or its root, e.g. `C:`, `C:\`, or `C:\*`.
+#[cfg(windows)]
+fn is_drive_root(path: &str) -> bool {
+    let mut chars = path.chars();
+    let (Some(letter), Some(':')) = (chars.next(), chars.next()) else {
+        return false;
+    };
+    letter.is_ascii_alphabetic()
+        && matches!(chars.as_str(), "" | "\\" | "/" | "\\*" | "\\*.*" | "/*")
+}
+
+// ============================================================================
+// Pattern preloading
+// ============================================================================
+
+/// Compile every regex used by the checks now instead of on first use.
+///
+/// Long-running hook servers call this at startup so the first request does not
+/// pay for regex compilation.
+pub fn preload_patterns() {
+    LazyLock::force(&RM_PATTERN);
+    LazyLock::force(&DESTRUCTIVE_REGEXES);
+    #[cfg(not(windows))]
+    LazyLock::force(&FIND_CHECK);
+    #[cfg(windows)]
+    LazyLock::force(&NUL_REDIRECT_PATTERN);
+    #[cfg(windows)]
+    LazyLock::force(&DISKPART_DESTRUCTIVE_PATTERN);
+    #[cfg(windows)]
+    LazyLock::force(&DISKPART_PIPE_PATTERN);
+    for pattern in [
+        &RUST_ALLOW_PATTERN,
+        &RUST_EXPECT_PATTERN,
+        &RUST_OUTER_ALLOW_PATTERN,
+        &RUST_OUTER_EXPECT_PATTERN,
+        &RUST_INNER_ALLOW_PATTERN,
+        &RUST_INNER_EXPECT_PATTERN,
+        &RUST_CFG_ATTR_PATTERN,
+        &RUST_CFG_ATTR_ITEM_PATTERN,
+        &RUST_QUOTE_MACRO_PATTERN,
+        &PM_COMMAND_PATTERN,
+        &PM_EXEC_PATTERN,
+        &SCRIPT_RUNTIME_PATTERN,
+        &WORKSPACES_FIELD_PATTERN,
+        &VOLTA_NODE_PATTERN,
+        &ENGINES_NODE_PATTERN,
+        &PID_LOOKUP_PATTERN,
+        &SQL_DROP_PATTERN,
+        &SQL_TRUNCATE_PATTERN,
+        &SQL_DELETE_PATTERN,
+        &SQL_WHERE_PATTERN,
+        &BASE64_DECODER_PATTERN,
+        &BASE64_EXEC_PATTERN,
+        &BASE64_PAYLOAD_PATTERN,
+        &OUTPUT_REDIRECT_PATTERN,
+        &HEREDOC_PATTERN,
+        &HEREDOC_SHELL_PATTERN,
+        &HEREDOC_PIPE_TO_SHELL_PATTERN,
+    ] {
+        LazyLock::force(pattern);
+    }
+}
+
 #[cfg(test)]
 mod tests;
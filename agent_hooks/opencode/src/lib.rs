@@ -5,9 +5,11 @@
 #![expect(clippy::needless_pass_by_value)]
 
 use agent_hooks::{
-    PackageManagerCheckResult, RustAllowCheckResult, check_dangerous_path_command,
-    check_destructive_find, check_package_manager, check_rust_allow_attributes, has_nul_redirect,
-    is_rm_command, is_rust_file,
+    CrontabAction, PackageManager, PackageManagerCheckResult, RustAllowCheckResult,
+    check_crontab_modification, check_dangerous_path_command, check_destructive_find,
+    check_hosts_file_modification, check_mount_operation, check_package_manager,
+    check_rust_allow_attributes, check_sensitive_file_read_with, check_sudo_escalation,
+    find_lock_files_with_paths, has_nul_redirect, is_cron_file, is_rm_command, is_rust_file,
 };
 use napi_derive::napi;
 
@@ -37,6 +39,109 @@ pub fn has_nul_redirect_js(cmd: String) -> bool {
     has_nul_redirect(&cmd)
 }
 
+/// Check if a command opens an interactive root shell (`sudo -i`, `sudo su -`, `su -`, ...).
+///
+/// Returns the escalation type if detected, or `null` if safe.
+#[napi(js_name = "checkSudoEscalation")]
+pub fn check_sudo_escalation_js(cmd: String) -> Option<String> {
+    check_sudo_escalation(&cmd).map(|escalation| escalation.escalation_type.to_string())
+}
+
+/// Result of detecting a mount operation.
+#[napi(object)]
+pub struct MountOperationResult {
+    /// The mount command (mount, umount, losetup, bindfs, fusermount).
+    pub command: String,
+    /// The mount point or device operated on, if one was given.
+    pub target: Option<String>,
+    /// The severity (`medium`, or `high` for bind mounts of dangerous paths).
+    pub severity: String,
+}
+
+/// Check if a command mounts, unmounts, or attaches a filesystem or loop device.
+///
+/// Returns the command, target, and severity if detected, or `null` if safe.
+#[napi(js_name = "checkMountOperation")]
+pub fn check_mount_operation_js(cmd: String) -> Option<MountOperationResult> {
+    check_mount_operation(&cmd).map(|mount| MountOperationResult {
+        command: mount.command,
+        target: mount.target,
+        severity: mount.severity.to_string(),
+    })
+}
+
+/// Result of detecting a read of a sensitive file.
+#[napi(object)]
+pub struct SensitiveReadResult {
+    /// The file reading command (cat, less, more, head, tail, bat).
+    pub command: String,
+    /// The path as written in the command.
+    pub path: String,
+    /// The sensitive path pattern that was matched.
+    pub sensitive_path: String,
+}
+
+/// Check if a command prints one of `sensitive_paths` with cat/less/more/head/tail/bat.
+///
+/// Returns the command and matched path if detected, or `null` if safe.
+#[napi(js_name = "checkSensitiveFileRead")]
+pub fn check_sensitive_file_read_js(
+    cmd: String,
+    sensitive_paths: Vec<String>,
+) -> Option<SensitiveReadResult> {
+    let paths: Vec<&str> = sensitive_paths.iter().map(String::as_str).collect();
+    check_sensitive_file_read_with(&cmd, &paths).map(|read| SensitiveReadResult {
+        command: read.command,
+        path: read.path,
+        sensitive_path: read.sensitive_path,
+    })
+}
+
+/// What a `crontab` command does.
+#[napi(string_enum)]
+pub enum CrontabCheck {
+    /// `crontab -r` removes every job.
+    Remove,
+    /// `crontab -e`, or installing a crontab from a file or stdin.
+    Edit,
+    /// `crontab -l` only prints the jobs.
+    List,
+}
+
+impl From<CrontabAction> for CrontabCheck {
+    fn from(action: CrontabAction) -> Self {
+        match action {
+            CrontabAction::Remove => Self::Remove,
+            CrontabAction::Edit => Self::Edit,
+            CrontabAction::List => Self::List,
+        }
+    }
+}
+
+/// Check if a command runs `crontab`.
+///
+/// Returns the crontab action if detected, or `null` otherwise.
+#[napi(js_name = "checkCrontabModification")]
+pub fn check_crontab_modification_js(cmd: String) -> Option<CrontabCheck> {
+    check_crontab_modification(&cmd).map(|crontab| crontab.action.into())
+}
+
+/// Check if a file path is a system cron file (`/etc/crontab`, `/etc/cron.d/`, ...).
+#[napi(js_name = "isCronFile")]
+#[must_use]
+pub fn is_cron_file_js(file_path: String) -> bool {
+    is_cron_file(&file_path)
+}
+
+/// Check if a command or file edit modifies `/etc/hosts` or DNS configuration.
+///
+/// `file_path` is the target of a file write, if any. Returns `true` if the change should be blocked.
+#[napi(js_name = "checkHostsFileModification")]
+#[must_use]
+pub fn check_hosts_file_modification_js(cmd: String, file_path: Option<String>) -> bool {
+    check_hosts_file_modification(&cmd, file_path.as_deref())
+}
+
 /// Check if a file path is a Rust file.
 #[napi(js_name = "isRustFile")]
 #[must_use]
@@ -55,6 +160,12 @@ pub enum RustAllowCheck {
     HasExpect,
     /// Found both #[allow(...)] and #[expect(...)] attributes.
     HasBoth,
+    /// Found only `#[cfg_attr(..., allow(...))]` attributes.
+    HasCfgAttrAllow,
+    /// Found only `#[cfg_attr(..., expect(...))]` attributes.
+    HasCfgAttrExpect,
+    /// Found only allow/expect attributes inside `quote! { ... }`.
+    HasMacroGenerated,
 }
 
 impl From<RustAllowCheckResult> for RustAllowCheck {
@@ -64,6 +175,9 @@ impl From<RustAllowCheckResult> for RustAllowCheck {
             RustAllowCheckResult::HasAllow => Self::HasAllow,
             RustAllowCheckResult::HasExpect => Self::HasExpect,
             RustAllowCheckResult::HasBoth => Self::HasBoth,
+            RustAllowCheckResult::HasCfgAttrAllow => Self::HasCfgAttrAllow,
+            RustAllowCheckResult::HasCfgAttrExpect => Self::HasCfgAttrExpect,
+            RustAllowCheckResult::HasMacroGenerated => Self::HasMacroGenerated,
         }
     }
 }
@@ -82,11 +196,15 @@ pub fn check_rust_allow_attributes_js(content: String) -> RustAllowCheck {
 pub struct DangerousPathResult {
     /// The dangerous path that was matched.
     pub matched_path: String,
-    /// The command type (rm, trash, mv).
+    /// The command type (rm, trash, mv, rsync, tar, ln).
     pub command_type: String,
+    /// For `mv`, the dangerous path matched by a source argument.
+    pub mv_from: Option<String>,
+    /// For `mv`, the dangerous path matched by the destination argument.
+    pub mv_to: Option<String>,
 }
 
-/// Check if a bash command targets dangerous paths with rm/trash/mv.
+/// Check if a bash command targets dangerous paths with rm/trash/mv/rsync/tar/ln.
 ///
 /// Returns the matched dangerous path and command type if detected, or `null` if safe.
 #[napi(js_name = "checkDangerousPathCommand")]
@@ -98,6 +216,11 @@ pub fn check_dangerous_path_command_js(
     check_dangerous_path_command(&cmd, &paths).map(|check| DangerousPathResult {
         matched_path: check.matched_path,
         command_type: check.command_type,
+        mv_from: check
+            .mv_direction
+            .as_ref()
+            .and_then(|direction| direction.from.clone()),
+        mv_to: check.mv_direction.and_then(|direction| direction.to),
     })
 }
 
@@ -125,6 +248,9 @@ pub struct PackageManagerCheckResultJs {
     pub expected_pm: Option<String>,
     /// Lock files detected (for Mismatch/Ambiguous).
     pub detected_lock_files: Option<Vec<String>>,
+    /// Full paths of the detected lock files (for Mismatch/Ambiguous), e.g.
+    /// `/home/user/project/pnpm-lock.yaml`.
+    pub lock_file_paths: Option<Vec<String>>,
 }
 
 /// Check if a bash command uses a mismatched package manager.
@@ -140,20 +266,22 @@ pub fn check_package_manager_js(cmd: String, start_dir: String) -> PackageManage
             command_pm: None,
             expected_pm: None,
             detected_lock_files: None,
+            lock_file_paths: None,
         },
         PackageManagerCheckResult::Matching => PackageManagerCheckResultJs {
             result: PackageManagerCheck::Matching,
             command_pm: None,
             expected_pm: None,
             detected_lock_files: None,
+            lock_file_paths: None,
         },
         PackageManagerCheckResult::Mismatch {
             command_pm,
             expected_pm,
         } => PackageManagerCheckResultJs {
             result: PackageManagerCheck::Mismatch,
-            command_pm: Some(command_pm.name().to_string()),
-            expected_pm: Some(expected_pm.name().to_string()),
+            command_pm: Some(command_pm.to_string()),
+            expected_pm: Some(expected_pm.to_string()),
             detected_lock_files: Some(
                 expected_pm
                     .lock_files()
@@ -161,13 +289,14 @@ pub fn check_package_manager_js(cmd: String, start_dir: String) -> PackageManage
                     .map(|s| (*s).to_string())
                     .collect(),
             ),
+            lock_file_paths: Some(lock_file_paths(path, &[expected_pm])),
         },
         PackageManagerCheckResult::Ambiguous {
             command_pm,
             detected_pms,
         } => PackageManagerCheckResultJs {
             result: PackageManagerCheck::Ambiguous,
-            command_pm: Some(command_pm.name().to_string()),
+            command_pm: Some(command_pm.to_string()),
             expected_pm: None,
             detected_lock_files: Some(
                 detected_pms
@@ -175,6 +304,16 @@ pub fn check_package_manager_js(cmd: String, start_dir: String) -> PackageManage
                     .flat_map(|pm| pm.lock_files().iter().map(|s| (*s).to_string()))
                     .collect(),
             ),
+            lock_file_paths: Some(lock_file_paths(path, &detected_pms)),
         },
     }
 }
+
+/// Paths of the lock files found from `start_dir` that belong to `pms`.
+fn lock_file_paths(start_dir: &std::path::Path, pms: &[PackageManager]) -> Vec<String> {
+    find_lock_files_with_paths(start_dir)
+        .into_iter()
+        .filter(|(pm, _)| pms.contains(pm))
+        .map(|(_, path)| path.display().to_string())
+        .collect()
+}
@@ -0,0 +1,191 @@
+//! Python bindings for `agent_hooks`, exposed as the `agent_hooks` module.
+//!
+//! These bindings wrap the core check functions with `PyO3` so that Python-based
+//! agent frameworks can run the same safety checks without shelling out.
+
+use agent_hooks::{
+    PackageManagerCheckResult, RustAllowCheckResult, check_dangerous_path_command,
+    check_destructive_find, check_package_manager, check_rust_allow_attributes, is_rm_command,
+};
+use pyo3::prelude::*;
+
+/// Check if a command contains an rm (or equivalent) command.
+///
+/// Returns `True` if the command should be blocked.
+#[pyfunction(name = "is_rm_command")]
+#[must_use]
+pub fn is_rm_command_py(cmd: &str) -> bool {
+    is_rm_command(cmd)
+}
+
+/// Check if a command is a destructive find command.
+///
+/// Returns the description of the destructive pattern if found, or `None` if safe.
+#[pyfunction(name = "check_destructive_find")]
+#[must_use]
+pub fn check_destructive_find_py(cmd: &str) -> Option<&'static str> {
+    check_destructive_find(cmd)
+}
+
+/// Result of checking for Rust allow/expect attributes.
+#[pyclass(eq, eq_int, frozen, from_py_object)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RustAllowCheck {
+    /// No problematic attributes found.
+    Ok,
+    /// Found #[allow(...)] attribute.
+    HasAllow,
+    /// Found #[expect(...)] attribute.
+    HasExpect,
+    /// Found both #[allow(...)] and #[expect(...)] attributes.
+    HasBoth,
+    /// Found only `#[cfg_attr(..., allow(...))]` attributes.
+    HasCfgAttrAllow,
+    /// Found only `#[cfg_attr(..., expect(...))]` attributes.
+    HasCfgAttrExpect,
+    /// Found only allow/expect attributes inside `quote! { ... }`.
+    HasMacroGenerated,
+}
+
+impl From<RustAllowCheckResult> for RustAllowCheck {
+    fn from(result: RustAllowCheckResult) -> Self {
+        match result {
+            RustAllowCheckResult::Ok => Self::Ok,
+            RustAllowCheckResult::HasAllow => Self::HasAllow,
+            RustAllowCheckResult::HasExpect => Self::HasExpect,
+            RustAllowCheckResult::HasBoth => Self::HasBoth,
+            RustAllowCheckResult::HasCfgAttrAllow => Self::HasCfgAttrAllow,
+            RustAllowCheckResult::HasCfgAttrExpect => Self::HasCfgAttrExpect,
+            RustAllowCheckResult::HasMacroGenerated => Self::HasMacroGenerated,
+        }
+    }
+}
+
+/// Check if content contains #[allow(...)] or #[expect(...)] attributes.
+///
+/// This function ignores attributes in comments and string literals.
+#[pyfunction(name = "check_rust_allow_attributes")]
+#[must_use]
+pub fn check_rust_allow_attributes_py(content: &str) -> RustAllowCheck {
+    check_rust_allow_attributes(content).into()
+}
+
+/// Result of checking for dangerous path operations.
+#[pyclass(get_all, frozen, skip_from_py_object)]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DangerousPathResult {
+    /// The dangerous path that was matched.
+    pub matched_path: String,
+    /// The command type (rm, trash, mv, rsync, tar, ln).
+    pub command_type: String,
+    /// For `mv`, the dangerous path matched by a source argument.
+    pub mv_from: Option<String>,
+    /// For `mv`, the dangerous path matched by the destination argument.
+    pub mv_to: Option<String>,
+}
+
+/// Check if a bash command targets dangerous paths with rm/trash/mv/rsync/tar/ln.
+///
+/// Returns the matched dangerous path and command type if detected, or `None` if safe.
+#[pyfunction(name = "check_dangerous_path_command")]
+#[must_use]
+#[expect(clippy::needless_pass_by_value)]
+pub fn check_dangerous_path_command_py(
+    cmd: &str,
+    dangerous_paths: Vec<String>,
+) -> Option<DangerousPathResult> {
+    let paths: Vec<&str> = dangerous_paths.iter().map(String::as_str).collect();
+    check_dangerous_path_command(cmd, &paths).map(|check| DangerousPathResult {
+        matched_path: check.matched_path,
+        command_type: check.command_type,
+        mv_from: check
+            .mv_direction
+            .as_ref()
+            .and_then(|direction| direction.from.clone()),
+        mv_to: check.mv_direction.and_then(|direction| direction.to),
+    })
+}
+
+/// Result of checking for package manager mismatch.
+#[pyclass(eq, eq_int, frozen, from_py_object)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PackageManagerCheck {
+    /// No package manager command detected or no lock file found.
+    Ok,
+    /// Command matches the lock file's package manager.
+    Matching,
+    /// Command uses a different package manager than the lock file indicates (should deny).
+    Mismatch,
+    /// Multiple lock files exist (should ask).
+    Ambiguous,
+}
+
+/// Detailed result of checking for package manager mismatch.
+#[pyclass(get_all, frozen, skip_from_py_object)]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PackageManagerCheckOutcome {
+    /// The check result type.
+    pub result: PackageManagerCheck,
+    /// The package manager being used in the command (if detected).
+    pub command_pm: Option<String>,
+    /// The expected package manager based on lock file (for Mismatch).
+    pub expected_pm: Option<String>,
+    /// Package managers whose lock files were detected (for Mismatch/Ambiguous).
+    pub detected_pms: Vec<String>,
+}
+
+/// Check if a bash command uses a mismatched package manager.
+///
+/// Searches for lock files starting from `start_dir` and going up to parent directories.
+#[pyfunction(name = "check_package_manager")]
+#[must_use]
+pub fn check_package_manager_py(cmd: &str, start_dir: &str) -> PackageManagerCheckOutcome {
+    match check_package_manager(cmd, std::path::Path::new(start_dir)) {
+        PackageManagerCheckResult::Ok => PackageManagerCheckOutcome {
+            result: PackageManagerCheck::Ok,
+            command_pm: None,
+            expected_pm: None,
+            detected_pms: Vec::new(),
+        },
+        PackageManagerCheckResult::Matching => PackageManagerCheckOutcome {
+            result: PackageManagerCheck::Matching,
+            command_pm: None,
+            expected_pm: None,
+            detected_pms: Vec::new(),
+        },
+        PackageManagerCheckResult::Mismatch {
+            command_pm,
+            expected_pm,
+        } => PackageManagerCheckOutcome {
+            result: PackageManagerCheck::Mismatch,
+            command_pm: Some(command_pm.to_string()),
+            expected_pm: Some(expected_pm.to_string()),
+            detected_pms: vec![expected_pm.to_string()],
+        },
+        PackageManagerCheckResult::Ambiguous {
+            command_pm,
+            detected_pms,
+        } => PackageManagerCheckOutcome {
+            result: PackageManagerCheck::Ambiguous,
+            command_pm: Some(command_pm.to_string()),
+            expected_pm: None,
+            detected_pms: detected_pms.iter().map(ToString::to_string).collect(),
+        },
+    }
+}
+
+/// The `agent_hooks` Python module.
+#[pymodule]
+#[pyo3(name = "agent_hooks")]
+fn agent_hooks_module(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    m.add_function(wrap_pyfunction!(is_rm_command_py, m)?)?;
+    m.add_function(wrap_pyfunction!(check_destructive_find_py, m)?)?;
+    m.add_function(wrap_pyfunction!(check_rust_allow_attributes_py, m)?)?;
+    m.add_function(wrap_pyfunction!(check_dangerous_path_command_py, m)?)?;
+    m.add_function(wrap_pyfunction!(check_package_manager_py, m)?)?;
+    m.add_class::<RustAllowCheck>()?;
+    m.add_class::<DangerousPathResult>()?;
+    m.add_class::<PackageManagerCheck>()?;
+    m.add_class::<PackageManagerCheckOutcome>()?;
+    Ok(())
+}
@@ -0,0 +1,82 @@
+"""Smoke tests for the agent_hooks Python bindings.
+
+Run with:
+    maturin develop -m agent_hooks/python/Cargo.toml
+    python -m pytest agent_hooks/python/tests
+"""
+
+import os
+import tempfile
+
+import agent_hooks
+
+
+def test_is_rm_command():
+    assert agent_hooks.is_rm_command("rm -rf /tmp/test")
+    assert not agent_hooks.is_rm_command("trash file.txt")
+
+
+def test_check_destructive_find():
+    if os.name == "nt":
+        assert agent_hooks.check_destructive_find("dir | move-item") is not None
+    else:
+        assert (
+            agent_hooks.check_destructive_find("find . -name '*.tmp' -delete")
+            == "find with -delete option"
+        )
+    assert agent_hooks.check_destructive_find("find . -name '*.rs'") is None
+
+
+def test_check_rust_allow_attributes():
+    check = agent_hooks.check_rust_allow_attributes
+    assert check("#[allow(dead_code)]") == agent_hooks.RustAllowCheck.HasAllow
+    assert check("#[expect(dead_code)]") == agent_hooks.RustAllowCheck.HasExpect
+    assert (
+        check("#[allow(dead_code)]\n#[expect(unused)]")
+        == agent_hooks.RustAllowCheck.HasBoth
+    )
+    assert check("// #[allow(dead_code)]") == agent_hooks.RustAllowCheck.Ok
+    assert (
+        check("#[cfg_attr(test, expect(dead_code))]")
+        == agent_hooks.RustAllowCheck.HasCfgAttrExpect
+    )
+
+
+def test_check_dangerous_path_command():
+    result = agent_hooks.check_dangerous_path_command("rm -rf ~/*", ["~/"])
+    assert result is not None
+    assert result.matched_path == "~/"
+    assert result.command_type == "rm"
+    assert result.mv_from is None
+
+    result = agent_hooks.check_dangerous_path_command("mv ./hosts /etc/hosts", ["/etc/hosts"])
+    assert result is not None
+    assert result.mv_from is None
+    assert result.mv_to == "/etc/hosts"
+
+    assert agent_hooks.check_dangerous_path_command("rm -rf ~/Documents", ["~/"]) is None
+
+
+def test_check_package_manager():
+    with tempfile.TemporaryDirectory() as temp_dir:
+        outcome = agent_hooks.check_package_manager("npm install", temp_dir)
+        assert outcome.result == agent_hooks.PackageManagerCheck.Ok
+
+        with open(os.path.join(temp_dir, "pnpm-lock.yaml"), "w"):
+            pass
+
+        outcome = agent_hooks.check_package_manager("pnpm install", temp_dir)
+        assert outcome.result == agent_hooks.PackageManagerCheck.Matching
+
+        outcome = agent_hooks.check_package_manager("npm install", temp_dir)
+        assert outcome.result == agent_hooks.PackageManagerCheck.Mismatch
+        assert outcome.command_pm == "npm"
+        assert outcome.expected_pm == "pnpm"
+        assert outcome.detected_pms == ["pnpm"]
+
+        with open(os.path.join(temp_dir, "package-lock.json"), "w"):
+            pass
+
+        outcome = agent_hooks.check_package_manager("npm install", temp_dir)
+        assert outcome.result == agent_hooks.PackageManagerCheck.Ambiguous
+        assert sorted(outcome.detected_pms) == ["npm", "pnpm"]
@@ -0,0 +1,112 @@
+//! WebAssembly bindings for `agent_hooks`, used by browser-based agents.
+//!
+//! These bindings expose the pure string check functions via `wasm-bindgen`.
+//! Filesystem-dependent checks (such as package manager detection) are not
+//! exported because they have no meaning inside a browser sandbox.
+#![expect(clippy::needless_pass_by_value)]
+
+use agent_hooks::{
+    RustAllowCheckResult, check_dangerous_path_command, check_destructive_find,
+    check_rust_allow_attributes, is_rm_command,
+};
+use wasm_bindgen::prelude::wasm_bindgen;
+
+/// Check if a command contains an rm (or equivalent) command.
+///
+/// Returns `true` if the command should be blocked.
+#[wasm_bindgen(js_name = "isRmCommand")]
+#[must_use]
+pub fn is_rm_command_js(cmd: String) -> bool {
+    is_rm_command(&cmd)
+}
+
+/// Check if a command is a destructive find command.
+///
+/// Returns the description of the destructive pattern if found, or `undefined` if safe.
+#[wasm_bindgen(js_name = "checkDestructiveFind")]
+#[must_use]
+pub fn check_destructive_find_js(cmd: String) -> Option<String> {
+    check_destructive_find(&cmd).map(String::from)
+}
+
+/// Result of checking for Rust allow/expect attributes.
+#[wasm_bindgen]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RustAllowCheck {
+    /// No problematic attributes found.
+    Ok = "Ok",
+    /// Found #[allow(...)] attribute.
+    HasAllow = "HasAllow",
+    /// Found #[expect(...)] attribute.
+    HasExpect = "HasExpect",
+    /// Found both #[allow(...)] and #[expect(...)] attributes.
+    HasBoth = "HasBoth",
+    /// Found only `#[cfg_attr(..., allow(...))]` attributes.
+    HasCfgAttrAllow = "HasCfgAttrAllow",
+    /// Found only `#[cfg_attr(..., expect(...))]` attributes.
+    HasCfgAttrExpect = "HasCfgAttrExpect",
+    /// Found only allow/expect attributes inside `quote! { ... }`.
+    HasMacroGenerated = "HasMacroGenerated",
+}
+
+impl From<RustAllowCheckResult> for RustAllowCheck {
+    fn from(result: RustAllowCheckResult) -> Self {
+        match result {
+            RustAllowCheckResult::Ok => Self::Ok,
+            RustAllowCheckResult::HasAllow => Self::HasAllow,
+            RustAllowCheckResult::HasExpect => Self::HasExpect,
+            RustAllowCheckResult::HasBoth => Self::HasBoth,
+            RustAllowCheckResult::HasCfgAttrAllow => Self::HasCfgAttrAllow,
+            RustAllowCheckResult::HasCfgAttrExpect => Self::HasCfgAttrExpect,
+            RustAllowCheckResult::HasMacroGenerated => Self::HasMacroGenerated,
+        }
+    }
+}
+
+/// Check if content contains #[allow(...)] or #[expect(...)] attributes.
+///
+/// This function ignores attributes in comments and string literals.
+#[wasm_bindgen(js_name = "checkRustAllowAttributes")]
+#[must_use]
+pub fn check_rust_allow_attributes_js(content: String) -> RustAllowCheck {
+    check_rust_allow_attributes(&content).into()
+}
+
+/// Result of checking for dangerous path operations.
+#[wasm_bindgen(getter_with_clone)]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DangerousPathResult {
+    /// The dangerous path that was matched.
+    #[wasm_bindgen(js_name = "matchedPath")]
+    pub matched_path: String,
+    /// The command type (rm, trash, mv, rsync, tar, ln).
+    #[wasm_bindgen(js_name = "commandType")]
+    pub command_type: String,
+    /// For `mv`, the dangerous path matched by a source argument.
+    #[wasm_bindgen(js_name = "mvFrom")]
+    pub mv_from: Option<String>,
+    /// For `mv`, the dangerous path matched by the destination argument.
+    #[wasm_bindgen(js_name = "mvTo")]
+    pub mv_to: Option<String>,
+}
+
+/// Check if a bash command targets dangerous paths with rm/trash/mv/rsync/tar/ln.
+///
+/// Returns the matched dangerous path and command type if detected, or `undefined` if safe.
+#[wasm_bindgen(js_name = "checkDangerousPathCommand")]
+#[must_use]
+pub fn check_dangerous_path_command_js(
+    cmd: String,
+    dangerous_paths: Vec<String>,
+) -> Option<DangerousPathResult> {
+    let paths: Vec<&str> = dangerous_paths.iter().map(String::as_str).collect();
+    check_dangerous_path_command(&cmd, &paths).map(|check| DangerousPathResult {
+        matched_path: check.matched_path,
+        command_type: check.command_type,
+        mv_from: check
+            .mv_direction
+            .as_ref()
+            .and_then(|direction| direction.from.clone()),
+        mv_to: check.mv_direction.and_then(|direction| direction.to),
+    })
+}
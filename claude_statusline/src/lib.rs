@@ -0,0 +1,10 @@
+//! Shared helpers for `claude_statusline`.
+//!
+//! The binary renders the statusline; this library exposes the pieces that
+//! other statusline tools can reuse without duplicating logic.
+
+pub mod model_names;
+pub mod width;
+
+pub use model_names::prettify_model_name;
+pub use width::{nerd_font_width, truncate_from_left_to_width, truncate_to_width, visible_width};
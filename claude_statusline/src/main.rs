@@ -1,17 +1,22 @@
+use claude_statusline::{
+    nerd_font_width, prettify_model_name, truncate_from_left_to_width, truncate_to_width,
+    visible_width,
+};
 use crossterm::style::{Color, ResetColor, SetBackgroundColor, SetForegroundColor};
 use serde::Deserialize;
 use std::{
+    collections::HashMap,
     fmt::Write as _,
-    io::{self, Read},
-    process::Command,
-    process::ExitCode,
+    io::{self, IsTerminal, Read},
+    process::{Command, ExitCode, Output, Stdio},
+    sync::{Mutex, OnceLock, PoisonError},
+    time::{Duration, Instant},
 };
-use unicode_width::{UnicodeWidthChar, UnicodeWidthStr};
 
 #[derive(Debug, Deserialize)]
 struct StatusInput {
-    #[serde(rename = "hook_event_name")]
-    _event_name: Option<String>,
+    #[serde(rename = "hook_event_name", default)]
+    event: HookEvent,
     cwd: Option<String>,
     model: Option<ModelInfo>,
     workspace: Option<WorkspaceInfo>,
@@ -19,6 +24,22 @@ struct StatusInput {
     version: Option<String>,
     cost: Option<CostInfo>,
     context_window: Option<ContextWindow>,
+    /// Tool calls queued but not yet run.
+    tool_calls_pending: Option<u32>,
+    session_id: Option<String>,
+}
+
+/// The hook event that produced the input, which decides what the statusline shows.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+enum HookEvent {
+    PreToolUse,
+    /// The session ended: cost and context usage give way to a session summary.
+    Stop,
+    Notification,
+    /// Any other event, including the regular `Status` updates.
+    #[default]
+    #[serde(other)]
+    Unknown,
 }
 
 #[derive(Debug, Deserialize)]
@@ -40,15 +61,42 @@ struct WorkspaceInfo {
 
 #[derive(Debug, Deserialize)]
 struct ContextWindow {
-    #[expect(dead_code)]
     total_input_tokens: Option<u64>,
-    #[expect(dead_code)]
     total_output_tokens: Option<u64>,
     #[serde(rename = "context_window_size")]
     window_size: Option<u64>,
+    /// Set with `conversation_window_size` by models that keep the system prompt
+    /// in a context window of its own.
+    #[serde(rename = "system_context_window_size")]
+    system_window_size: Option<u64>,
+    #[serde(rename = "conversation_context_window_size")]
+    conversation_window_size: Option<u64>,
     current_usage: Option<CurrentUsage>,
 }
 
+/// How the model's context is sized.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ContextWindowMode {
+    /// One window shared by the system prompt and the conversation.
+    Single,
+    /// Separate windows for the system prompt and the conversation history.
+    Split { system: u64, conversation: u64 },
+}
+
+impl ContextWindow {
+    const fn mode(&self) -> ContextWindowMode {
+        match (self.system_window_size, self.conversation_window_size) {
+            (Some(system), Some(conversation)) if system > 0 && conversation > 0 => {
+                ContextWindowMode::Split {
+                    system,
+                    conversation,
+                }
+            }
+            _ => ContextWindowMode::Single,
+        }
+    }
+}
+
 #[derive(Debug, Deserialize)]
 struct CurrentUsage {
     #[serde(rename = "input_tokens")]
@@ -59,6 +107,10 @@ struct CurrentUsage {
     cache_creation_input: Option<u64>,
     #[serde(rename = "cache_read_input_tokens")]
     cache_read_input: Option<u64>,
+    /// Share of the tokens above taken by the system prompt; only reported for
+    /// split context windows.
+    #[serde(rename = "system_prompt_tokens")]
+    system_prompt: Option<u64>,
 }
 
 struct Segment {
@@ -67,26 +119,252 @@ struct Segment {
     bg: Color,
 }
 
-#[derive(Clone, Copy, Debug, Eq, PartialEq)]
-enum QualifierKind {
-    Bracket,
-    Paren,
+const POWERLINE_ARROW: char = '\u{e0b0}';
+const CONTEXT_BAR_FILLED: char = '█';
+const CONTEXT_BAR_EMPTY: char = '░';
+const CONTEXT_WARN_MARKER: char = '⚠';
+const CONTEXT_ERROR_MARKER: char = '!';
+const DEFAULT_CONTEXT_BAR_WIDTH: usize = 10;
+const DEFAULT_MAX_MODEL_WIDTH: usize = 24;
+const SESSION_ID_WIDTH: usize = 8;
+const DEFAULT_GIT_CACHE_TTL: Duration = Duration::from_secs(5);
+const DEFAULT_GIT_TIMEOUT: Duration = Duration::from_millis(500);
+const GIT_POLL_INTERVAL: Duration = Duration::from_millis(5);
+
+const USAGE: &str = "\
+Usage:
+  claude_statusline [flags] < status.json
+
+Flags:
+  --max-model-width <N>
+  --context-bar-width <N>
+  --bar-filled <CHAR>
+  --bar-empty <CHAR>
+  --cwd-depth <N>
+  --separator <CHAR>
+  --context-warn <PERCENT>
+  --context-error <PERCENT>
+  --context-critical <PERCENT>
+  --git-cache-ttl <SECONDS>
+  --git-timeout-ms <N>
+  --cost-currency <SYMBOL>
+  --cost-decimals <N>
+  --color [always|auto|never]
+  --no-color
+  --lines <1|2>
+  --tmux
+  --json
+";
+
+/// Whether the statusline is rendered with ANSI color sequences.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+enum ColorMode {
+    /// Always emit colors; Claude Code pipes stdout, so this is the default.
+    #[default]
+    Always,
+    /// Emit colors only when stdout is a terminal and `NO_COLOR` is unset.
+    Auto,
+    Never,
+}
+
+impl ColorMode {
+    fn parse(value: &str) -> Option<Self> {
+        match value {
+            "always" => Some(Self::Always),
+            "auto" => Some(Self::Auto),
+            "never" => Some(Self::Never),
+            _ => None,
+        }
+    }
+
+    fn enabled(self) -> bool {
+        match self {
+            Self::Always => true,
+            Self::Never => false,
+            Self::Auto => {
+                std::env::var_os("NO_COLOR").is_none_or(|value| value.is_empty())
+                    && io::stdout().is_terminal()
+            }
+        }
+    }
+}
+
+/// Characters drawn for filled and empty slots of the context bar.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct BarStyle {
+    filled: char,
+    empty: char,
 }
 
-#[derive(Debug)]
-struct ModelQualifier {
-    display: String,
-    kind: QualifierKind,
+impl Default for BarStyle {
+    fn default() -> Self {
+        Self {
+            filled: CONTEXT_BAR_FILLED,
+            empty: CONTEXT_BAR_EMPTY,
+        }
+    }
 }
 
-const POWERLINE_ARROW: char = '\u{e0b0}';
-const CONTEXT_BAR_SLOTS: usize = 10;
-const CONTEXT_BAR_FILLED: char = '█';
-const CONTEXT_BAR_EMPTY: char = '░';
-const CONTEXT_BAR_THRESHOLDS: [f64; CONTEXT_BAR_SLOTS] =
-    [10.0, 20.0, 30.0, 40.0, 50.0, 60.0, 70.0, 80.0, 90.0, 100.0];
+/// What is drawn between segments.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct SeparatorStyle {
+    char: char,
+    /// Powerline arrows take the next segment's background so they blend into it;
+    /// other separators are drawn on the terminal's own background.
+    is_powerline: bool,
+}
+
+impl SeparatorStyle {
+    /// A separator drawn with `ch`, treated as powerline only for the arrow glyph.
+    const fn new(ch: char) -> Self {
+        Self {
+            char: ch,
+            is_powerline: ch == POWERLINE_ARROW,
+        }
+    }
+}
+
+impl Default for SeparatorStyle {
+    fn default() -> Self {
+        Self::new(POWERLINE_ARROW)
+    }
+}
+
+/// Context usage levels (in percent) at which the context bar gains a marker.
+/// Unset levels never trigger.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+struct ContextThresholds {
+    warn: Option<u8>,
+    error: Option<u8>,
+    critical: Option<u8>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ContextLevel {
+    Warn,
+    Error,
+    Critical,
+}
+
+impl ContextThresholds {
+    /// The highest level `percent` has reached, if any.
+    fn level(&self, percent: f64) -> Option<ContextLevel> {
+        let reached = |threshold: Option<u8>| threshold.is_some_and(|t| percent >= f64::from(t));
+        if reached(self.critical) {
+            Some(ContextLevel::Critical)
+        } else if reached(self.error) {
+            Some(ContextLevel::Error)
+        } else if reached(self.warn) {
+            Some(ContextLevel::Warn)
+        } else {
+            None
+        }
+    }
+
+    /// Rejects thresholds that are set out of order, e.g. a warning above the error level.
+    fn validate(&self) -> Result<(), String> {
+        let levels = [
+            ("--context-warn", self.warn),
+            ("--context-error", self.error),
+            ("--context-critical", self.critical),
+        ];
+        let set: Vec<(&str, u8)> = levels
+            .into_iter()
+            .filter_map(|(flag, value)| value.map(|value| (flag, value)))
+            .collect();
+        for pair in set.windows(2) {
+            let [(lower_flag, lower), (upper_flag, upper)] = pair else {
+                continue;
+            };
+            if lower > upper {
+                return Err(format!(
+                    "{lower_flag} ({lower}) must not be greater than {upper_flag} ({upper})"
+                ));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// How the session cost segment is written, e.g. `$ 1.23`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct CostFormat {
+    symbol: String,
+    decimals: usize,
+}
+
+impl Default for CostFormat {
+    fn default() -> Self {
+        Self {
+            symbol: "$".to_string(),
+            decimals: 2,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Options {
+    max_model_width: usize,
+    context_bar_width: usize,
+    bar_style: BarStyle,
+    separator: SeparatorStyle,
+    /// Trailing path components shown in the working directory segment.
+    cwd_depth: usize,
+    context_thresholds: ContextThresholds,
+    /// How long a directory's git ref is reused before `git` runs again.
+    git_cache_ttl: Duration,
+    /// How long a `git` call may run before its segment is dropped; zero waits forever.
+    git_timeout: Duration,
+    cost_format: CostFormat,
+    color: ColorMode,
+    /// 1 for a single powerline; 2 to move the cost and context segments to a second line.
+    lines: usize,
+    /// Emit tmux `#[fg=...,bg=...]` style directives instead of ANSI sequences.
+    tmux: bool,
+    /// Print the segment texts and full session ID as JSON instead of a powerline.
+    json: bool,
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Self {
+            max_model_width: DEFAULT_MAX_MODEL_WIDTH,
+            context_bar_width: DEFAULT_CONTEXT_BAR_WIDTH,
+            bar_style: BarStyle::default(),
+            separator: SeparatorStyle::default(),
+            cwd_depth: 1,
+            context_thresholds: ContextThresholds::default(),
+            git_cache_ttl: DEFAULT_GIT_CACHE_TTL,
+            git_timeout: DEFAULT_GIT_TIMEOUT,
+            cost_format: CostFormat::default(),
+            color: ColorMode::default(),
+            lines: 1,
+            tmux: false,
+            json: false,
+        }
+    }
+}
+
+enum ParseCliResult {
+    Help,
+    Run(Options),
+}
 
 fn main() -> ExitCode {
+    let options = match parse_cli(std::env::args().skip(1)) {
+        Ok(ParseCliResult::Run(options)) => options,
+        Ok(ParseCliResult::Help) => {
+            println!("{USAGE}");
+            return ExitCode::SUCCESS;
+        }
+        Err(message) => {
+            eprintln!("{message}\n\n{USAGE}");
+            return ExitCode::from(2);
+        }
+    };
+
+    // Color is decided by `--color`, so keep crossterm from applying its own
+    // `NO_COLOR` check on top of it.
     crossterm::style::force_color_output(true);
 
     let mut stdin = String::new();
@@ -107,18 +385,226 @@ fn main() -> ExitCode {
         }
     };
 
-    println!("{}", build_statusline(&input));
+    if options.json {
+        println!("{}", build_statusline_json(&input, &options));
+    } else if options.lines == 2 {
+        println!("{}", build_statusline_two_line(&input, &options));
+    } else {
+        println!("{}", build_statusline(&input, &options));
+    }
     ExitCode::SUCCESS
 }
 
-fn build_statusline(input: &StatusInput) -> String {
+fn parse_cli(args: impl Iterator<Item = String>) -> Result<ParseCliResult, String> {
+    let args: Vec<String> = args.collect();
+    if args.iter().any(|arg| arg == "-h" || arg == "--help") {
+        return Ok(ParseCliResult::Help);
+    }
+
+    let mut options = Options::default();
+    let mut index = 0;
+    while index < args.len() {
+        match args[index].as_str() {
+            "--max-model-width" => {
+                index += 1;
+                options.max_model_width = parse_width_value("--max-model-width", args.get(index))?;
+            }
+            "--context-bar-width" => {
+                index += 1;
+                options.context_bar_width =
+                    parse_positive_value("--context-bar-width", args.get(index))?;
+            }
+            "--bar-filled" => {
+                index += 1;
+                options.bar_style.filled = parse_char_value("--bar-filled", args.get(index))?;
+            }
+            "--bar-empty" => {
+                index += 1;
+                options.bar_style.empty = parse_char_value("--bar-empty", args.get(index))?;
+            }
+            "--separator" => {
+                index += 1;
+                options.separator =
+                    SeparatorStyle::new(parse_char_value("--separator", args.get(index))?);
+            }
+            "--cwd-depth" => {
+                index += 1;
+                options.cwd_depth = parse_positive_value("--cwd-depth", args.get(index))?;
+            }
+            "--context-warn" => {
+                index += 1;
+                options.context_thresholds.warn =
+                    Some(parse_percent_value("--context-warn", args.get(index))?);
+            }
+            "--context-error" => {
+                index += 1;
+                options.context_thresholds.error =
+                    Some(parse_percent_value("--context-error", args.get(index))?);
+            }
+            "--context-critical" => {
+                index += 1;
+                options.context_thresholds.critical =
+                    Some(parse_percent_value("--context-critical", args.get(index))?);
+            }
+            "--git-cache-ttl" => {
+                index += 1;
+                let seconds = parse_count_value("--git-cache-ttl", "seconds", args.get(index))?;
+                options.git_cache_ttl = Duration::from_secs(seconds);
+            }
+            "--git-timeout-ms" => {
+                index += 1;
+                let millis =
+                    parse_count_value("--git-timeout-ms", "milliseconds", args.get(index))?;
+                options.git_timeout = Duration::from_millis(millis);
+            }
+            "--cost-currency" => {
+                index += 1;
+                let symbol = args.get(index).ok_or("--cost-currency requires a value")?;
+                options.cost_format.symbol.clone_from(symbol);
+            }
+            "--cost-decimals" => {
+                index += 1;
+                options.cost_format.decimals =
+                    parse_width_value("--cost-decimals", args.get(index))?;
+            }
+            "--color" => {
+                // The value is optional: a bare `--color` forces colors on.
+                if let Some(mode) = args
+                    .get(index + 1)
+                    .and_then(|value| ColorMode::parse(value))
+                {
+                    options.color = mode;
+                    index += 1;
+                } else {
+                    options.color = ColorMode::Always;
+                }
+            }
+            "--no-color" => options.color = ColorMode::Never,
+            "--lines" => {
+                index += 1;
+                options.lines = parse_width_value("--lines", args.get(index))?;
+                if !(1..=2).contains(&options.lines) {
+                    return Err(format!("--lines expects 1 or 2, got: {}", options.lines));
+                }
+            }
+            "--tmux" => options.tmux = true,
+            "--json" => options.json = true,
+            other => return Err(format!("unknown flag: {other}")),
+        }
+        index += 1;
+    }
+
+    options.context_thresholds.validate()?;
+    Ok(ParseCliResult::Run(options))
+}
+
+fn parse_width_value(flag: &str, value: Option<&String>) -> Result<usize, String> {
+    let value = value.ok_or_else(|| format!("{flag} requires a value"))?;
+    value
+        .parse()
+        .map_err(|_| format!("{flag} expects a non-negative integer, got: {value}"))
+}
+
+fn parse_positive_value(flag: &str, value: Option<&String>) -> Result<usize, String> {
+    match parse_width_value(flag, value)? {
+        0 => Err(format!("{flag} must be at least 1")),
+        width => Ok(width),
+    }
+}
+
+fn parse_count_value(flag: &str, unit: &str, value: Option<&String>) -> Result<u64, String> {
+    let value = value.ok_or_else(|| format!("{flag} requires a value"))?;
+    value
+        .parse()
+        .map_err(|_| format!("{flag} expects a number of {unit}, got: {value}"))
+}
+
+fn parse_char_value(flag: &str, value: Option<&String>) -> Result<char, String> {
+    let value = value.ok_or_else(|| format!("{flag} requires a value"))?;
+    let mut chars = value.chars();
+    match (chars.next(), chars.next()) {
+        (Some(ch), None) => Ok(ch),
+        _ => Err(format!("{flag} expects a single character, got: {value}")),
+    }
+}
+
+fn parse_percent_value(flag: &str, value: Option<&String>) -> Result<u8, String> {
+    let value = value.ok_or_else(|| format!("{flag} requires a value"))?;
+    value
+        .parse()
+        .ok()
+        .filter(|percent| *percent <= 100)
+        .ok_or_else(|| format!("{flag} expects a percentage between 0 and 100, got: {value}"))
+}
+
+fn build_statusline(input: &StatusInput, options: &Options) -> String {
+    let (left_styled, _left_width) = render_powerline(
+        &build_segments(input, options),
+        select_renderer(options),
+        options.separator,
+    );
+
+    left_styled
+}
+
+/// `--lines 2` output: model, session, and location segments on the first line,
+/// cost and context usage on the second. Without cost or usage data, only the
+/// first line is printed.
+fn build_statusline_two_line(input: &StatusInput, options: &Options) -> String {
+    let format = select_renderer(options);
+    let (mut lines, _) = render_powerline(
+        &build_location_segments(input, options),
+        format,
+        options.separator,
+    );
+    let usage_segments = build_usage_segments(input, options);
+    if !usage_segments.is_empty() {
+        let (usage_styled, _) = render_powerline(&usage_segments, format, options.separator);
+        lines.push('\n');
+        lines.push_str(&usage_styled);
+    }
+    lines
+}
+
+fn select_renderer(options: &Options) -> &'static dyn Renderer {
+    if !options.color.enabled() {
+        &PlainRenderer
+    } else if options.tmux {
+        &TmuxRenderer
+    } else {
+        &AnsiRenderer
+    }
+}
+
+/// `--json` output: the segment texts as rendered, plus the untruncated session ID
+/// for correlating a statusline with its Claude Code session.
+fn build_statusline_json(input: &StatusInput, options: &Options) -> String {
+    let segments: Vec<String> = build_segments(input, options)
+        .into_iter()
+        .map(|segment| segment.text)
+        .collect();
+    serde_json::json!({
+        "session_id": input.session_id,
+        "segments": segments,
+    })
+    .to_string()
+}
+
+fn build_segments(input: &StatusInput, options: &Options) -> Vec<Segment> {
+    let mut segments = build_location_segments(input, options);
+    segments.extend(build_usage_segments(input, options));
+    segments
+}
+
+/// Model, session, working directory, and git segments.
+fn build_location_segments(input: &StatusInput, options: &Options) -> Vec<Segment> {
     let raw_model = input
         .model
         .as_ref()
         .and_then(|value| value.display_name.as_deref().or(value.id.as_deref()))
         .filter(|value| !value.is_empty())
         .unwrap_or("unknown");
-    let model = prettify_model_name(raw_model);
+    let model = truncate_to_width(&prettify_model_name(raw_model), options.max_model_width);
 
     let cwd = input
         .workspace
@@ -133,18 +619,36 @@ fn build_statusline(input: &StatusInput) -> String {
         .and_then(|workspace| workspace.project_dir.as_deref());
     let git_lookup_dir = project_dir.unwrap_or(cwd);
 
-    let mut left_segments = vec![
-        Segment {
-            text: format!("\u{f4b8} {model}"),
-            fg: rgb(245, 240, 255),
-            bg: rgb(146, 72, 177),
-        },
-        Segment {
-            text: format!("\u{f07c} {}", folder_name(cwd)),
-            fg: rgb(255, 235, 244),
-            bg: rgb(238, 96, 146),
-        },
-    ];
+    let mut left_segments = vec![Segment {
+        text: format!("\u{f4b8} {model}"),
+        fg: rgb(245, 240, 255),
+        bg: rgb(146, 72, 177),
+    }];
+
+    if let Some(session_id) = input.session_id.as_deref().filter(|id| !id.is_empty()) {
+        left_segments.push(Segment {
+            text: session_id.chars().take(SESSION_ID_WIDTH).collect(),
+            fg: rgb(214, 214, 214),
+            bg: rgb(88, 88, 88),
+        });
+    }
+
+    if let Some(pending) = input.tool_calls_pending.filter(|pending| *pending > 0) {
+        left_segments.push(Segment {
+            text: format!("⚙ {pending}"),
+            fg: rgb(255, 247, 235),
+            bg: rgb(186, 104, 200),
+        });
+    }
+
+    left_segments.push(Segment {
+        text: format!(
+            "\u{f07c} {}",
+            folder_path_abbreviated(cwd, options.cwd_depth)
+        ),
+        fg: rgb(255, 235, 244),
+        bg: rgb(238, 96, 146),
+    });
 
     if let Some(project_dir) = project_dir
         && project_dir != cwd
@@ -156,7 +660,9 @@ fn build_statusline(input: &StatusInput) -> String {
         });
     }
 
-    if let Some(git_ref) = git_ref_for_dir(git_lookup_dir) {
+    if let Some(git_ref) =
+        git_ref_for_dir_cached(git_lookup_dir, options.git_cache_ttl, options.git_timeout)
+    {
         left_segments.push(Segment {
             text: format!("\u{e725} {git_ref}"),
             fg: rgb(232, 247, 239),
@@ -164,8 +670,27 @@ fn build_statusline(input: &StatusInput) -> String {
         });
     }
 
-    if let Some(cost_label) = format_cost(input) {
-        left_segments.push(Segment {
+    left_segments
+}
+
+/// Session cost and context window usage segments, or the session summary for
+/// `Stop` events.
+fn build_usage_segments(input: &StatusInput, options: &Options) -> Vec<Segment> {
+    let mut usage_segments = Vec::new();
+
+    if input.event == HookEvent::Stop {
+        if let Some(summary) = session_summary_label(input, &options.cost_format) {
+            usage_segments.push(Segment {
+                text: summary,
+                fg: rgb(235, 245, 255),
+                bg: rgb(48, 120, 168),
+            });
+        }
+        return usage_segments;
+    }
+
+    if let Some(cost_label) = format_cost(input, &options.cost_format) {
+        usage_segments.push(Segment {
             text: cost_label,
             fg: rgb(235, 245, 255),
             bg: rgb(48, 120, 168),
@@ -174,284 +699,78 @@ fn build_statusline(input: &StatusInput) -> String {
 
     if let Some(percent) = context_usage_percent(input) {
         let (text_color, fill_color) = context_segment_colors(percent);
-        left_segments.push(Segment {
-            text: context_usage_label(percent),
+        usage_segments.push(Segment {
+            text: context_usage_label(
+                percent,
+                options.context_bar_width,
+                &options.context_thresholds,
+                options.bar_style,
+            ),
             fg: text_color,
             bg: fill_color,
         });
     }
 
-    let (left_styled, _left_width) = render_powerline(&left_segments);
-
-    left_styled
-}
-
-/// Transform a raw model ID into a human-friendly display name.
-///
-/// Examples:
-///   `ag/claude-opus-4-6-thinking`      -> `Opus 4.6 🧠`
-///   `ag/claude-opus-4-6-thinking[1m]`  -> `Opus 4.6 [1M] 🧠`
-///   `ag/claude-sonnet-4-5-thinking`    -> `Sonnet 4.5 🧠`
-///   `ag/gemini-2.5-flash-lite[1m]`     -> `Gemini 2.5 Flash Lite [1M]`
-///   `ag/gemini-2.5-pro`                -> `Gemini 2.5 Pro 🧠`
-///   `claude-opus-4.5`                  -> `Opus 4.5`
-///   `v/gpt-5.3-codex(xhigh)`          -> `GPT-5.3-Codex (xhigh) 🧠`
-///   `gpt-5.4(xhigh)[1m]`              -> `GPT-5.4 (xhigh) [1M] 🧠`
-///   `gpt-5.4(xhigh)[1m]+fast`         -> `GPT-5.4 (xhigh) [1M] 🧠⚡️`
-///   `gpt-4.1-2025-04-14`              -> `GPT-4.1`
-///   `unknown-model`                    -> `unknown-model`
-fn prettify_model_name(raw: &str) -> String {
-    let (body, qualifiers, is_fast) = extract_qualifiers(raw);
-
-    // Strip routing prefixes: "ag/", "v/"
-    let body = body
-        .strip_prefix("ag/")
-        .or_else(|| body.strip_prefix("v/"))
-        .unwrap_or(body);
-
-    let is_thinking = body.ends_with("-thinking");
-    let body = body.strip_suffix("-thinking").unwrap_or(body);
-
-    let (pretty, is_reasoning) = if let Some(rest) = body.strip_prefix("claude-") {
-        (prettify_claude(rest), is_thinking)
-    } else if let Some(rest) = body.strip_prefix("gemini-") {
-        let parts: Vec<&str> = rest.split('-').collect();
-        let is_pro = parts.iter().any(|p| p.eq_ignore_ascii_case("pro"));
-        (prettify_generic("Gemini", rest), is_pro)
-    } else if let Some(rest) = body.strip_prefix("gpt-") {
-        let reasoning = is_gpt_reasoning(rest, &qualifiers);
-        (prettify_gpt(rest), reasoning)
-    } else {
-        return raw.to_string();
-    };
-
-    let mut result = pretty;
-    for qualifier in &qualifiers {
-        match qualifier.kind {
-            QualifierKind::Bracket => write!(result, " [{}]", qualifier.display),
-            QualifierKind::Paren => write!(result, " ({})", qualifier.display),
-        }
-        .expect("writing into String must succeed");
-    }
-
-    if is_reasoning {
-        result.push_str(" 🧠");
-    }
-
-    if is_fast {
-        if is_reasoning {
-            result.push_str("⚡️");
-        } else {
-            result.push_str(" ⚡️");
-        }
-    }
-
-    result
-}
-
-/// Determine if a GPT model qualifies as a reasoning model.
-///
-/// Rules:
-/// - GPT Codex variants with medium+ reasoning qualifier -> true
-/// - GPT mini / nano variants without codex -> false
-/// - Other GPT 5+ variants -> true
-fn is_gpt_reasoning(rest: &str, qualifiers: &[ModelQualifier]) -> bool {
-    let parts: Vec<&str> = rest.split('-').collect();
-    let is_codex = parts.iter().any(|p| p.eq_ignore_ascii_case("codex"));
-    if is_codex {
-        return qualifiers.iter().any(|qualifier| {
-            qualifier.kind == QualifierKind::Paren
-                && matches!(
-                    qualifier.display.to_ascii_lowercase().as_str(),
-                    "medium" | "high" | "xhigh"
-                )
-        });
-    }
-
-    if parts
-        .iter()
-        .any(|p| p.eq_ignore_ascii_case("mini") || p.eq_ignore_ascii_case("nano"))
-    {
-        return false;
-    }
-
-    // GPT 5+ (excluding mini / nano)
-    parts
-        .first()
-        .and_then(|v| v.split('.').next())
-        .and_then(|major| major.parse::<u32>().ok())
-        .is_some_and(|major| major >= 5)
-}
-
-/// Extract trailing qualifiers from a model ID.
-/// Handles stacked `(...)`, `[...]`, and `+fast` suffixes in any order,
-/// preserving the original order for displayed qualifiers.
-fn extract_qualifiers(raw: &str) -> (&str, Vec<ModelQualifier>, bool) {
-    let mut body = raw;
-    let mut qualifiers = Vec::new();
-    let mut is_fast = false;
-
-    loop {
-        if let Some(stripped) = body.strip_suffix("+fast") {
-            is_fast = true;
-            body = stripped;
-            continue;
-        }
-
-        if let Some(start) = body.rfind('[')
-            && body.ends_with(']')
-        {
-            let inner = &body[start + 1..body.len() - 1];
-            qualifiers.push(ModelQualifier {
-                display: inner.to_uppercase(),
-                kind: QualifierKind::Bracket,
-            });
-            body = &body[..start];
-            continue;
-        }
-
-        if let Some(start) = body.rfind('(')
-            && body.ends_with(')')
-        {
-            let inner = &body[start + 1..body.len() - 1];
-            qualifiers.push(ModelQualifier {
-                display: inner.to_string(),
-                kind: QualifierKind::Paren,
-            });
-            body = &body[..start];
-            continue;
-        }
-
-        qualifiers.reverse();
-        return (body, qualifiers, is_fast);
-    }
-}
-
-/// Prettify a Claude model name after "claude-" prefix is stripped.
-/// e.g. "opus-4-6" -> "Opus 4.6", "sonnet-4-5" -> "Sonnet 4.5"
-fn prettify_claude(rest: &str) -> String {
-    let parts: Vec<&str> = rest.splitn(2, '-').collect();
-    if parts.len() < 2 {
-        return title_case(rest);
-    }
-
-    let tier = title_case(parts[0]);
-    let version = dotted_version(parts[1]);
-    format!("{tier} {version}")
+    usage_segments
 }
 
-/// Prettify a non-Claude, non-GPT model after the prefix is stripped.
-/// e.g. brand="Gemini", rest="2.5-flash-lite" -> "Gemini 2.5 Flash Lite"
-fn prettify_generic(brand: &str, rest: &str) -> String {
-    let (version, name_parts) = split_version_and_name(rest);
-    if name_parts.is_empty() {
-        format!("{brand} {version}")
-    } else {
-        let name = name_parts
-            .iter()
-            .map(|p| title_case(p))
-            .collect::<Vec<_>>()
-            .join(" ");
-        format!("{brand} {version} {name}")
-    }
-}
+/// Git refs looked up by this process, keyed by directory. Repos without a ref
+/// are cached too, so non-repo directories don't respawn `git` either.
+static GIT_REF_CACHE: OnceLock<Mutex<HashMap<String, CachedGitRef>>> = OnceLock::new();
 
-/// Prettify a GPT model after the `gpt-` prefix is stripped.
-///
-/// Rules:
-/// - Always keep the `GPT-<version>` prefix.
-/// - `codex` families stay hyphenated and Title Cased.
-/// - `mini` / `nano` families stay lowercase and use a space separator.
-fn prettify_gpt(rest: &str) -> String {
-    let (version, name_parts) = split_version_and_name(rest);
-    let mut result = format!("GPT-{version}");
-    if name_parts.is_empty() {
-        return result;
-    }
-
-    if name_parts[0].eq_ignore_ascii_case("codex") {
-        result.push('-');
-        result.push_str(
-            &name_parts
-                .iter()
-                .map(|part| title_case(part))
-                .collect::<Vec<_>>()
-                .join("-"),
-        );
-        return result;
-    }
-
-    result.push(' ');
-    result.push_str(
-        &name_parts
-            .iter()
-            .map(|part| match part.to_ascii_lowercase().as_str() {
-                "mini" | "nano" => part.to_ascii_lowercase(),
-                _ => title_case(part),
-            })
-            .collect::<Vec<_>>()
-            .join(" "),
-    );
-    result
-}
+type CachedGitRef = (Option<String>, Instant);
 
-/// Split version and name segments from a model suffix.
-///
-/// - `5.3-codex` -> `("5.3", ["codex"])`
-/// - `2.5-flash-lite` -> `("2.5", ["flash", "lite"])`
-/// - `4.1-2025-04-14` -> `("4.1", [])` (date suffixes are dropped)
-fn split_version_and_name(rest: &str) -> (String, Vec<&str>) {
-    let parts: Vec<&str> = rest.split('-').collect();
-    if parts.is_empty() {
-        return (rest.to_string(), vec![]);
+/// [`git_ref_for_dir`], reusing a result younger than `ttl` for the same directory.
+/// A zero `ttl` always runs `git`. Timed-out lookups are cached like repos without
+/// a ref, so a slow repo is not queried on every update.
+fn git_ref_for_dir_cached(dir: &str, ttl: Duration, timeout: Duration) -> Option<String> {
+    if ttl.is_zero() {
+        return git_ref_for_dir(dir, timeout);
     }
 
-    let version = parts[0].to_string();
-    let remaining = &parts[1..];
-
-    // Drop date suffixes (YYYY-MM-DD pattern)
-    if remaining.len() >= 3
-        && remaining[0].len() == 4
-        && remaining[0].chars().all(|c| c.is_ascii_digit())
-        && remaining[1].len() == 2
-        && remaining[2].len() == 2
+    let cache = GIT_REF_CACHE.get_or_init(Mutex::default);
+    if let Some((git_ref, looked_up_at)) = cache
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+        .get(dir)
+        && looked_up_at.elapsed() < ttl
     {
-        return (version, vec![]);
+        return git_ref.clone();
     }
 
-    (version, remaining.to_vec())
-}
-
-/// Convert a hyphenated version like "4-6" to "4.6".
-/// If it already contains dots (e.g. "4.5"), return as-is.
-fn dotted_version(version: &str) -> String {
-    if version.contains('.') {
-        return version.to_string();
-    }
-    version.replace('-', ".")
-}
-
-fn title_case(word: &str) -> String {
-    let mut chars = word.chars();
-    chars.next().map_or_else(String::new, |first| {
-        let upper: String = first.to_uppercase().collect();
-        format!("{upper}{}", chars.as_str())
-    })
+    let git_ref = git_ref_for_dir(dir, timeout);
+    cache
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+        .insert(dir.to_string(), (git_ref.clone(), Instant::now()));
+    git_ref
 }
 
-fn git_ref_for_dir(dir: &str) -> Option<String> {
-    git_command_output(dir, &["symbolic-ref", "--quiet", "--short", "HEAD"])
-        .or_else(|| git_command_output(dir, &["rev-parse", "--short", "HEAD"]))
-        .map(|value| truncate_to_width(&value, 28))
+fn git_ref_for_dir(dir: &str, timeout: Duration) -> Option<String> {
+    git_command_output(
+        dir,
+        &["symbolic-ref", "--quiet", "--short", "HEAD"],
+        timeout,
+    )
+    .or_else(|| git_command_output(dir, &["rev-parse", "--short", "HEAD"], timeout))
+    .map(|value| truncate_from_left_to_width(&value, 28))
 }
 
-fn git_command_output(dir: &str, args: &[&str]) -> Option<String> {
-    let output = Command::new("git")
-        .arg("-C")
-        .arg(dir)
-        .args(args)
-        .output()
-        .ok()?;
+fn git_command_output(dir: &str, args: &[&str], timeout: Duration) -> Option<String> {
+    let mut command = Command::new("git");
+    command.arg("-C").arg(dir).args(args);
+    let output = match command_output_with_timeout(&mut command, timeout) {
+        Ok(Some(output)) => output,
+        Ok(None) => {
+            eprintln!(
+                "git {} in {dir} did not finish within {}ms",
+                args.join(" "),
+                timeout.as_millis()
+            );
+            return None;
+        }
+        Err(_) => return None,
+    };
     if !output.status.success() {
         return None;
     }
@@ -465,42 +784,39 @@ fn git_command_output(dir: &str, args: &[&str]) -> Option<String> {
     }
 }
 
-fn truncate_to_width(value: &str, max_width: usize) -> String {
-    if visible_width(value) <= max_width {
-        return value.to_string();
-    }
-
-    if max_width == 0 {
-        return String::new();
+/// Runs `command` like [`Command::output`], but kills it and returns `Ok(None)` once
+/// it runs longer than `timeout` (slow network mounts, hanging git hooks). A zero
+/// `timeout` waits forever.
+fn command_output_with_timeout(
+    command: &mut Command,
+    timeout: Duration,
+) -> io::Result<Option<Output>> {
+    command
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null());
+    if timeout.is_zero() {
+        return command.output().map(Some);
     }
 
-    let ellipsis = "…";
-    let ellipsis_width = visible_width(ellipsis);
-    if max_width <= ellipsis_width {
-        return ellipsis.to_string();
-    }
-
-    let mut result = String::new();
-    let mut width = 0usize;
-    for ch in value.chars() {
-        let ch_width = UnicodeWidthChar::width(ch).unwrap_or(0);
-        if width + ch_width + ellipsis_width > max_width {
-            break;
+    // Only short output is expected, so the pipe cannot fill up while polling.
+    let mut child = command.spawn()?;
+    let deadline = Instant::now() + timeout;
+    loop {
+        if child.try_wait()?.is_some() {
+            return child.wait_with_output().map(Some);
+        }
+        if Instant::now() >= deadline {
+            let _ = child.kill();
+            let _ = child.wait();
+            return Ok(None);
         }
-        result.push(ch);
-        width += ch_width;
+        std::thread::sleep(GIT_POLL_INTERVAL);
     }
-
-    result.push('…');
-    result
 }
 
 fn context_usage_percent(input: &StatusInput) -> Option<f64> {
     let context = input.context_window.as_ref()?;
-    let window_size = context.window_size?;
-    if window_size == 0 {
-        return None;
-    }
 
     // Only use current_usage for context window calculation.
     // total_input_tokens / total_output_tokens are session-wide cumulative totals
@@ -521,18 +837,67 @@ fn context_usage_percent(input: &StatusInput) -> Option<f64> {
         return None;
     }
 
+    match context.mode() {
+        ContextWindowMode::Single => {
+            let window_size = context.window_size.filter(|size| *size > 0)?;
+            Some(usage_percent(used_tokens, window_size))
+        }
+        // Either window filling up ends the conversation, so report the fuller one.
+        ContextWindowMode::Split {
+            system,
+            conversation,
+        } => {
+            let system_tokens = current_usage.system_prompt.unwrap_or(0).min(used_tokens);
+            let conversation_tokens = used_tokens - system_tokens;
+            Some(
+                usage_percent(system_tokens, system)
+                    .max(usage_percent(conversation_tokens, conversation)),
+            )
+        }
+    }
+}
+
+fn usage_percent(used_tokens: u64, window_size: u64) -> f64 {
     let used_tokens = u32::try_from(used_tokens).unwrap_or(u32::MAX);
     let window_size = u32::try_from(window_size).unwrap_or(u32::MAX);
 
-    Some(f64::from(used_tokens) * 100.0 / f64::from(window_size))
+    f64::from(used_tokens) * 100.0 / f64::from(window_size)
 }
 
-fn format_cost(input: &StatusInput) -> Option<String> {
+fn format_cost(input: &StatusInput, fmt: &CostFormat) -> Option<String> {
     let cost = input.cost.as_ref()?.total_cost_usd?;
     if cost <= 0.0 {
         return None;
     }
-    Some(format!("$ {cost:.2}"))
+    Some(format!("{} {cost:.*}", fmt.symbol, fmt.decimals))
+}
+
+/// `Σ $ 1.23 · 45.6k tokens` for the end of a session, from the session-wide totals.
+fn session_summary_label(input: &StatusInput, cost_format: &CostFormat) -> Option<String> {
+    let total_tokens = input.context_window.as_ref().and_then(|context| {
+        match (context.total_input_tokens, context.total_output_tokens) {
+            (None, None) => None,
+            (input, output) => Some(input.unwrap_or(0).saturating_add(output.unwrap_or(0))),
+        }
+    });
+    let parts: Vec<String> = format_cost(input, cost_format)
+        .into_iter()
+        .chain(total_tokens.map(|tokens| format!("{} tokens", format_token_count(tokens))))
+        .collect();
+    if parts.is_empty() {
+        return None;
+    }
+    Some(format!("Σ {}", parts.join(" · ")))
+}
+
+/// `950`, `45.6k`, or `1.2M`, rounded down to one decimal.
+fn format_token_count(tokens: u64) -> String {
+    let (tenths, unit) = match tokens {
+        0..1_000 => return tokens.to_string(),
+        1_000..1_000_000 => (tokens / 100, 'k'),
+        _ => (tokens / 100_000, 'M'),
+    };
+    format!("{}.{}{unit}", tenths / 10, tenths % 10)
 }
 
 fn context_segment_colors(percent: f64) -> (Color, Color) {
@@ -545,19 +910,56 @@ fn context_segment_colors(percent: f64) -> (Color, Color) {
     }
 }
 
-fn context_usage_label(percent: f64) -> String {
+fn context_usage_label(
+    percent: f64,
+    bar_width: usize,
+    thresholds: &ContextThresholds,
+    style: BarStyle,
+) -> String {
+    format!(
+        "󰆼 {} {percent:.1}%",
+        context_bar_text(percent, bar_width, thresholds, style)
+    )
+}
+
+/// Renders the context bar with `bar_width` slots, each filled once usage reaches
+/// its share of `100.0 / bar_width` percent.
+///
+/// Past the warn or error threshold the slot after the filled ones shows `⚠` or
+/// `!`; past the critical threshold the bar is drawn full with a trailing `!`.
+fn context_bar_text(
+    percent: f64,
+    bar_width: usize,
+    thresholds: &ContextThresholds,
+    style: BarStyle,
+) -> String {
     let clamped_percent = percent.clamp(0.0, 100.0);
-    let filled_slots = CONTEXT_BAR_THRESHOLDS
-        .iter()
-        .filter(|&&threshold| clamped_percent >= threshold)
+    let slots = u32::try_from(bar_width).unwrap_or(u32::MAX);
+    let step = 100.0 / f64::from(slots);
+    let filled_slots = (1..=slots)
+        .filter(|&slot| clamped_percent >= f64::from(slot) * step)
         .count();
-    let empty_slots = CONTEXT_BAR_SLOTS.saturating_sub(filled_slots);
-    let bar = format!(
-        "{}{}",
-        CONTEXT_BAR_FILLED.to_string().repeat(filled_slots),
-        CONTEXT_BAR_EMPTY.to_string().repeat(empty_slots)
-    );
-    format!("󰆼 [{bar}] {percent:.1}%")
+    let empty_slots = bar_width.saturating_sub(filled_slots);
+    let mut bar: Vec<char> = std::iter::repeat_n(style.filled, filled_slots)
+        .chain(std::iter::repeat_n(style.empty, empty_slots))
+        .collect();
+
+    let marker = match thresholds.level(percent) {
+        None => None,
+        Some(ContextLevel::Warn) => Some(CONTEXT_WARN_MARKER),
+        Some(ContextLevel::Error) => Some(CONTEXT_ERROR_MARKER),
+        Some(ContextLevel::Critical) => {
+            let full = style.filled.to_string().repeat(bar_width);
+            return format!("[{full}{CONTEXT_ERROR_MARKER}]");
+        }
+    };
+    if let Some(marker) = marker
+        && let Some(slot) = bar.get_mut(filled_slots.min(bar_width.saturating_sub(1)))
+    {
+        *slot = marker;
+    }
+
+    format!("[{}]", bar.into_iter().collect::<String>())
 }
 
 fn folder_name(path: &str) -> String {
@@ -576,58 +978,132 @@ fn folder_name(path: &str) -> String {
         .map_or_else(|| ".".to_string(), ToString::to_string)
 }
 
-fn render_powerline(segments: &[Segment]) -> (String, usize) {
+/// The last `max_depth` components of `path` joined by `/`, e.g. `project/src` for
+/// depth 2. Paths with no more components than that are shown whole, so `/tmp` stays
+/// `/tmp` at depth 2; depth 1 is the same as [`folder_name`].
+fn folder_path_abbreviated(path: &str, max_depth: usize) -> String {
+    if max_depth <= 1 {
+        return folder_name(path);
+    }
+
+    let trimmed = path.trim_end_matches(['/', '\\']);
+    let components: Vec<&str> = trimmed
+        .split(['/', '\\'])
+        .filter(|part| !part.is_empty())
+        .collect();
+    if components.len() <= max_depth {
+        return if trimmed.is_empty() {
+            folder_name(path)
+        } else {
+            trimmed.to_string()
+        };
+    }
+    components[components.len() - max_depth..].join("/")
+}
+
+/// Writes the color directives around powerline segments for one output format.
+trait Renderer {
+    /// Switches to `fg`, and to `bg` when given, for the text that follows.
+    fn style(&self, out: &mut String, fg: Color, bg: Option<Color>);
+    /// Restores the default colors.
+    fn reset(&self, out: &mut String);
+    /// Writes segment text, escaping anything the format would interpret.
+    fn text(&self, out: &mut String, text: &str) {
+        out.push_str(text);
+    }
+}
+
+/// ANSI CSI sequences for terminals, as Claude Code renders them.
+struct AnsiRenderer;
+
+impl Renderer for AnsiRenderer {
+    fn style(&self, out: &mut String, fg: Color, bg: Option<Color>) {
+        if let Some(bg) = bg {
+            write!(out, "{}", SetBackgroundColor(bg)).expect("writing into String must succeed");
+        }
+        write!(out, "{}", SetForegroundColor(fg)).expect("writing into String must succeed");
+    }
+
+    fn reset(&self, out: &mut String) {
+        write!(out, "{ResetColor}").expect("writing into String must succeed");
+    }
+}
+
+/// tmux format directives for use in `status-left` / `status-right`.
+struct TmuxRenderer;
+
+impl Renderer for TmuxRenderer {
+    fn style(&self, out: &mut String, fg: Color, bg: Option<Color>) {
+        write!(out, "#[fg={}", tmux_color(fg)).expect("writing into String must succeed");
+        if let Some(bg) = bg {
+            write!(out, ",bg={}", tmux_color(bg)).expect("writing into String must succeed");
+        }
+        out.push(']');
+    }
+
+    fn reset(&self, out: &mut String) {
+        out.push_str("#[default]");
+    }
+
+    fn text(&self, out: &mut String, text: &str) {
+        // tmux expands `#` in status formats, e.g. in branch names like `fix#12`
+        out.push_str(&text.replace('#', "##"));
+    }
+}
+
+/// Text and arrows only, for `--no-color`.
+struct PlainRenderer;
+
+impl Renderer for PlainRenderer {
+    fn style(&self, _out: &mut String, _fg: Color, _bg: Option<Color>) {}
+
+    fn reset(&self, _out: &mut String) {}
+}
+
+fn tmux_color(color: Color) -> String {
+    match color {
+        Color::Rgb { r, g, b } => format!("#{r:02x}{g:02x}{b:02x}"),
+        _ => "default".to_string(),
+    }
+}
+
+/// Joins `segments` with `separator` (powerline arrows by default), returning the
+/// string and its display width.
+fn render_powerline(
+    segments: &[Segment],
+    format: &dyn Renderer,
+    separator: SeparatorStyle,
+) -> (String, usize) {
     if segments.is_empty() {
         return (String::new(), 0);
     }
 
-    let arrow_width = UnicodeWidthChar::width(POWERLINE_ARROW).unwrap_or(1);
+    let separator_text = separator.char.to_string();
+    let separator_width = nerd_font_width(separator.char);
     let mut rendered = String::new();
     let mut width = 0usize;
 
     for (idx, segment) in segments.iter().enumerate() {
-        write!(
-            rendered,
-            "{}{} {} {}",
-            SetBackgroundColor(segment.bg),
-            SetForegroundColor(segment.fg),
-            segment.text,
-            ResetColor
-        )
-        .expect("writing into String must succeed");
-        width += visible_width(&segment.text) + 2;
-
-        if let Some(next) = segments.get(idx + 1) {
-            write!(
-                rendered,
-                "{}{}{}{}",
-                SetForegroundColor(segment.bg),
-                SetBackgroundColor(next.bg),
-                POWERLINE_ARROW,
-                ResetColor
-            )
-            .expect("writing into String must succeed");
-        } else {
-            write!(
-                rendered,
-                "{}{}{}",
-                SetForegroundColor(segment.bg),
-                POWERLINE_ARROW,
-                ResetColor
-            )
-            .expect("writing into String must succeed");
-        }
-
-        width += arrow_width;
+        format.style(&mut rendered, segment.fg, Some(segment.bg));
+        rendered.push(' ');
+        format.text(&mut rendered, &segment.text);
+        rendered.push(' ');
+        format.reset(&mut rendered);
+
+        let next_bg = segments
+            .get(idx + 1)
+            .map(|next| next.bg)
+            .filter(|_| separator.is_powerline);
+        format.style(&mut rendered, segment.bg, next_bg);
+        format.text(&mut rendered, &separator_text);
+        format.reset(&mut rendered);
+
+        width += visible_width(&segment.text) + 2 + separator_width;
     }
 
     (rendered, width)
 }
 
-fn visible_width(text: &str) -> usize {
-    UnicodeWidthStr::width(text)
-}
-
 const fn rgb(r: u8, g: u8, b: u8) -> Color {
     Color::Rgb { r, g, b }
 }
@@ -639,21 +1115,26 @@ mod tests {
     #[test]
     fn context_usage_prefers_current_usage() {
         let input = StatusInput {
-            _event_name: None,
+            event: HookEvent::Unknown,
             cwd: None,
             model: None,
             workspace: None,
             version: None,
             cost: None,
+            tool_calls_pending: None,
+            session_id: None,
             context_window: Some(ContextWindow {
                 total_input_tokens: Some(1),
                 total_output_tokens: Some(1),
                 window_size: Some(100),
+                system_window_size: None,
+                conversation_window_size: None,
                 current_usage: Some(CurrentUsage {
                     input: Some(20),
                     output: Some(5),
                     cache_creation_input: Some(10),
                     cache_read_input: Some(15),
+                    system_prompt: None,
                 }),
             }),
         };
@@ -662,22 +1143,65 @@ mod tests {
         assert!((percent - 50.0).abs() < 0.0001);
     }
 
+    #[test]
+    fn context_usage_reports_fuller_split_window() {
+        let input: StatusInput = serde_json::from_value(serde_json::json!({
+            "context_window": {
+                "system_context_window_size": 20_000,
+                "conversation_context_window_size": 180_000,
+                "current_usage": {
+                    "input_tokens": 30_000,
+                    "cache_read_input_tokens": 15_000,
+                    "system_prompt_tokens": 9_000,
+                },
+            },
+        }))
+        .unwrap();
+        let context = input.context_window.as_ref().unwrap();
+        assert_eq!(
+            context.mode(),
+            ContextWindowMode::Split {
+                system: 20_000,
+                conversation: 180_000,
+            }
+        );
+
+        // System: 9k / 20k = 45%; conversation: 36k / 180k = 20%.
+        let percent = context_usage_percent(&input).unwrap_or_default();
+        assert!((percent - 45.0).abs() < 0.0001);
+
+        let input: StatusInput = serde_json::from_value(serde_json::json!({
+            "context_window": {
+                "system_context_window_size": 20_000,
+                "conversation_context_window_size": 180_000,
+                "current_usage": { "input_tokens": 171_000, "system_prompt_tokens": 9_000 },
+            },
+        }))
+        .unwrap();
+        let percent = context_usage_percent(&input).unwrap_or_default();
+        assert!((percent - 90.0).abs() < 0.0001);
+    }
+
     #[test]
     fn context_usage_none_without_current_usage() {
         // After /clear or plan mode reset, current_usage may be absent while
         // total_input/output_tokens still carry stale cumulative values.
         // We must NOT fall back to those totals.
         let input = StatusInput {
-            _event_name: None,
+            event: HookEvent::Unknown,
             cwd: None,
             model: None,
             workspace: None,
             version: None,
             cost: None,
+            tool_calls_pending: None,
+            session_id: None,
             context_window: Some(ContextWindow {
                 total_input_tokens: Some(500_000),
                 total_output_tokens: Some(200_000),
                 window_size: Some(200_000),
+                system_window_size: None,
+                conversation_window_size: None,
                 current_usage: None,
             }),
         };
@@ -691,21 +1215,26 @@ mod tests {
         // token counts are zero. This should be treated as absent to
         // avoid briefly flashing "0.0%".
         let input = StatusInput {
-            _event_name: None,
+            event: HookEvent::Unknown,
             cwd: None,
             model: None,
             workspace: None,
             version: None,
             cost: None,
+            tool_calls_pending: None,
+            session_id: None,
             context_window: Some(ContextWindow {
                 total_input_tokens: None,
                 total_output_tokens: None,
                 window_size: Some(200_000),
+                system_window_size: None,
+                conversation_window_size: None,
                 current_usage: Some(CurrentUsage {
                     input: Some(0),
                     output: Some(0),
                     cache_creation_input: Some(0),
                     cache_read_input: Some(0),
+                    system_prompt: None,
                 }),
             }),
         };
@@ -735,170 +1264,779 @@ mod tests {
 
     #[test]
     fn context_usage_label_displays_progress_bar() {
-        assert_eq!(context_usage_label(0.0), "󰆼 [░░░░░░░░░░] 0.0%");
-        assert_eq!(context_usage_label(50.0), "󰆼 [█████░░░░░] 50.0%");
-        assert_eq!(context_usage_label(87.3), "󰆼 [████████░░] 87.3%");
-        assert_eq!(context_usage_label(120.0), "󰆼 [██████████] 120.0%");
+        assert_eq!(
+            context_usage_label(0.0, 10, &ContextThresholds::default(), BarStyle::default()),
+            "󰆼 [░░░░░░░░░░] 0.0%"
+        );
+        assert_eq!(
+            context_usage_label(50.0, 10, &ContextThresholds::default(), BarStyle::default()),
+            "󰆼 [█████░░░░░] 50.0%"
+        );
+        assert_eq!(
+            context_usage_label(87.3, 10, &ContextThresholds::default(), BarStyle::default()),
+            "󰆼 [████████░░] 87.3%"
+        );
+        assert_eq!(
+            context_usage_label(
+                120.0,
+                10,
+                &ContextThresholds::default(),
+                BarStyle::default()
+            ),
+            "󰆼 [██████████] 120.0%"
+        );
     }
 
     #[test]
-    fn folder_name_is_extracted() {
-        assert_eq!(folder_name("/Users/alice/work/project/src/bin"), "bin");
-        assert_eq!(folder_name("/tmp/"), "tmp");
-        assert_eq!(folder_name("/"), "/");
-        assert_eq!(folder_name(r"V:\Projects\ctf"), "ctf");
-        assert_eq!(folder_name(r"C:\Users\alice\work"), "work");
-        assert_eq!(folder_name(r"C:\Users\alice\work\"), "work");
+    fn context_usage_label_scales_to_bar_width() {
+        assert_eq!(
+            context_usage_label(39.9, 5, &ContextThresholds::default(), BarStyle::default()),
+            "󰆼 [█░░░░] 39.9%"
+        );
+        assert_eq!(
+            context_usage_label(40.0, 5, &ContextThresholds::default(), BarStyle::default()),
+            "󰆼 [██░░░] 40.0%"
+        );
+        assert_eq!(
+            context_usage_label(52.5, 20, &ContextThresholds::default(), BarStyle::default()),
+            "󰆼 [██████████░░░░░░░░░░] 52.5%"
+        );
+        assert_eq!(
+            context_usage_label(100.0, 3, &ContextThresholds::default(), BarStyle::default()),
+            "󰆼 [███] 100.0%"
+        );
     }
 
     #[test]
-    fn truncate_preserves_width_limit() {
-        let original = "feature/very-long-branch-name-for-statusline";
-        let truncated = truncate_to_width(original, 16);
-        assert!(visible_width(&truncated) <= 16);
-        assert!(truncated.ends_with('…'));
+    fn context_usage_label_uses_bar_style() {
+        let ascii = BarStyle {
+            filled: '=',
+            empty: '-',
+        };
+        let thresholds = ContextThresholds::default();
+        assert_eq!(
+            context_usage_label(0.0, 10, &thresholds, ascii),
+            "󰆼 [----------] 0.0%"
+        );
+        assert_eq!(
+            context_usage_label(39.9, 5, &thresholds, ascii),
+            "󰆼 [=----] 39.9%"
+        );
+        assert_eq!(
+            context_usage_label(52.5, 20, &thresholds, ascii),
+            "󰆼 [==========----------] 52.5%"
+        );
+        assert_eq!(
+            context_usage_label(120.0, 3, &thresholds, ascii),
+            "󰆼 [===] 120.0%"
+        );
+        for width in 1..=12 {
+            let bar = context_bar_text(42.0, width, &thresholds, ascii);
+            assert_eq!(visible_width(&bar), width + 2, "{bar}");
+        }
+
+        let critical = ContextThresholds {
+            critical: Some(90),
+            ..ContextThresholds::default()
+        };
+        assert_eq!(context_bar_text(95.0, 4, &critical, ascii), "[====!]");
     }
 
     #[test]
-    fn prettify_claude_opus() {
+    fn context_bar_text_marks_thresholds() {
+        let thresholds = ContextThresholds {
+            warn: Some(80),
+            error: Some(90),
+            critical: Some(95),
+        };
+        assert_eq!(
+            context_bar_text(79.9, 10, &thresholds, BarStyle::default()),
+            "[███████░░░]"
+        );
+        assert_eq!(
+            context_bar_text(85.0, 10, &thresholds, BarStyle::default()),
+            "[████████⚠░]"
+        );
         assert_eq!(
-            prettify_model_name("ag/claude-opus-4-6-thinking"),
-            "Opus 4.6 🧠"
+            context_bar_text(92.0, 10, &thresholds, BarStyle::default()),
+            "[█████████!]"
+        );
+        assert_eq!(
+            context_bar_text(95.0, 10, &thresholds, BarStyle::default()),
+            "[██████████!]"
+        );
+        assert_eq!(
+            context_bar_text(120.0, 4, &thresholds, BarStyle::default()),
+            "[████!]"
+        );
+
+        // A marker past a full bar replaces the last slot instead of growing it.
+        let warn_only = ContextThresholds {
+            warn: Some(50),
+            ..ContextThresholds::default()
+        };
+        assert_eq!(
+            context_bar_text(100.0, 4, &warn_only, BarStyle::default()),
+            "[███⚠]"
+        );
+        assert_eq!(
+            context_usage_label(60.0, 5, &warn_only, BarStyle::default()),
+            "󰆼 [███⚠░] 60.0%"
         );
     }
 
     #[test]
-    fn prettify_claude_opus_with_context() {
+    fn render_powerline_width_counts_nerd_font_glyphs() {
+        let segments = [
+            Segment {
+                text: "\u{f07c} src".to_string(),
+                fg: rgb(0, 0, 0),
+                bg: rgb(255, 255, 255),
+            },
+            Segment {
+                text: "\u{e725} main".to_string(),
+                fg: rgb(0, 0, 0),
+                bg: rgb(0, 0, 255),
+            },
+        ];
+
+        // Each segment is padded by a space on both sides and followed by a
+        // two-column arrow: (6 + 2 + 2) + (7 + 2 + 2).
+        let (_, width) = render_powerline(&segments, &AnsiRenderer, SeparatorStyle::default());
+        assert_eq!(width, 21);
+        let (plain, plain_width) =
+            render_powerline(&segments, &PlainRenderer, SeparatorStyle::default());
+        assert_eq!(plain, " \u{f07c} src \u{e0b0} \u{e725} main \u{e0b0}");
+        assert_eq!(plain_width, 21);
+    }
+
+    #[test]
+    fn render_powerline_emits_tmux_directives() {
+        let segments = [
+            Segment {
+                text: "\u{e725} fix#12".to_string(),
+                fg: rgb(232, 247, 239),
+                bg: rgb(72, 153, 120),
+            },
+            Segment {
+                text: "$ 1.23".to_string(),
+                fg: rgb(235, 245, 255),
+                bg: rgb(48, 120, 168),
+            },
+        ];
+
+        let (rendered, width) =
+            render_powerline(&segments, &TmuxRenderer, SeparatorStyle::default());
         assert_eq!(
-            prettify_model_name("ag/claude-opus-4-6-thinking[1m]"),
-            "Opus 4.6 [1M] 🧠"
+            rendered,
+            "#[fg=#e8f7ef,bg=#489978] \u{e725} fix##12 #[default]\
+             #[fg=#489978,bg=#3078a8]\u{e0b0}#[default]\
+             #[fg=#ebf5ff,bg=#3078a8] $ 1.23 #[default]\
+             #[fg=#3078a8]\u{e0b0}#[default]"
         );
+        assert!(!rendered.contains('\x1b'));
+        assert_eq!(width, 23);
     }
 
     #[test]
-    fn prettify_claude_sonnet() {
+    fn render_powerline_plain_separator_has_no_background() {
+        let segments = [
+            Segment {
+                text: "a".to_string(),
+                fg: rgb(232, 247, 239),
+                bg: rgb(72, 153, 120),
+            },
+            Segment {
+                text: "b".to_string(),
+                fg: rgb(235, 245, 255),
+                bg: rgb(48, 120, 168),
+            },
+        ];
+
+        let (rendered, width) =
+            render_powerline(&segments, &TmuxRenderer, SeparatorStyle::new('|'));
         assert_eq!(
-            prettify_model_name("ag/claude-sonnet-4-5-thinking"),
-            "Sonnet 4.5 🧠"
+            rendered,
+            "#[fg=#e8f7ef,bg=#489978] a #[default]\
+             #[fg=#489978]|#[default]\
+             #[fg=#ebf5ff,bg=#3078a8] b #[default]\
+             #[fg=#3078a8]|#[default]"
         );
+        assert_eq!(width, 8);
+
+        let (plain, plain_width) =
+            render_powerline(&segments, &PlainRenderer, SeparatorStyle::new(' '));
+        assert_eq!(plain, " a   b  ");
+        assert_eq!(plain_width, 8);
     }
 
     #[test]
-    fn prettify_claude_without_thinking() {
-        assert_eq!(prettify_model_name("claude-opus-4.5"), "Opus 4.5");
-        assert_eq!(prettify_model_name("claude-sonnet-4.5"), "Sonnet 4.5");
+    fn folder_name_is_extracted() {
+        assert_eq!(folder_name("/Users/alice/work/project/src/bin"), "bin");
+        assert_eq!(folder_name("/tmp/"), "tmp");
+        assert_eq!(folder_name("/"), "/");
+        assert_eq!(folder_name(r"V:\Projects\ctf"), "ctf");
+        assert_eq!(folder_name(r"C:\Users\alice\work"), "work");
+        assert_eq!(folder_name(r"C:\Users\alice\work\"), "work");
     }
 
     #[test]
-    fn prettify_gemini_pro_is_reasoning() {
+    fn folder_path_abbreviated_keeps_trailing_components() {
+        let path = "/Users/alice/work/project/src";
+        assert_eq!(folder_path_abbreviated(path, 1), "src");
+        assert_eq!(folder_path_abbreviated(path, 2), "project/src");
+        assert_eq!(
+            folder_path_abbreviated("/Users/alice/work/project/src/", 3),
+            "work/project/src"
+        );
         assert_eq!(
-            prettify_model_name("ag/gemini-2.5-pro"),
-            "Gemini 2.5 Pro 🧠"
+            folder_path_abbreviated(r"C:\Users\alice\work", 2),
+            "alice/work"
         );
+        assert_eq!(folder_path_abbreviated("/tmp/", 2), "/tmp");
+        assert_eq!(folder_path_abbreviated(r"C:\Users", 3), r"C:\Users");
+        assert_eq!(folder_path_abbreviated("/", 2), "/");
+        assert_eq!(folder_path_abbreviated("", 2), ".");
+    }
+
+    #[test]
+    fn parse_cli_defaults_max_model_width() {
+        let Ok(ParseCliResult::Run(options)) = parse_cli(std::iter::empty()) else {
+            panic!("expected options");
+        };
+        assert_eq!(options.max_model_width, DEFAULT_MAX_MODEL_WIDTH);
+    }
+
+    #[test]
+    fn parse_cli_reads_max_model_width() {
+        let result = parse_cli(["--max-model-width", "12"].into_iter().map(String::from));
+        let Ok(ParseCliResult::Run(options)) = result else {
+            panic!("expected options");
+        };
+        assert_eq!(options.max_model_width, 12);
+    }
+
+    #[test]
+    fn parse_cli_reads_context_bar_width() {
+        let result = parse_cli(["--context-bar-width", "5"].into_iter().map(String::from));
+        let Ok(ParseCliResult::Run(options)) = result else {
+            panic!("expected options");
+        };
+        assert_eq!(options.context_bar_width, 5);
+        assert_eq!(options.max_model_width, DEFAULT_MAX_MODEL_WIDTH);
+        assert!(parse_cli(["--context-bar-width", "0"].into_iter().map(String::from)).is_err());
     }
 
     #[test]
-    fn prettify_gemini_flash_is_not_reasoning() {
+    fn parse_cli_reads_separator() {
+        let Ok(ParseCliResult::Run(options)) = parse_cli(std::iter::empty()) else {
+            panic!("expected options");
+        };
+        assert!(options.separator.is_powerline);
+
+        let result = parse_cli(["--separator", "|"].into_iter().map(String::from));
+        let Ok(ParseCliResult::Run(options)) = result else {
+            panic!("expected options");
+        };
         assert_eq!(
-            prettify_model_name("ag/gemini-2.5-flash-lite[1m]"),
-            "Gemini 2.5 Flash Lite [1M]"
+            options.separator,
+            SeparatorStyle {
+                char: '|',
+                is_powerline: false,
+            }
         );
+        assert!(parse_cli(["--separator", "||"].into_iter().map(String::from)).is_err());
     }
 
     #[test]
-    fn prettify_gpt_codex_with_reasoning() {
-        assert_eq!(
-            prettify_model_name("v/gpt-5.3-codex(xhigh)"),
-            "GPT-5.3-Codex (xhigh) 🧠"
+    fn parse_cli_reads_bar_characters() {
+        let Ok(ParseCliResult::Run(options)) = parse_cli(std::iter::empty()) else {
+            panic!("expected options");
+        };
+        assert_eq!(options.bar_style, BarStyle::default());
+
+        let result = parse_cli(
+            ["--bar-filled", "#", "--bar-empty", "."]
+                .into_iter()
+                .map(String::from),
         );
+        let Ok(ParseCliResult::Run(options)) = result else {
+            panic!("expected options");
+        };
         assert_eq!(
-            prettify_model_name("gpt-5.3-codex(high)"),
-            "GPT-5.3-Codex (high) 🧠"
+            options.bar_style,
+            BarStyle {
+                filled: '#',
+                empty: '.',
+            }
         );
-        assert_eq!(
-            prettify_model_name("gpt-5.3-codex(medium)"),
-            "GPT-5.3-Codex (medium) 🧠"
+        assert!(parse_cli(["--bar-filled", "=="].into_iter().map(String::from)).is_err());
+        assert!(parse_cli(["--bar-empty", ""].into_iter().map(String::from)).is_err());
+        assert!(parse_cli(std::iter::once("--bar-filled".to_string())).is_err());
+    }
+
+    #[test]
+    fn parse_cli_reads_color_flags() {
+        let parse = |args: &[&str]| {
+            let Ok(ParseCliResult::Run(options)) =
+                parse_cli(args.iter().map(|arg| (*arg).to_string()))
+            else {
+                panic!("expected options for {args:?}");
+            };
+            options
+        };
+        assert_eq!(parse(&[]).color, ColorMode::Always);
+        assert_eq!(parse(&["--no-color"]).color, ColorMode::Never);
+        assert_eq!(parse(&["--color", "auto"]).color, ColorMode::Auto);
+        assert_eq!(parse(&["--color", "never"]).color, ColorMode::Never);
+        assert_eq!(parse(&["--no-color", "--color"]).color, ColorMode::Always);
+        assert!(!parse(&[]).tmux);
+        assert!(parse(&["--json"]).json);
+        assert!(parse(&["--tmux"]).tmux);
+
+        let options = parse(&["--color", "--context-bar-width", "5"]);
+        assert_eq!(options.color, ColorMode::Always);
+        assert_eq!(options.context_bar_width, 5);
+    }
+
+    #[test]
+    fn parse_cli_reads_cwd_depth() {
+        let result = parse_cli(["--cwd-depth", "2"].into_iter().map(String::from));
+        let Ok(ParseCliResult::Run(options)) = result else {
+            panic!("expected options");
+        };
+        assert_eq!(options.cwd_depth, 2);
+        assert!(parse_cli(["--cwd-depth", "0"].into_iter().map(String::from)).is_err());
+    }
+
+    #[test]
+    fn parse_cli_reads_context_thresholds() {
+        let result = parse_cli(
+            ["--context-warn", "80", "--context-critical", "95"]
+                .into_iter()
+                .map(String::from),
         );
+        let Ok(ParseCliResult::Run(options)) = result else {
+            panic!("expected options");
+        };
         assert_eq!(
-            prettify_model_name("gpt-5.3-codex(xhigh)[1m]"),
-            "GPT-5.3-Codex (xhigh) [1M] 🧠"
+            options.context_thresholds,
+            ContextThresholds {
+                warn: Some(80),
+                error: None,
+                critical: Some(95),
+            }
         );
-        assert_eq!(
-            prettify_model_name("gpt-5.1-codex-max"),
-            "GPT-5.1-Codex-Max"
+
+        assert!(parse_cli(["--context-warn", "101"].into_iter().map(String::from)).is_err());
+        assert!(parse_cli(["--context-error", "high"].into_iter().map(String::from)).is_err());
+        let result = parse_cli(
+            ["--context-warn", "90", "--context-error", "80"]
+                .into_iter()
+                .map(String::from),
         );
+        assert!(matches!(result, Err(message) if message.contains("--context-warn (90)")));
     }
 
     #[test]
-    fn prettify_gpt_codex_low_reasoning_is_not_thinking() {
-        assert_eq!(
-            prettify_model_name("gpt-5.3-codex(low)"),
-            "GPT-5.3-Codex (low)"
+    fn parse_cli_rejects_invalid_max_model_width() {
+        assert!(parse_cli(std::iter::once("--max-model-width".to_string())).is_err());
+        assert!(parse_cli(["--max-model-width", "wide"].into_iter().map(String::from)).is_err());
+    }
+
+    fn strip_ansi(text: &str) -> String {
+        regex::Regex::new(r"\x1b\[[^m]*m")
+            .unwrap()
+            .replace_all(text, "")
+            .into_owned()
+    }
+
+    /// Asserts that `parts` appear in `text` in order, without overlapping.
+    fn assert_in_order(text: &str, parts: &[&str]) {
+        let mut rest = text;
+        for part in parts {
+            let Some(index) = rest.find(part) else {
+                panic!("expected {part:?} after the previous segments in {text:?}");
+            };
+            rest = &rest[index + part.len()..];
+        }
+    }
+
+    #[test]
+    fn build_statusline_renders_all_segments_in_order() {
+        let repo = std::env::temp_dir().join("claude_statusline_build_all_segments");
+        let _ = std::fs::remove_dir_all(&repo);
+        std::fs::create_dir_all(&repo).unwrap();
+        let git = |args: &[&str]| {
+            let status = Command::new("git")
+                .arg("-C")
+                .arg(&repo)
+                .args(args)
+                .status()
+                .unwrap();
+            assert!(status.success());
+        };
+        git(&["init", "--quiet"]);
+        git(&["symbolic-ref", "HEAD", "refs/heads/feature/statusline"]);
+
+        let project_dir = repo.display().to_string();
+        let input: StatusInput = serde_json::from_value(serde_json::json!({
+            "model": { "id": "claude-opus-4-6-thinking" },
+            "workspace": {
+                "current_dir": format!("{project_dir}/src"),
+                "project_dir": project_dir,
+            },
+            "cost": { "total_cost_usd": 1.234 },
+            "context_window": {
+                "context_window_size": 200_000,
+                "current_usage": { "input_tokens": 100_000 },
+            },
+        }))
+        .unwrap();
+
+        let rendered = build_statusline(&input, &Options::default());
+        let _ = std::fs::remove_dir_all(&repo);
+
+        assert!(rendered.contains('\x1b'));
+        let plain = strip_ansi(&rendered);
+        assert_in_order(
+            &plain,
+            &[
+                " \u{f4b8} Opus 4.6 🧠 ",
+                "\u{e0b0}",
+                " \u{f07c} src ",
+                "\u{e0b0}",
+                " \u{e5fb} claude_statusline_build_all_segments ",
+                "\u{e0b0}",
+                " \u{e725} feature/statusline ",
+                "\u{e0b0}",
+                " $ 1.23 ",
+                "\u{e0b0}",
+                " 󰆼 [█████░░░░░] 50.0% ",
+                "\u{e0b0}",
+            ],
         );
+        assert!(plain.ends_with('\u{e0b0}'));
     }
 
     #[test]
-    fn prettify_gpt_codex_mini_with_reasoning_is_thinking() {
-        assert_eq!(
-            prettify_model_name("gpt-5.3-codex-mini(high)"),
-            "GPT-5.3-Codex-Mini (high) 🧠"
+    fn build_statusline_two_line_moves_usage_to_second_line() {
+        let dir = std::env::temp_dir().join("claude_statusline_build_two_line");
+        std::fs::create_dir_all(&dir).unwrap();
+        let cwd = dir.display().to_string();
+        let input: StatusInput = serde_json::from_value(serde_json::json!({
+            "model": { "id": "claude-opus-4-6" },
+            "cwd": cwd,
+            "cost": { "total_cost_usd": 0.5 },
+            "context_window": {
+                "context_window_size": 200_000,
+                "current_usage": { "input_tokens": 20_000 },
+            },
+        }))
+        .unwrap();
+        let options = Options {
+            lines: 2,
+            ..Options::default()
+        };
+
+        let plain = strip_ansi(&build_statusline_two_line(&input, &options));
+        assert_eq!(plain.matches('\n').count(), 1);
+        let (first, second) = plain.split_once('\n').unwrap();
+        assert!(first.contains("Opus 4.6"));
+        assert!(first.contains("claude_statusline_build_two_line"));
+        assert!(!first.contains("$ 0.50"));
+        assert!(second.starts_with(" $ 0.50 \u{e0b0}"));
+        assert!(second.contains("10.0%"));
+
+        // Nothing for the second line: no trailing empty line
+        let input: StatusInput = serde_json::from_value(serde_json::json!({ "cwd": cwd })).unwrap();
+        let plain = strip_ansi(&build_statusline_two_line(&input, &options));
+        let _ = std::fs::remove_dir(&dir);
+        assert_eq!(plain.matches('\n').count(), 0);
+        assert_eq!(plain, strip_ansi(&build_statusline(&input, &options)));
+    }
+
+    #[test]
+    fn parse_cli_reads_lines() {
+        let Ok(ParseCliResult::Run(options)) = parse_cli(std::iter::empty()) else {
+            panic!("expected options");
+        };
+        assert_eq!(options.lines, 1);
+        let result = parse_cli(["--lines", "2"].into_iter().map(String::from));
+        let Ok(ParseCliResult::Run(options)) = result else {
+            panic!("expected options");
+        };
+        assert_eq!(options.lines, 2);
+        assert!(parse_cli(["--lines", "0"].into_iter().map(String::from)).is_err());
+        assert!(parse_cli(["--lines", "3"].into_iter().map(String::from)).is_err());
+    }
+
+    #[test]
+    fn status_input_reads_hook_event() {
+        let event = |name: &str| {
+            serde_json::from_value::<StatusInput>(serde_json::json!({ "hook_event_name": name }))
+                .unwrap()
+                .event
+        };
+        assert_eq!(event("PreToolUse"), HookEvent::PreToolUse);
+        assert_eq!(event("Stop"), HookEvent::Stop);
+        assert_eq!(event("Notification"), HookEvent::Notification);
+        assert_eq!(event("Status"), HookEvent::Unknown);
+        let input: StatusInput = serde_json::from_value(serde_json::json!({})).unwrap();
+        assert_eq!(input.event, HookEvent::Unknown);
+    }
+
+    #[test]
+    fn build_statusline_shows_summary_on_stop() {
+        let dir = std::env::temp_dir().join("claude_statusline_build_stop");
+        std::fs::create_dir_all(&dir).unwrap();
+        let input: StatusInput = serde_json::from_value(serde_json::json!({
+            "hook_event_name": "Stop",
+            "cwd": dir.display().to_string(),
+            "cost": { "total_cost_usd": 1.234 },
+            "context_window": {
+                "total_input_tokens": 40_000,
+                "total_output_tokens": 5_600,
+                "context_window_size": 200_000,
+                "current_usage": { "input_tokens": 100_000 },
+            },
+        }))
+        .unwrap();
+
+        let plain = strip_ansi(&build_statusline(&input, &Options::default()));
+        let _ = std::fs::remove_dir(&dir);
+
+        assert!(
+            plain.ends_with(" Σ $ 1.23 · 45.6k tokens \u{e0b0}"),
+            "{plain}"
         );
+        assert!(!plain.contains("50.0%"));
     }
 
     #[test]
-    fn prettify_gpt5_is_reasoning() {
-        assert_eq!(prettify_model_name("gpt-5"), "GPT-5 🧠");
-        assert_eq!(prettify_model_name("gpt-5.1"), "GPT-5.1 🧠");
+    fn format_token_count_scales_units() {
+        assert_eq!(format_token_count(950), "950");
+        assert_eq!(format_token_count(45_600), "45.6k");
+        assert_eq!(format_token_count(1_250_000), "1.2M");
+    }
+
+    #[test]
+    fn build_statusline_omits_optional_segments() {
+        let dir = std::env::temp_dir().join("claude_statusline_build_minimal");
+        std::fs::create_dir_all(&dir).unwrap();
+        let input: StatusInput = serde_json::from_value(serde_json::json!({
+            "cwd": dir.display().to_string(),
+        }))
+        .unwrap();
+
+        let plain = strip_ansi(&build_statusline(&input, &Options::default()));
+        let _ = std::fs::remove_dir(&dir);
+
         assert_eq!(
-            prettify_model_name("gpt-5.4(xhigh)[1m]"),
-            "GPT-5.4 (xhigh) [1M] 🧠"
+            plain,
+            " \u{f4b8} unknown \u{e0b0} \u{f07c} claude_statusline_build_minimal \u{e0b0}"
         );
     }
 
     #[test]
-    fn prettify_gpt5_fast_adds_lightning() {
+    fn build_statusline_shows_pending_tool_calls_after_model() {
+        let dir = std::env::temp_dir().join("claude_statusline_build_pending");
+        std::fs::create_dir_all(&dir).unwrap();
+        let input = |pending: Option<u32>| StatusInput {
+            event: HookEvent::Unknown,
+            cwd: Some(dir.display().to_string()),
+            model: None,
+            workspace: None,
+            version: None,
+            cost: None,
+            context_window: None,
+            tool_calls_pending: pending,
+            session_id: None,
+        };
+
+        let pending = strip_ansi(&build_statusline(&input(Some(3)), &Options::default()));
+        let idle = strip_ansi(&build_statusline(&input(Some(0)), &Options::default()));
+        let absent = strip_ansi(&build_statusline(&input(None), &Options::default()));
+        let _ = std::fs::remove_dir(&dir);
+
         assert_eq!(
-            prettify_model_name("gpt-5.4(xhigh)[1m]+fast"),
-            "GPT-5.4 (xhigh) [1M] 🧠⚡️"
+            pending,
+            " \u{f4b8} unknown \u{e0b0} ⚙ 3 \u{e0b0} \u{f07c} claude_statusline_build_pending \u{e0b0}"
         );
+        assert!(!idle.contains('⚙'));
+        assert_eq!(idle, absent);
+    }
+
+    #[test]
+    fn build_statusline_shows_truncated_session_id() {
+        let dir = std::env::temp_dir().join("claude_statusline_build_session");
+        std::fs::create_dir_all(&dir).unwrap();
+        let input: StatusInput = serde_json::from_value(serde_json::json!({
+            "cwd": dir.display().to_string(),
+            "session_id": "0f3c9a2e-41b7-4c55-9d1e-7a8b6c5d4e3f",
+        }))
+        .unwrap();
+
+        let plain = strip_ansi(&build_statusline(&input, &Options::default()));
+        let json = build_statusline_json(&input, &Options::default());
+        let _ = std::fs::remove_dir(&dir);
+
         assert_eq!(
-            prettify_model_name("gpt-5.4(xhigh)+fast[1m]"),
-            "GPT-5.4 (xhigh) [1M] 🧠⚡️"
+            plain,
+            " \u{f4b8} unknown \u{e0b0} 0f3c9a2e \u{e0b0} \u{f07c} claude_statusline_build_session \u{e0b0}"
         );
+        let json: serde_json::Value = serde_json::from_str(&json).unwrap();
         assert_eq!(
-            prettify_model_name("gpt-5.4+fast(xhigh)[1m]"),
-            "GPT-5.4 (xhigh) [1M] 🧠⚡️"
+            json,
+            serde_json::json!({
+                "session_id": "0f3c9a2e-41b7-4c55-9d1e-7a8b6c5d4e3f",
+                "segments": [
+                    "\u{f4b8} unknown",
+                    "0f3c9a2e",
+                    "\u{f07c} claude_statusline_build_session",
+                ],
+            })
         );
-        assert_eq!(prettify_model_name("gpt-4.1+fast"), "GPT-4.1 ⚡️");
     }
 
     #[test]
-    fn prettify_gpt4_is_not_reasoning() {
-        assert_eq!(prettify_model_name("gpt-4.1-2025-04-14"), "GPT-4.1");
+    fn build_statusline_truncates_model_name() {
+        let dir = std::env::temp_dir().join("claude_statusline_build_truncated");
+        std::fs::create_dir_all(&dir).unwrap();
+        let input: StatusInput = serde_json::from_value(serde_json::json!({
+            "cwd": dir.display().to_string(),
+            "model": { "display_name": "A Very Long Model Display Name" },
+        }))
+        .unwrap();
+        let options = Options {
+            max_model_width: 8,
+            ..Options::default()
+        };
+
+        let plain = strip_ansi(&build_statusline(&input, &options));
+        let _ = std::fs::remove_dir(&dir);
+
+        assert!(plain.starts_with(" \u{f4b8} A Very … \u{e0b0}"));
+    }
+
+    #[test]
+    fn build_statusline_without_color_has_no_escape_sequences() {
+        let dir = std::env::temp_dir().join("claude_statusline_build_no_color");
+        std::fs::create_dir_all(&dir).unwrap();
+        let input: StatusInput = serde_json::from_value(serde_json::json!({
+            "cwd": dir.display().to_string(),
+        }))
+        .unwrap();
+        let options = Options {
+            color: ColorMode::Never,
+            ..Options::default()
+        };
+
+        let rendered = build_statusline(&input, &options);
+        let _ = std::fs::remove_dir(&dir);
+
+        assert_eq!(
+            rendered,
+            " \u{f4b8} unknown \u{e0b0} \u{f07c} claude_statusline_build_no_color \u{e0b0}"
+        );
     }
 
     #[test]
-    fn prettify_gpt5_mini_is_not_reasoning() {
-        assert_eq!(prettify_model_name("gpt-5-mini"), "GPT-5 mini");
+    fn git_ref_for_dir_cached_reuses_recent_lookups() {
+        let repo = std::env::temp_dir().join("claude_statusline_git_ref_cache");
+        let _ = std::fs::remove_dir_all(&repo);
+        std::fs::create_dir_all(&repo).unwrap();
+        let git = |args: &[&str]| {
+            let status = Command::new("git")
+                .arg("-C")
+                .arg(&repo)
+                .args(args)
+                .status()
+                .unwrap();
+            assert!(status.success());
+        };
+        git(&["init", "--quiet"]);
+        git(&["symbolic-ref", "HEAD", "refs/heads/first"]);
+        let dir = repo.display().to_string();
+        let ttl = Duration::from_mins(1);
+
+        let timeout = Duration::from_secs(10);
+
+        assert_eq!(
+            git_ref_for_dir_cached(&dir, ttl, timeout).as_deref(),
+            Some("first")
+        );
+        git(&["symbolic-ref", "HEAD", "refs/heads/second"]);
+        let cached = git_ref_for_dir_cached(&dir, ttl, timeout);
+        let uncached = git_ref_for_dir_cached(&dir, Duration::ZERO, timeout);
+        let _ = std::fs::remove_dir_all(&repo);
+
+        assert_eq!(cached.as_deref(), Some("first"));
+        assert_eq!(uncached.as_deref(), Some("second"));
     }
 
     #[test]
-    fn prettify_gpt5_nano_keeps_lowercase_suffix() {
-        assert_eq!(prettify_model_name("gpt-5-nano"), "GPT-5 nano");
+    fn parse_cli_reads_git_cache_ttl() {
+        let Ok(ParseCliResult::Run(options)) = parse_cli(std::iter::empty()) else {
+            panic!("expected options");
+        };
+        assert_eq!(options.git_cache_ttl, DEFAULT_GIT_CACHE_TTL);
+
+        let result = parse_cli(["--git-cache-ttl", "0"].into_iter().map(String::from));
+        let Ok(ParseCliResult::Run(options)) = result else {
+            panic!("expected options");
+        };
+        assert_eq!(options.git_cache_ttl, Duration::ZERO);
+        assert!(parse_cli(["--git-cache-ttl", "soon"].into_iter().map(String::from)).is_err());
     }
 
+    #[cfg(unix)]
     #[test]
-    fn prettify_unknown_passthrough() {
-        assert_eq!(prettify_model_name("unknown"), "unknown");
-        assert_eq!(
-            prettify_model_name("some-custom-model"),
-            "some-custom-model"
+    fn command_output_with_timeout_kills_slow_commands() {
+        let started = Instant::now();
+        let mut slow = Command::new("sleep");
+        slow.arg("5");
+        assert!(
+            command_output_with_timeout(&mut slow, Duration::from_millis(50))
+                .unwrap()
+                .is_none()
+        );
+        assert!(started.elapsed() < Duration::from_secs(5));
+
+        let mut fast = Command::new("echo");
+        fast.arg("main");
+        let output = command_output_with_timeout(&mut fast, Duration::from_secs(5))
+            .unwrap()
+            .unwrap();
+        assert_eq!(String::from_utf8(output.stdout).unwrap(), "main\n");
+
+        let mut untimed = Command::new("echo");
+        untimed.arg("main");
+        assert!(
+            command_output_with_timeout(&mut untimed, Duration::ZERO)
+                .unwrap()
+                .is_some()
         );
     }
 
+    #[test]
+    fn parse_cli_reads_git_timeout() {
+        let Ok(ParseCliResult::Run(options)) = parse_cli(std::iter::empty()) else {
+            panic!("expected options");
+        };
+        assert_eq!(options.git_timeout, DEFAULT_GIT_TIMEOUT);
+
+        let result = parse_cli(["--git-timeout-ms", "1500"].into_iter().map(String::from));
+        let Ok(ParseCliResult::Run(options)) = result else {
+            panic!("expected options");
+        };
+        assert_eq!(options.git_timeout, Duration::from_millis(1500));
+        assert!(parse_cli(["--git-timeout-ms", "-1"].into_iter().map(String::from)).is_err());
+    }
+
     fn make_input_with_cost(cost: Option<f64>) -> StatusInput {
         StatusInput {
-            _event_name: None,
+            event: HookEvent::Unknown,
             cwd: None,
             model: None,
             workspace: None,
@@ -907,24 +2045,50 @@ mod tests {
                 total_cost_usd: Some(c),
             }),
             context_window: None,
+            tool_calls_pending: None,
+            session_id: None,
         }
     }
 
     #[test]
     fn format_cost_displays_usd() {
         let input = make_input_with_cost(Some(1.234));
-        assert_eq!(format_cost(&input).unwrap(), "$ 1.23");
+        assert_eq!(
+            format_cost(&input, &CostFormat::default()).unwrap(),
+            "$ 1.23"
+        );
     }
 
     #[test]
     fn format_cost_zero_returns_none() {
         let input = make_input_with_cost(Some(0.0));
-        assert!(format_cost(&input).is_none());
+        assert!(format_cost(&input, &CostFormat::default()).is_none());
     }
 
     #[test]
     fn format_cost_none_returns_none() {
         let input = make_input_with_cost(None);
-        assert!(format_cost(&input).is_none());
+        assert!(format_cost(&input, &CostFormat::default()).is_none());
+    }
+
+    #[test]
+    fn format_cost_uses_configured_currency_and_decimals() {
+        let input = make_input_with_cost(Some(1.234_56));
+        let fmt = CostFormat {
+            symbol: "EUR".to_string(),
+            decimals: 4,
+        };
+        assert_eq!(format_cost(&input, &fmt).unwrap(), "EUR 1.2346");
+
+        let result = parse_cli(
+            ["--cost-currency", "€", "--cost-decimals", "0"]
+                .into_iter()
+                .map(String::from),
+        );
+        let Ok(ParseCliResult::Run(options)) = result else {
+            panic!("expected options");
+        };
+        assert_eq!(format_cost(&input, &options.cost_format).unwrap(), "€ 1");
+        assert!(parse_cli(std::iter::once("--cost-currency".to_string())).is_err());
     }
 }
@@ -0,0 +1,771 @@
+//! Model name prettification shared by statusline renderers.
+
+use std::{
+    collections::HashMap,
+    fmt::Write as _,
+    sync::{Mutex, OnceLock, PoisonError},
+};
+
+/// Display names already computed by [`prettify_model_name`], keyed by raw model ID.
+/// A statusline sees only a handful of models, so the map is simply cleared if it
+/// ever reaches [`MODEL_NAME_CACHE_LIMIT`] entries.
+static MODEL_NAME_CACHE: OnceLock<Mutex<HashMap<String, String>>> = OnceLock::new();
+
+const MODEL_NAME_CACHE_LIMIT: usize = 256;
+
+/// Delimiter style of a trailing model qualifier.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum QualifierKind {
+    Bracket,
+    Paren,
+}
+
+/// A trailing `[...]` or `(...)` qualifier extracted from a model ID.
+#[derive(Debug)]
+pub struct ModelQualifier {
+    pub display: String,
+    pub kind: QualifierKind,
+}
+
+/// Transform a raw model ID into a human-friendly display name.
+///
+/// Examples:
+///   `ag/claude-opus-4-6-thinking`      -> `Opus 4.6 🧠`
+///   `ag/claude-opus-4-6-thinking[1m]`  -> `Opus 4.6 [1M] 🧠`
+///   `ag/claude-sonnet-4-5-thinking`    -> `Sonnet 4.5 🧠`
+///   `ag/gemini-2.5-flash-lite[1m]`     -> `Gemini 2.5 Flash Lite [1M]`
+///   `ag/gemini-2.5-pro`                -> `Gemini 2.5 Pro 🧠`
+///   `claude-opus-4.5`                  -> `Opus 4.5`
+///   `claude-3-5-sonnet-20241022`       -> `Sonnet 3.5`
+///   `v/gpt-5.3-codex(xhigh)`          -> `GPT-5.3-Codex (xhigh) 🧠`
+///   `gpt-5.4(xhigh)[1m]`              -> `GPT-5.4 (xhigh) [1M] 🧠`
+///   `gpt-5.4(xhigh)[1m]+fast`         -> `GPT-5.4 (xhigh) [1M] 🧠⚡️`
+///   `gpt-4.1-2025-04-14`              -> `GPT-4.1`
+///   `o3-mini`                          -> `O3 Mini 🧠`
+///   `mistral-large-2411`               -> `Mistral Large`
+///   `meta-llama/Llama-3.1-70B`         -> `LLaMA 3.1 70B`
+///   `llama3.2:3b-instruct-q4_K_M`      -> `LLaMA 3.2 3B Instruct (q4_K_M)`
+///   `qwen2.5-coder-32b`                -> `Qwen 2.5 Coder 32B`
+///   `unknown-model`                    -> `unknown-model`
+///
+/// Results are memoized per process, since statuslines prettify the same model ID
+/// on every update.
+///
+/// # Panics
+///
+/// Does not panic in practice: formatting into a `String` cannot fail.
+#[must_use]
+pub fn prettify_model_name(raw: &str) -> String {
+    let cache = MODEL_NAME_CACHE.get_or_init(Mutex::default);
+    if let Some(pretty) = cache
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+        .get(raw)
+    {
+        return pretty.clone();
+    }
+
+    let pretty = prettify_model_name_uncached(raw);
+    let mut cache = cache.lock().unwrap_or_else(PoisonError::into_inner);
+    if cache.len() >= MODEL_NAME_CACHE_LIMIT {
+        cache.clear();
+    }
+    cache.insert(raw.to_string(), pretty.clone());
+    pretty
+}
+
+fn prettify_model_name_uncached(raw: &str) -> String {
+    let (body, mut qualifiers, is_fast) = extract_qualifiers(raw);
+
+    // Strip routing prefixes: "ag/", "v/"
+    let body = body
+        .strip_prefix("ag/")
+        .or_else(|| body.strip_prefix("v/"))
+        .unwrap_or(body);
+
+    // Ollama names carry a `:tag` with the size, variant, and quantization
+    let ollama_body;
+    let body = if let Some((name, tag)) = body.split_once(':')
+        && !name.is_empty()
+    {
+        let (name_parts, quantizations) = split_ollama_tag(tag);
+        qualifiers.extend(
+            quantizations
+                .into_iter()
+                .map(|quantization| ModelQualifier {
+                    display: quantization.to_string(),
+                    kind: QualifierKind::Paren,
+                }),
+        );
+        ollama_body = std::iter::once(name)
+            .chain(name_parts)
+            .collect::<Vec<_>>()
+            .join("-");
+        ollama_body.as_str()
+    } else {
+        body
+    };
+
+    let is_thinking = body.ends_with("-thinking");
+    let body = body.strip_suffix("-thinking").unwrap_or(body);
+
+    // Open-weight models are often namespaced by organization: "meta-llama/..."
+    let model_id = body.rsplit_once('/').map_or(body, |(_, name)| name);
+
+    let (pretty, is_reasoning) = if let Some(rest) = body.strip_prefix("claude-") {
+        (prettify_claude(rest), is_thinking)
+    } else if let Some(rest) = body.strip_prefix("gemini-") {
+        let parts: Vec<&str> = rest.split('-').collect();
+        let is_pro = parts.iter().any(|p| p.eq_ignore_ascii_case("pro"));
+        (prettify_generic("Gemini", rest), is_pro)
+    } else if let Some(rest) = body.strip_prefix("gpt-") {
+        let reasoning = is_gpt_reasoning(rest, &qualifiers);
+        (prettify_gpt(rest), reasoning)
+    } else if is_openai_o_series(body) {
+        // The o-series are reasoning models throughout
+        (prettify_openai_o_series(body), true)
+    } else if let Some(rest) = strip_prefix_ignore_ascii_case(model_id, "mistral-") {
+        (prettify_mistral(rest), false)
+    } else if let Some(rest) = strip_family_prefix(model_id, "llama") {
+        (prettify_generic("LLaMA", rest), false)
+    } else if let Some(rest) = strip_family_prefix(model_id, "qwen") {
+        (prettify_generic("Qwen", rest), false)
+    } else {
+        return raw.to_string();
+    };
+
+    let mut result = pretty;
+    for qualifier in &qualifiers {
+        match qualifier.kind {
+            QualifierKind::Bracket => write!(result, " [{}]", qualifier.display),
+            QualifierKind::Paren => write!(result, " ({})", qualifier.display),
+        }
+        .expect("writing into String must succeed");
+    }
+
+    if is_reasoning {
+        result.push_str(" 🧠");
+    }
+
+    if is_fast {
+        if is_reasoning {
+            result.push_str("⚡️");
+        } else {
+            result.push_str(" ⚡️");
+        }
+    }
+
+    result
+}
+
+/// Determine if a GPT model qualifies as a reasoning model.
+///
+/// Rules:
+/// - GPT Codex variants with medium+ reasoning qualifier -> true
+/// - GPT mini / nano variants without codex -> false
+/// - Other GPT 5+ variants -> true
+#[must_use]
+pub fn is_gpt_reasoning(rest: &str, qualifiers: &[ModelQualifier]) -> bool {
+    let parts: Vec<&str> = rest.split('-').collect();
+    let is_codex = parts.iter().any(|p| p.eq_ignore_ascii_case("codex"));
+    if is_codex {
+        return qualifiers.iter().any(|qualifier| {
+            qualifier.kind == QualifierKind::Paren
+                && matches!(
+                    qualifier.display.to_ascii_lowercase().as_str(),
+                    "medium" | "high" | "xhigh"
+                )
+        });
+    }
+
+    if parts
+        .iter()
+        .any(|p| p.eq_ignore_ascii_case("mini") || p.eq_ignore_ascii_case("nano"))
+    {
+        return false;
+    }
+
+    // GPT 5+ (excluding mini / nano)
+    parts
+        .first()
+        .and_then(|v| v.split('.').next())
+        .and_then(|major| major.parse::<u32>().ok())
+        .is_some_and(|major| major >= 5)
+}
+
+/// Split an Ollama tag such as `3b-instruct-q4_0` into the parts that belong to the
+/// model name (`3b`, `instruct`) and quantization levels (`q4_0`).
+/// The `latest` alias is dropped.
+#[must_use]
+pub fn split_ollama_tag(tag: &str) -> (Vec<&str>, Vec<&str>) {
+    tag.split('-')
+        .filter(|part| !part.is_empty() && !part.eq_ignore_ascii_case("latest"))
+        .partition(|part| !is_quantization(part))
+}
+
+/// Whether `part` names a quantization level like `q4_0`, `q4_K_M`, `fp16`, or `bf16`.
+fn is_quantization(part: &str) -> bool {
+    let lower = part.to_ascii_lowercase();
+    let bits = lower
+        .strip_prefix('q')
+        .or_else(|| lower.strip_prefix("fp"))
+        .or_else(|| lower.strip_prefix("bf"))
+        .or_else(|| lower.strip_prefix("iq"));
+    bits.is_some_and(|bits| bits.starts_with(|c: char| c.is_ascii_digit()))
+}
+
+/// Extract trailing qualifiers from a model ID.
+/// Handles stacked `(...)`, `[...]`, and `+fast` suffixes in any order,
+/// preserving the original order for displayed qualifiers.
+#[must_use]
+pub fn extract_qualifiers(raw: &str) -> (&str, Vec<ModelQualifier>, bool) {
+    let mut body = raw;
+    let mut qualifiers = Vec::new();
+    let mut is_fast = false;
+
+    loop {
+        if let Some(stripped) = body.strip_suffix("+fast") {
+            is_fast = true;
+            body = stripped;
+            continue;
+        }
+
+        if let Some(start) = body.rfind('[')
+            && body.ends_with(']')
+        {
+            let inner = &body[start + 1..body.len() - 1];
+            qualifiers.push(ModelQualifier {
+                display: inner.to_uppercase(),
+                kind: QualifierKind::Bracket,
+            });
+            body = &body[..start];
+            continue;
+        }
+
+        if let Some(start) = body.rfind('(')
+            && body.ends_with(')')
+        {
+            let inner = &body[start + 1..body.len() - 1];
+            qualifiers.push(ModelQualifier {
+                display: inner.to_string(),
+                kind: QualifierKind::Paren,
+            });
+            body = &body[..start];
+            continue;
+        }
+
+        qualifiers.reverse();
+        return (body, qualifiers, is_fast);
+    }
+}
+
+/// Prettify a Claude model name after "claude-" prefix is stripped.
+/// e.g. "opus-4-6" -> "Opus 4.6", "sonnet-4-5" -> "Sonnet 4.5"
+///
+/// Claude 3 IDs lead with the version instead ("3-5-sonnet" -> "Sonnet 3.5"), and
+/// `YYYYMMDD` snapshot dates or a `latest` alias are dropped.
+#[must_use]
+pub fn prettify_claude(rest: &str) -> String {
+    let mut parts: Vec<&str> = rest.split('-').collect();
+    if parts.len() > 1
+        && parts
+            .last()
+            .is_some_and(|last| *last == "latest" || is_compact_date(last))
+    {
+        parts.pop();
+    }
+
+    let Some(tier_index) = parts
+        .iter()
+        .position(|part| !part.chars().all(|c| c.is_ascii_digit() || c == '.'))
+    else {
+        return title_case(rest);
+    };
+    let tier = title_case(parts.remove(tier_index));
+    if parts.is_empty() {
+        return tier;
+    }
+
+    let version = dotted_version(&parts.join("-"));
+    format!("{tier} {version}")
+}
+
+/// Whether `part` is a `YYYYMMDD` snapshot date such as `20241022`.
+fn is_compact_date(part: &str) -> bool {
+    part.len() == 8 && part.starts_with("20") && part.chars().all(|c| c.is_ascii_digit())
+}
+
+/// Prettify a non-Claude, non-GPT model after the prefix is stripped.
+/// e.g. brand="Gemini", rest="2.5-flash-lite" -> "Gemini 2.5 Flash Lite"
+#[must_use]
+pub fn prettify_generic(brand: &str, rest: &str) -> String {
+    let (version, name_parts) = split_version_and_name(rest);
+    if name_parts.is_empty() {
+        format!("{brand} {version}")
+    } else {
+        let name = name_parts
+            .iter()
+            .map(|p| format_name_part(p))
+            .collect::<Vec<_>>()
+            .join(" ");
+        format!("{brand} {version} {name}")
+    }
+}
+
+/// Prettify a Mistral model after the `mistral-` prefix is stripped.
+///
+/// Mistral IDs lead with the family name and end with a `YYMM` release tag
+/// or `latest`, both of which are dropped.
+/// e.g. "large-2411" -> "Mistral Large", "7b-instruct" -> "Mistral 7B Instruct"
+#[must_use]
+pub fn prettify_mistral(rest: &str) -> String {
+    let name = rest
+        .split('-')
+        .filter(|part| {
+            let is_release_tag = part.eq_ignore_ascii_case("latest")
+                || (part.len() == 4 && part.chars().all(|c| c.is_ascii_digit()));
+            !is_release_tag
+        })
+        .map(format_name_part)
+        .collect::<Vec<_>>()
+        .join(" ");
+    if name.is_empty() {
+        "Mistral".to_string()
+    } else {
+        format!("Mistral {name}")
+    }
+}
+
+/// Format a single name segment, keeping parameter sizes like `70b` or
+/// `8x7b` in their conventional upper-case form (`70B`, `8x7B`).
+fn format_name_part(part: &str) -> String {
+    let is_param_size = part.len() > 1
+        && part.starts_with(|c: char| c.is_ascii_digit())
+        && part.ends_with(['b', 'B', 'm', 'M'])
+        && part[..part.len() - 1]
+            .chars()
+            .all(|c| c.is_ascii_digit() || c == '.' || c == 'x');
+    if is_param_size {
+        part.to_ascii_uppercase().replace('X', "x")
+    } else {
+        title_case(part)
+    }
+}
+
+fn strip_prefix_ignore_ascii_case<'a>(value: &'a str, prefix: &str) -> Option<&'a str> {
+    value
+        .get(..prefix.len())
+        .filter(|head| head.eq_ignore_ascii_case(prefix))
+        .map(|_| &value[prefix.len()..])
+}
+
+/// Strip a model family name such as `llama` when it is followed by a version or a
+/// separator (`llama3.2`, `llama-3.3`, `llama:8b`), so `llamafile-0.8` is not matched.
+fn strip_family_prefix<'a>(model_id: &'a str, family: &str) -> Option<&'a str> {
+    let rest = strip_prefix_ignore_ascii_case(model_id, family)?;
+    match rest.chars().next() {
+        None => Some(rest),
+        Some('-' | ':') => Some(&rest[1..]),
+        Some(c) if c.is_ascii_digit() => Some(rest),
+        Some(_) => None,
+    }
+}
+
+/// Prettify a GPT model after the `gpt-` prefix is stripped.
+///
+/// Rules:
+/// - Always keep the `GPT-<version>` prefix.
+/// - `codex` families stay hyphenated and Title Cased.
+/// - `mini` / `nano` families stay lowercase and use a space separator.
+#[must_use]
+pub fn prettify_gpt(rest: &str) -> String {
+    let (version, name_parts) = split_version_and_name(rest);
+    let mut result = format!("GPT-{version}");
+    if name_parts.is_empty() {
+        return result;
+    }
+
+    if name_parts[0].eq_ignore_ascii_case("codex") {
+        result.push('-');
+        result.push_str(
+            &name_parts
+                .iter()
+                .map(|part| title_case(part))
+                .collect::<Vec<_>>()
+                .join("-"),
+        );
+        return result;
+    }
+
+    result.push(' ');
+    result.push_str(
+        &name_parts
+            .iter()
+            .map(|part| match part.to_ascii_lowercase().as_str() {
+                "mini" | "nano" => part.to_ascii_lowercase(),
+                _ => title_case(part),
+            })
+            .collect::<Vec<_>>()
+            .join(" "),
+    );
+    result
+}
+
+/// Whether `body` is an o-series reasoning model such as `o1` or `o4-mini`:
+/// an `o` followed only by digits, then optionally `-<name>`.
+#[must_use]
+pub fn is_openai_o_series(body: &str) -> bool {
+    let family = body.split('-').next().unwrap_or(body);
+    family
+        .strip_prefix('o')
+        .is_some_and(|number| !number.is_empty() && number.chars().all(|c| c.is_ascii_digit()))
+}
+
+/// Prettify an o-series model, dropping a trailing `YYYY-MM-DD` snapshot date.
+/// e.g. "o1" -> "O1", "o3-mini-2025-01-31" -> "O3 Mini"
+#[must_use]
+pub fn prettify_openai_o_series(body: &str) -> String {
+    let mut parts: Vec<&str> = body.split('-').collect();
+    if let [.., year, month, day] = parts[..]
+        && parts.len() > 3
+        && year.len() == 4
+        && month.len() == 2
+        && day.len() == 2
+        && [year, month, day]
+            .iter()
+            .all(|part| part.chars().all(|c| c.is_ascii_digit()))
+    {
+        parts.truncate(parts.len() - 3);
+    }
+    parts
+        .iter()
+        .map(|part| format_name_part(part))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Split version and name segments from a model suffix.
+///
+/// - `5.3-codex` -> `("5.3", ["codex"])`
+/// - `2.5-flash-lite` -> `("2.5", ["flash", "lite"])`
+/// - `4.1-2025-04-14` -> `("4.1", [])` (date suffixes are dropped)
+#[must_use]
+pub fn split_version_and_name(rest: &str) -> (String, Vec<&str>) {
+    let parts: Vec<&str> = rest.split('-').collect();
+    if parts.is_empty() {
+        return (rest.to_string(), vec![]);
+    }
+
+    let version = parts[0].to_string();
+    let remaining = &parts[1..];
+
+    // Drop date suffixes (YYYY-MM-DD pattern)
+    if remaining.len() >= 3
+        && remaining[0].len() == 4
+        && remaining[0].chars().all(|c| c.is_ascii_digit())
+        && remaining[1].len() == 2
+        && remaining[2].len() == 2
+    {
+        return (version, vec![]);
+    }
+
+    (version, remaining.to_vec())
+}
+
+/// Convert a hyphenated version like "4-6" to "4.6".
+/// If it already contains dots (e.g. "4.5"), return as-is.
+#[must_use]
+pub fn dotted_version(version: &str) -> String {
+    if version.contains('.') {
+        return version.to_string();
+    }
+    version.replace('-', ".")
+}
+
+#[must_use]
+pub fn title_case(word: &str) -> String {
+    let mut chars = word.chars();
+    chars.next().map_or_else(String::new, |first| {
+        let upper: String = first.to_uppercase().collect();
+        format!("{upper}{}", chars.as_str())
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn prettify_claude_opus() {
+        assert_eq!(
+            prettify_model_name("ag/claude-opus-4-6-thinking"),
+            "Opus 4.6 🧠"
+        );
+    }
+
+    #[test]
+    fn prettify_claude_opus_with_context() {
+        assert_eq!(
+            prettify_model_name("ag/claude-opus-4-6-thinking[1m]"),
+            "Opus 4.6 [1M] 🧠"
+        );
+    }
+
+    #[test]
+    fn prettify_claude_sonnet() {
+        assert_eq!(
+            prettify_model_name("ag/claude-sonnet-4-5-thinking"),
+            "Sonnet 4.5 🧠"
+        );
+    }
+
+    #[test]
+    fn prettify_claude_without_thinking() {
+        assert_eq!(prettify_model_name("claude-opus-4.5"), "Opus 4.5");
+        assert_eq!(prettify_model_name("claude-sonnet-4.5"), "Sonnet 4.5");
+    }
+
+    #[test]
+    fn prettify_claude_3_family_puts_tier_first() {
+        assert_eq!(
+            prettify_model_name("claude-3-5-sonnet-20241022"),
+            "Sonnet 3.5"
+        );
+        assert_eq!(
+            prettify_model_name("claude-3-7-sonnet-20250219"),
+            "Sonnet 3.7"
+        );
+        assert_eq!(prettify_model_name("claude-3-5-haiku-latest"), "Haiku 3.5");
+        assert_eq!(prettify_model_name("claude-3-opus-20240229"), "Opus 3");
+        assert_eq!(prettify_model_name("claude-3-haiku-20240307"), "Haiku 3");
+    }
+
+    #[test]
+    fn prettify_claude_drops_snapshot_dates() {
+        assert_eq!(prettify_model_name("claude-sonnet-4-20250514"), "Sonnet 4");
+        assert_eq!(prettify_model_name("claude-opus-4-1-20250805"), "Opus 4.1");
+        assert_eq!(
+            prettify_model_name("claude-sonnet-4-5-20250929[1m]"),
+            "Sonnet 4.5 [1M]"
+        );
+    }
+
+    #[test]
+    fn prettify_ollama_drops_latest_tag() {
+        assert_eq!(prettify_model_name("llama3.2:latest"), "LLaMA 3.2");
+        assert_eq!(prettify_model_name("llama3.1:70b"), "LLaMA 3.1 70B");
+        assert_eq!(prettify_model_name("qwen2.5-coder:7b"), "Qwen 2.5 Coder 7B");
+    }
+
+    #[test]
+    fn prettify_ollama_quantization_is_a_qualifier() {
+        assert_eq!(
+            prettify_model_name("llama3.2:3b-instruct-q4_K_M"),
+            "LLaMA 3.2 3B Instruct (q4_K_M)"
+        );
+        assert_eq!(prettify_model_name("llama3:8b-q8_0"), "LLaMA 3 8B (q8_0)");
+        assert_eq!(
+            prettify_model_name("qwen2.5:14b-instruct-fp16"),
+            "Qwen 2.5 14B Instruct (fp16)"
+        );
+    }
+
+    #[test]
+    fn prettify_ollama_unknown_family_is_unchanged() {
+        assert_eq!(prettify_model_name("phi3:mini"), "phi3:mini");
+    }
+
+    #[test]
+    fn split_ollama_tag_separates_quantization() {
+        assert_eq!(
+            split_ollama_tag("3b-instruct-q4_0"),
+            (vec!["3b", "instruct"], vec!["q4_0"])
+        );
+        assert_eq!(split_ollama_tag("latest"), (vec![], vec![]));
+        assert_eq!(split_ollama_tag("70b"), (vec!["70b"], vec![]));
+    }
+
+    #[test]
+    fn prettify_gemini_pro_is_reasoning() {
+        assert_eq!(
+            prettify_model_name("ag/gemini-2.5-pro"),
+            "Gemini 2.5 Pro 🧠"
+        );
+    }
+
+    #[test]
+    fn prettify_gemini_flash_is_not_reasoning() {
+        assert_eq!(
+            prettify_model_name("ag/gemini-2.5-flash-lite[1m]"),
+            "Gemini 2.5 Flash Lite [1M]"
+        );
+    }
+
+    #[test]
+    fn prettify_gpt_codex_with_reasoning() {
+        assert_eq!(
+            prettify_model_name("v/gpt-5.3-codex(xhigh)"),
+            "GPT-5.3-Codex (xhigh) 🧠"
+        );
+        assert_eq!(
+            prettify_model_name("gpt-5.3-codex(high)"),
+            "GPT-5.3-Codex (high) 🧠"
+        );
+        assert_eq!(
+            prettify_model_name("gpt-5.3-codex(medium)"),
+            "GPT-5.3-Codex (medium) 🧠"
+        );
+        assert_eq!(
+            prettify_model_name("gpt-5.3-codex(xhigh)[1m]"),
+            "GPT-5.3-Codex (xhigh) [1M] 🧠"
+        );
+        assert_eq!(
+            prettify_model_name("gpt-5.1-codex-max"),
+            "GPT-5.1-Codex-Max"
+        );
+    }
+
+    #[test]
+    fn prettify_gpt_codex_low_reasoning_is_not_thinking() {
+        assert_eq!(
+            prettify_model_name("gpt-5.3-codex(low)"),
+            "GPT-5.3-Codex (low)"
+        );
+    }
+
+    #[test]
+    fn prettify_gpt_codex_mini_with_reasoning_is_thinking() {
+        assert_eq!(
+            prettify_model_name("gpt-5.3-codex-mini(high)"),
+            "GPT-5.3-Codex-Mini (high) 🧠"
+        );
+    }
+
+    #[test]
+    fn prettify_gpt5_is_reasoning() {
+        assert_eq!(prettify_model_name("gpt-5"), "GPT-5 🧠");
+        assert_eq!(prettify_model_name("gpt-5.1"), "GPT-5.1 🧠");
+        assert_eq!(
+            prettify_model_name("gpt-5.4(xhigh)[1m]"),
+            "GPT-5.4 (xhigh) [1M] 🧠"
+        );
+    }
+
+    #[test]
+    fn prettify_gpt5_fast_adds_lightning() {
+        assert_eq!(
+            prettify_model_name("gpt-5.4(xhigh)[1m]+fast"),
+            "GPT-5.4 (xhigh) [1M] 🧠⚡️"
+        );
+        assert_eq!(
+            prettify_model_name("gpt-5.4(xhigh)+fast[1m]"),
+            "GPT-5.4 (xhigh) [1M] 🧠⚡️"
+        );
+        assert_eq!(
+            prettify_model_name("gpt-5.4+fast(xhigh)[1m]"),
+            "GPT-5.4 (xhigh) [1M] 🧠⚡️"
+        );
+        assert_eq!(prettify_model_name("gpt-4.1+fast"), "GPT-4.1 ⚡️");
+    }
+
+    #[test]
+    fn prettify_gpt4_is_not_reasoning() {
+        assert_eq!(prettify_model_name("gpt-4.1-2025-04-14"), "GPT-4.1");
+    }
+
+    #[test]
+    fn prettify_openai_o_series_is_reasoning() {
+        assert_eq!(prettify_model_name("o1"), "O1 🧠");
+        assert_eq!(prettify_model_name("o1-mini"), "O1 Mini 🧠");
+        assert_eq!(prettify_model_name("o1-pro"), "O1 Pro 🧠");
+        assert_eq!(prettify_model_name("o1-2024-12-17"), "O1 🧠");
+        assert_eq!(prettify_model_name("o3"), "O3 🧠");
+        assert_eq!(prettify_model_name("o3-mini"), "O3 Mini 🧠");
+        assert_eq!(prettify_model_name("o3-mini-2025-01-31"), "O3 Mini 🧠");
+        assert_eq!(prettify_model_name("o3-pro"), "O3 Pro 🧠");
+        assert_eq!(prettify_model_name("o4-mini"), "O4 Mini 🧠");
+        assert_eq!(prettify_model_name("v/o3(high)"), "O3 (high) 🧠");
+        assert_eq!(prettify_model_name("o4-mini+fast"), "O4 Mini 🧠⚡️");
+    }
+
+    #[test]
+    fn prettify_openai_o_series_requires_digits() {
+        assert_eq!(prettify_model_name("omni-model"), "omni-model");
+        assert_eq!(prettify_model_name("o"), "o");
+        assert_eq!(prettify_model_name("o3x-mini"), "o3x-mini");
+    }
+
+    #[test]
+    fn prettify_gpt5_mini_is_not_reasoning() {
+        assert_eq!(prettify_model_name("gpt-5-mini"), "GPT-5 mini");
+    }
+
+    #[test]
+    fn prettify_gpt5_nano_keeps_lowercase_suffix() {
+        assert_eq!(prettify_model_name("gpt-5-nano"), "GPT-5 nano");
+    }
+
+    #[test]
+    fn prettify_mistral_drops_release_tag() {
+        assert_eq!(prettify_model_name("mistral-large-2411"), "Mistral Large");
+        assert_eq!(prettify_model_name("mistral-small-latest"), "Mistral Small");
+        assert_eq!(
+            prettify_model_name("mistral-7b-instruct"),
+            "Mistral 7B Instruct"
+        );
+    }
+
+    #[test]
+    fn prettify_llama_strips_org_prefix() {
+        assert_eq!(
+            prettify_model_name("meta-llama/Llama-3.1-70B"),
+            "LLaMA 3.1 70B"
+        );
+        assert_eq!(
+            prettify_model_name("llama-3.3-70b-instruct"),
+            "LLaMA 3.3 70B Instruct"
+        );
+    }
+
+    #[test]
+    fn prettify_llama_requires_version_or_separator() {
+        assert_eq!(prettify_model_name("llamafile-0.8"), "llamafile-0.8");
+        assert_eq!(prettify_model_name("qwenlike-7b"), "qwenlike-7b");
+        assert_eq!(prettify_model_name("llama3:8b"), "LLaMA 3 8B");
+    }
+
+    #[test]
+    fn prettify_qwen_with_and_without_hyphen() {
+        assert_eq!(
+            prettify_model_name("qwen2.5-coder-32b"),
+            "Qwen 2.5 Coder 32B"
+        );
+        assert_eq!(prettify_model_name("qwen-3-8x7b"), "Qwen 3 8x7B");
+    }
+
+    #[test]
+    fn prettify_model_name_is_memoized() {
+        let raw = "claude-haiku-4-5-20251001";
+        assert_eq!(prettify_model_name(raw), prettify_model_name_uncached(raw));
+        assert_eq!(
+            MODEL_NAME_CACHE
+                .get()
+                .unwrap()
+                .lock()
+                .unwrap()
+                .get(raw)
+                .map(String::as_str),
+            Some("Haiku 4.5")
+        );
+        assert_eq!(prettify_model_name(raw), "Haiku 4.5");
+    }
+
+    #[test]
+    fn prettify_unknown_passthrough() {
+        assert_eq!(prettify_model_name("unknown"), "unknown");
+        assert_eq!(
+            prettify_model_name("some-custom-model"),
+            "some-custom-model"
+        );
+    }
+}
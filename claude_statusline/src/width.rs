@@ -0,0 +1,175 @@
+//! Display-width aware truncation shared by statusline renderers.
+
+use unicode_width::UnicodeWidthChar;
+
+const ELLIPSIS: char = '…';
+
+/// Nerd Font private-use ranges whose glyphs render two columns wide, although
+/// Unicode gives them a width of 1.
+const NERD_FONT_WIDE_RANGES: &[(char, char)] = &[
+    // Pomicons
+    ('\u{e000}', '\u{e00a}'),
+    // Powerline symbols and Powerline Extra (arrows, flames, pixelated blocks)
+    ('\u{e0a0}', '\u{e0d7}'),
+    // Weather icons
+    ('\u{e300}', '\u{e3e3}'),
+    // Seti-UI and custom icons
+    ('\u{e5fa}', '\u{e6b7}'),
+    // Devicons
+    ('\u{e700}', '\u{e8ef}'),
+    // Codicons
+    ('\u{ea60}', '\u{ec1e}'),
+    // Font Awesome Extension and Font Awesome
+    ('\u{ed00}', '\u{f2ff}'),
+    // Font Logos
+    ('\u{f300}', '\u{f381}'),
+    // Octicons
+    ('\u{f400}', '\u{f533}'),
+    // Material Design
+    ('\u{f0001}', '\u{f1af0}'),
+];
+
+/// Returns the number of terminal columns `text` occupies.
+///
+/// Nerd Font glyphs are counted as two columns; see [`nerd_font_width`].
+#[must_use]
+pub fn visible_width(text: &str) -> usize {
+    text.chars().map(nerd_font_width).sum()
+}
+
+/// Returns the number of terminal columns `ch` occupies, counting Nerd Font glyphs
+/// (e.g. the Powerline arrow `\u{e0b0}` or the folder icon `\u{f07c}`) as two
+/// columns, as most terminals render them.
+#[must_use]
+pub fn nerd_font_width(ch: char) -> usize {
+    if NERD_FONT_WIDE_RANGES
+        .iter()
+        .any(|&(start, end)| (start..=end).contains(&ch))
+    {
+        return 2;
+    }
+    UnicodeWidthChar::width(ch).unwrap_or(0)
+}
+
+/// Truncate `value` to at most `max_width` columns, keeping the beginning.
+///
+/// `feature/very-long-branch-name` becomes `feature/very-lo…`.
+#[must_use]
+pub fn truncate_to_width(value: &str, max_width: usize) -> String {
+    let Some(budget) = truncation_budget(value, max_width) else {
+        return fit_without_truncation(value, max_width);
+    };
+
+    let mut result: String = take_within_width(value.chars(), budget).collect();
+    result.push(ELLIPSIS);
+    result
+}
+
+/// Truncate `value` to at most `max_width` columns, keeping the end.
+///
+/// Useful for paths and branch names, where the end is the most specific part:
+/// `/very/long/path/to/project/src/bin` becomes `…project/src/bin`.
+#[must_use]
+pub fn truncate_from_left_to_width(value: &str, max_width: usize) -> String {
+    let Some(budget) = truncation_budget(value, max_width) else {
+        return fit_without_truncation(value, max_width);
+    };
+
+    let mut kept: Vec<char> = take_within_width(value.chars().rev(), budget).collect();
+    kept.reverse();
+    std::iter::once(ELLIPSIS).chain(kept).collect()
+}
+
+/// Columns left for the kept text after the ellipsis, or `None` if `value` fits
+/// as-is or there is no room for anything but the ellipsis.
+fn truncation_budget(value: &str, max_width: usize) -> Option<usize> {
+    let ellipsis_width = nerd_font_width(ELLIPSIS);
+    if visible_width(value) <= max_width || max_width <= ellipsis_width {
+        return None;
+    }
+    Some(max_width - ellipsis_width)
+}
+
+/// Result for values that need no truncation budget: the value itself if it fits,
+/// an empty string for a zero width, or a lone ellipsis.
+fn fit_without_truncation(value: &str, max_width: usize) -> String {
+    if visible_width(value) <= max_width {
+        value.to_string()
+    } else if max_width == 0 {
+        String::new()
+    } else {
+        ELLIPSIS.to_string()
+    }
+}
+
+fn take_within_width(
+    chars: impl Iterator<Item = char>,
+    budget: usize,
+) -> impl Iterator<Item = char> {
+    let mut width = 0usize;
+    chars.take_while(move |&ch| {
+        width += nerd_font_width(ch);
+        width <= budget
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn truncate_preserves_width_limit() {
+        let original = "feature/very-long-branch-name-for-statusline";
+        let truncated = truncate_to_width(original, 16);
+        assert!(visible_width(&truncated) <= 16);
+        assert!(truncated.ends_with('…'));
+    }
+
+    #[test]
+    fn truncate_counts_emoji_as_double_width() {
+        // "Opus 4.6 " is 9 columns and the brain emoji takes 2 more.
+        assert_eq!(visible_width("Opus 4.6 🧠"), 11);
+        assert_eq!(truncate_to_width("Opus 4.6 🧠", 11), "Opus 4.6 🧠");
+        assert_eq!(truncate_to_width("Opus 4.6 🧠", 10), "Opus 4.6 …");
+    }
+
+    #[test]
+    fn nerd_font_glyphs_are_double_width() {
+        assert_eq!(nerd_font_width('\u{e0b0}'), 2);
+        assert_eq!(nerd_font_width('\u{f07c}'), 2);
+        assert_eq!(nerd_font_width('\u{f4b8}'), 2);
+        assert_eq!(nerd_font_width('\u{e5fb}'), 2);
+        assert_eq!(nerd_font_width('\u{e725}'), 2);
+        assert_eq!(nerd_font_width('󰆼'), 2);
+        assert_eq!(nerd_font_width('a'), 1);
+        assert_eq!(nerd_font_width('█'), 1);
+        assert_eq!(nerd_font_width('\u{e0ff}'), 1);
+    }
+
+    #[test]
+    fn visible_width_counts_statusline_segments() {
+        assert_eq!(visible_width("\u{f07c} src"), 6);
+        assert_eq!(visible_width("\u{e725} main"), 7);
+        assert_eq!(visible_width("󰆼 [█████░░░░░] 50.0%"), 21);
+        assert_eq!(visible_width("日本語"), 6);
+        assert_eq!(truncate_to_width("\u{f07c} project", 5), "\u{f07c} p…");
+    }
+
+    #[test]
+    fn truncate_from_left_keeps_the_end() {
+        assert_eq!(
+            truncate_from_left_to_width("/very/long/path/to/project/src/bin", 16),
+            "…project/src/bin"
+        );
+        assert_eq!(truncate_from_left_to_width("main", 16), "main");
+        assert_eq!(truncate_from_left_to_width("🧠 Opus 4.6", 10), "… Opus 4.6");
+    }
+
+    #[test]
+    fn truncate_handles_tiny_widths() {
+        assert_eq!(truncate_to_width("statusline", 0), "");
+        assert_eq!(truncate_to_width("statusline", 1), "…");
+        assert_eq!(truncate_from_left_to_width("statusline", 0), "");
+        assert_eq!(truncate_from_left_to_width("statusline", 1), "…");
+    }
+}